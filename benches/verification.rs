@@ -0,0 +1,107 @@
+// Verification Benchmarks
+// =======================
+// `cargo bench --bench verification`
+// `cargo bench --bench verification --features blst`
+//
+// `verify_signature/reference` is the previous verifier kept here for
+// comparison: B summed with one scalar multiplication per generator and two
+// full pairings. `verify_signature/fast` is `BbsPublicKey::verify`
+// (multi-scalar multiplication, one multi-Miller loop, one final
+// exponentiation). Messages are integer-encoded so the reference can map
+// them to scalars without the crate's internal hash. Group names carry the
+// curve backend so runs with and without `blst` can be compared.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use multipass::predicate::encode_integer_message;
+use multipass::{BbsPublicKey, BbsSecretKey, BbsSignature, G1Affine, G1Projective, G2Affine, G2Projective, Scalar};
+use std::sync::Arc;
+
+const MESSAGE_COUNTS: [u32; 4] = [10, 25, 50, 100];
+
+const BACKEND: &str = if cfg!(feature = "blst") { "blst" } else { "bls12_381" };
+
+struct Fixture {
+    public_key: Arc<BbsPublicKey>,
+    signature: Arc<BbsSignature>,
+    values: Vec<u64>,
+    messages: Vec<Vec<u8>>,
+}
+
+fn fixture(message_count: u32) -> Fixture {
+    let secret_key = BbsSecretKey::generate().unwrap();
+    let public_key = secret_key.public_key(message_count);
+    let values: Vec<u64> = (0..message_count as u64).map(|i| 1_000 + i).collect();
+    let messages: Vec<Vec<u8>> = values.iter().map(|&v| encode_integer_message(v)).collect();
+    let signature = secret_key.sign(public_key.clone(), messages.clone()).unwrap();
+    Fixture { public_key, signature, values, messages }
+}
+
+fn reference_verify(public_key: &BbsPublicKey, signature: &[u8], values: &[u64]) -> bool {
+    let a = G1Affine::from_compressed(signature[..48].try_into().unwrap()).unwrap();
+    let e = Scalar::from_bytes(signature[48..80].try_into().unwrap()).unwrap();
+    let s = Scalar::from_bytes(signature[80..112].try_into().unwrap()).unwrap();
+    let h = public_key.generators();
+
+    let mut b = G1Projective::generator() + h[0] * s;
+    for (hi, &v) in h[1..].iter().zip(values) {
+        b += hi * Scalar::from(v);
+    }
+    let g2 = G2Projective::generator();
+    let left = bls12_381::pairing(&a, &(public_key.w() + g2 * e).into());
+    let right = bls12_381::pairing(&b.into(), &G2Affine::generator());
+    left == right
+}
+
+fn verify_signature(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("verify_signature/{}", BACKEND));
+    for n in MESSAGE_COUNTS {
+        let f = fixture(n);
+        let signature_bytes = f.signature.to_bytes();
+        assert!(reference_verify(&f.public_key, &signature_bytes, &f.values));
+
+        group.bench_with_input(BenchmarkId::new("reference", n), &f, |b, f| {
+            b.iter(|| reference_verify(&f.public_key, &signature_bytes, &f.values))
+        });
+        group.bench_with_input(BenchmarkId::new("fast", n), &f, |b, f| {
+            b.iter(|| f.public_key.verify(f.signature.clone(), f.messages.clone()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Half of the messages disclosed, the other half hidden; the last message
+/// stands in for the nym secret and is never disclosed
+fn verify_proof(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("verify_proof/{}", BACKEND));
+    for n in MESSAGE_COUNTS {
+        let f = fixture(n);
+        let revealed: Vec<u32> = (0..n - 1).step_by(2).collect();
+        let revealed_messages: Vec<Vec<u8>> = revealed.iter().map(|&i| f.messages[i as usize].clone()).collect();
+        let nonce = b"bench-nonce".to_vec();
+        let proof = f
+            .signature
+            .create_proof(
+                f.public_key.clone(),
+                f.messages.clone(),
+                revealed.clone(),
+                Some(nonce.clone()),
+                b"bench.example".to_vec(),
+                None,
+                0,
+                None,
+            )
+            .unwrap();
+
+        group.bench_with_input(BenchmarkId::from_parameter(n), &proof, |b, proof| {
+            b.iter(|| {
+                f.public_key
+                    .verify_proof(proof.clone(), n as u64, revealed.clone(), revealed_messages.clone(), nonce.clone(), b"bench.example".to_vec(), None, 0, None)
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, verify_signature, verify_proof);
+criterion_main!(benches);
@@ -0,0 +1,531 @@
+// Accumulator Revocation
+// ======================
+// Universal Vitto-Biryukov accumulator (VB20) over the revoked credentials:
+// V = P*u*prod(y + alpha) for every revoked element y, with the issuer's
+// secret alpha, public key Q = G2*alpha and a random initial u. The element
+// of a credential is one of its signed messages (its revocation id).
+//
+// A holder keeps a non-membership witness (C, d) with C*(y + alpha) + P*d = V
+// and d != 0, which exists only while y is not accumulated. Witnesses follow
+// every revocation and reinstatement from the public `AccumulatorUpdate` log
+// alone. Presentations prove non-revocation in zero knowledge: with random r,
+// C' = C*r, delta = d*r, D = P*delta != 1 and Cbar = V*r - C'*y - D, the
+// verifier checks e(C', Q) == e(Cbar, G2) and proofs of knowledge of (r, y)
+// with Cbar + D = V*r - C'*y and of delta with D = P*delta, where y shares
+// its response with the BBS+ proof. Without the second one a holder could
+// pick D freely and turn a witness from before their revocation into a
+// proof against the current accumulator.
+
+use crate::backend::{Backend, CurveBackend, G2_PREPARED};
+use crate::bbs::{
+    append_proof_context, message_to_scalar, pseudonym_base, BbsProof, BbsPublicKey, BbsSignature, NymBinding,
+    NymCheck, PROOF_VERSION,
+};
+use crate::periwinkle::PeriwinkleRng;
+use crate::{hash_to_scalar, parse_secret_key, random_scalar, VerifyError};
+use bls12_381::{G1Affine, G1Projective, G2Affine, Scalar};
+use ff::Field;
+use group::Curve;
+use rand::{CryptoRng, RngCore};
+use std::collections::HashMap;
+
+const WITNESS_LEN: usize = 48 + 32;
+const UPDATE_LEN: usize = 1 + 32 + 48 + 48;
+const NON_REVOCATION_PROOF_LEN: usize = 48 * 3 + 32 * 2;
+
+fn g1_from_slice(bytes: &[u8]) -> Result<G1Affine, VerifyError> {
+    let arr: [u8; 48] = bytes.try_into().map_err(|_| VerifyError::InvalidSignature)?;
+    G1Affine::from_compressed(&arr).into_option().ok_or(VerifyError::InvalidSignature)
+}
+
+fn scalar_from_slice(bytes: &[u8]) -> Result<Scalar, VerifyError> {
+    let arr: [u8; 32] = bytes.try_into().map_err(|_| VerifyError::InvalidSignature)?;
+    Scalar::from_bytes(&arr).into_option().ok_or(VerifyError::InvalidSignature)
+}
+
+fn parse_accumulator_public_key(bytes: &[u8]) -> Result<G2Affine, VerifyError> {
+    let arr: [u8; 96] = bytes.try_into().map_err(|_| VerifyError::InvalidKey)?;
+    G2Affine::from_compressed(&arr).into_option().ok_or(VerifyError::InvalidKey)
+}
+
+pub(crate) fn parse_accumulator(bytes: &[u8]) -> Result<G1Affine, VerifyError> {
+    let value = g1_from_slice(bytes)?;
+    if bool::from(value.is_identity()) {
+        return Err(VerifyError::InvalidSignature);
+    }
+    Ok(value)
+}
+
+// ============================================================================
+// Issuer
+// ============================================================================
+
+/// Fresh accumulator value V = P*u, before any revocation
+pub(crate) fn initial_accumulator<R: RngCore + CryptoRng>(rng: &mut R) -> Result<G1Affine, VerifyError> {
+    Ok(Backend::lincomb(&[G1Affine::generator()], &[random_scalar(rng)?]).to_affine())
+}
+
+/// V*(y + alpha) when revoking, V/(y + alpha) when reinstating
+pub(crate) fn accumulate(
+    value: &G1Affine,
+    secret_key: &[u8],
+    revocation_message: &[u8],
+    revoke: bool,
+) -> Result<G1Affine, VerifyError> {
+    let alpha = parse_secret_key(secret_key)?;
+    let factor = message_to_scalar(revocation_message) + alpha;
+    let factor = if revoke { factor } else { factor.invert().into_option().ok_or(VerifyError::CryptoError)? };
+    Ok(Backend::lincomb(&[*value], &[factor]).to_affine())
+}
+
+/// Non-membership witness C = (V - P)/(y + alpha), d = 1. The caller must
+/// make sure the element is not revoked.
+pub(crate) fn issue_witness(
+    value: &G1Affine,
+    secret_key: &[u8],
+    revocation_message: &[u8],
+) -> Result<Vec<u8>, VerifyError> {
+    let alpha = parse_secret_key(secret_key)?;
+    let inverse = (message_to_scalar(revocation_message) + alpha).invert().into_option().ok_or(VerifyError::CryptoError)?;
+    let c = Backend::lincomb(&[*value, G1Affine::generator()], &[inverse, -inverse]).to_affine();
+    Ok(NonMembershipWitness { c, d: Scalar::ONE }.to_bytes())
+}
+
+/// Generates an issuer accumulator secret key alpha
+#[uniffi::export]
+pub fn generate_accumulator_secret_key() -> Result<Vec<u8>, VerifyError> {
+    generate_accumulator_secret_key_with_rng(&mut PeriwinkleRng)
+}
+
+pub fn generate_accumulator_secret_key_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Result<Vec<u8>, VerifyError> {
+    Ok(random_scalar(rng)?.to_bytes().to_vec())
+}
+
+/// Q = G2*alpha (96 bytes, compressed)
+#[uniffi::export]
+pub fn accumulator_public_key(secret_key: Vec<u8>) -> Result<Vec<u8>, VerifyError> {
+    let alpha = parse_secret_key(&secret_key)?;
+    Ok((G2Affine::generator() * alpha).to_affine().to_compressed().to_vec())
+}
+
+// ============================================================================
+// Holder
+// ============================================================================
+
+/// One revocation (`revoked`) or reinstatement of `element`, taking the
+/// accumulator from `previous_value` to `value`
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct AccumulatorUpdate {
+    pub sequence: u64,
+    pub revoked: bool,
+    pub element: Vec<u8>,
+    pub previous_value: Vec<u8>,
+    pub value: Vec<u8>,
+}
+
+impl AccumulatorUpdate {
+    pub(crate) fn new(sequence: u64, revoked: bool, revocation_message: &[u8], previous: &G1Affine, value: &G1Affine) -> Self {
+        Self {
+            sequence,
+            revoked,
+            element: message_to_scalar(revocation_message).to_bytes().to_vec(),
+            previous_value: previous.to_compressed().to_vec(),
+            value: value.to_compressed().to_vec(),
+        }
+    }
+
+    /// revoked || element || previous value || value (sequence is the key)
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(UPDATE_LEN);
+        out.push(self.revoked as u8);
+        out.extend_from_slice(&self.element);
+        out.extend_from_slice(&self.previous_value);
+        out.extend_from_slice(&self.value);
+        out
+    }
+
+    pub(crate) fn from_bytes(sequence: u64, bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != UPDATE_LEN {
+            return None;
+        }
+        Some(Self {
+            sequence,
+            revoked: bytes[0] == 1,
+            element: bytes[1..33].to_vec(),
+            previous_value: bytes[33..81].to_vec(),
+            value: bytes[81..].to_vec(),
+        })
+    }
+}
+
+/// C || d with C*(y + alpha) + P*d = V
+struct NonMembershipWitness {
+    c: G1Affine,
+    d: Scalar,
+}
+
+impl NonMembershipWitness {
+    fn parse(bytes: &[u8]) -> Result<Self, VerifyError> {
+        if bytes.len() != WITNESS_LEN {
+            return Err(VerifyError::InvalidSignature);
+        }
+        let d = scalar_from_slice(&bytes[48..])?;
+        if bool::from(d.is_zero()) {
+            return Err(VerifyError::InvalidSignature);
+        }
+        Ok(Self { c: g1_from_slice(&bytes[..48])?, d })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.c.to_compressed().to_vec();
+        out.extend_from_slice(&self.d.to_bytes());
+        out
+    }
+
+    /// Revoking y': d' = d*(y' - y), C' = V + C*(y' - y).
+    /// Reinstating y': d' = d/(y' - y), C' = (C - V')/(y' - y).
+    fn apply(&mut self, y: Scalar, update: &AccumulatorUpdate) -> Result<(), VerifyError> {
+        let delta = scalar_from_slice(&update.element)? - y;
+        if update.revoked {
+            let previous = parse_accumulator(&update.previous_value)?;
+            self.c = Backend::lincomb(&[previous, self.c], &[Scalar::ONE, delta]).to_affine();
+            self.d *= delta;
+        } else {
+            let value = parse_accumulator(&update.value)?;
+            let inverse = delta.invert().into_option().ok_or(VerifyError::InvalidSignature)?;
+            self.c = Backend::lincomb(&[self.c, value], &[inverse, -inverse]).to_affine();
+            self.d *= inverse;
+        }
+        // d = 0 once y itself is revoked
+        if bool::from(self.d.is_zero()) {
+            return Err(VerifyError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    /// e(C, G2*y + Q) * e(P*d - V, G2) == 1
+    fn verify(&self, value: &G1Affine, public_key: &G2Affine, y: Scalar) -> bool {
+        let shifted = (G2Affine::generator() * y + public_key).to_affine();
+        let rest = Backend::msm_vartime(&[G1Affine::generator(), *value], &[self.d, -Scalar::ONE]).to_affine();
+        Backend::pairing_product_is_identity(&[(&self.c, &Backend::prepare_g2(&shifted)), (&rest, &*G2_PREPARED)])
+    }
+}
+
+/// Applies `updates`, in sequence order, to a non-membership witness for the
+/// credential whose revocation id is `revocation_message`. Fails once the
+/// credential itself is revoked.
+#[uniffi::export]
+pub fn update_non_revocation_witness(
+    witness: Vec<u8>,
+    revocation_message: Vec<u8>,
+    updates: Vec<AccumulatorUpdate>,
+) -> Result<Vec<u8>, VerifyError> {
+    let mut witness = NonMembershipWitness::parse(&witness)?;
+    let y = message_to_scalar(&revocation_message);
+    for update in &updates {
+        witness.apply(y, update)?;
+    }
+    Ok(witness.to_bytes())
+}
+
+/// Checks a non-membership witness against the accumulator `value`
+#[uniffi::export]
+pub fn verify_non_revocation_witness(
+    public_key: Vec<u8>,
+    accumulator: Vec<u8>,
+    witness: Vec<u8>,
+    revocation_message: Vec<u8>,
+) -> Result<bool, VerifyError> {
+    let public_key = parse_accumulator_public_key(&public_key)?;
+    let value = parse_accumulator(&accumulator)?;
+    let witness = NonMembershipWitness::parse(&witness)?;
+    Ok(witness.verify(&value, &public_key, message_to_scalar(&revocation_message)))
+}
+
+// ============================================================================
+// Zero-Knowledge Non-Revocation Proof
+// ============================================================================
+
+/// Prover input: the witness for the hidden message at `index`
+pub(crate) struct NonRevocationClaim {
+    value: G1Affine,
+    witness: NonMembershipWitness,
+    pub(crate) index: usize,
+}
+
+/// Verifier input: accumulator value and key, revocation id at `index`
+pub(crate) struct RevocationStatement {
+    value: G1Affine,
+    public_key: G2Affine,
+    pub(crate) index: usize,
+}
+
+/// V || C' || Cbar || D || T || T_delta
+fn append_statement(out: &mut Vec<u8>, value: &G1Affine, proof_points: [&G1Affine; 3], t: [&G1Affine; 2]) {
+    out.extend_from_slice(&value.to_compressed());
+    for p in proof_points.into_iter().chain(t) {
+        out.extend_from_slice(&p.to_compressed());
+    }
+}
+
+impl NonRevocationClaim {
+    /// Randomizes the witness and appends the commitments. `y_tilde` must be
+    /// the BBS+ blinding of the revocation id.
+    pub(crate) fn commit<R: RngCore + CryptoRng>(
+        &self,
+        y: Scalar,
+        y_tilde: Scalar,
+        out: &mut Vec<u8>,
+        rng: &mut R,
+    ) -> Result<NonRevocationWitness, VerifyError> {
+        let r = random_scalar(rng)?;
+        if bool::from(r.is_zero()) {
+            return Err(VerifyError::CryptoError);
+        }
+        let p = G1Affine::generator();
+        let delta = self.witness.d * r;
+        let c_prime = Backend::lincomb(&[self.witness.c], &[r]).to_affine();
+        let d = Backend::lincomb(&[p], &[delta]);
+        let r_tilde = random_scalar(rng)?;
+        let delta_tilde = random_scalar(rng)?;
+        let mut points = [G1Affine::identity(); 2];
+        G1Projective::batch_normalize(&[Backend::lincomb(&[self.value, c_prime], &[r, -y]) - d, d], &mut points);
+        let [c_bar, d] = points;
+        let mut t = [G1Affine::identity(); 2];
+        G1Projective::batch_normalize(
+            &[Backend::lincomb(&[self.value, c_prime], &[r_tilde, -y_tilde]), Backend::lincomb(&[p], &[delta_tilde])],
+            &mut t,
+        );
+        append_statement(out, &self.value, [&c_prime, &c_bar, &d], [&t[0], &t[1]]);
+        Ok(NonRevocationWitness { c_prime, c_bar, d, r, r_tilde, delta, delta_tilde })
+    }
+}
+
+/// Prover state between commitment and response
+pub(crate) struct NonRevocationWitness {
+    c_prime: G1Affine,
+    c_bar: G1Affine,
+    d: G1Affine,
+    r: Scalar,
+    r_tilde: Scalar,
+    delta: Scalar,
+    delta_tilde: Scalar,
+}
+
+impl NonRevocationWitness {
+    pub(crate) fn respond(&self, challenge: Scalar) -> NonRevocationProof {
+        NonRevocationProof {
+            c_prime: self.c_prime,
+            c_bar: self.c_bar,
+            d: self.d,
+            r_hat: self.r_tilde + challenge * self.r,
+            delta_hat: self.delta_tilde + challenge * self.delta,
+        }
+    }
+}
+
+/// C' || Cbar || D || r^ || delta^
+pub(crate) struct NonRevocationProof {
+    c_prime: G1Affine,
+    c_bar: G1Affine,
+    d: G1Affine,
+    r_hat: Scalar,
+    delta_hat: Scalar,
+}
+
+impl NonRevocationProof {
+    pub(crate) fn write(&self, out: &mut Vec<u8>) {
+        for p in [&self.c_prime, &self.c_bar, &self.d] {
+            out.extend_from_slice(&p.to_compressed());
+        }
+        out.extend_from_slice(&self.r_hat.to_bytes());
+        out.extend_from_slice(&self.delta_hat.to_bytes());
+    }
+
+    /// Reads one non-revocation proof and returns it with the number of bytes consumed
+    pub(crate) fn read(bytes: &[u8]) -> Result<(Self, usize), VerifyError> {
+        let bytes = bytes.get(..NON_REVOCATION_PROOF_LEN).ok_or(VerifyError::InvalidSignature)?;
+        let proof = Self {
+            c_prime: g1_from_slice(&bytes[..48])?,
+            c_bar: g1_from_slice(&bytes[48..96])?,
+            d: g1_from_slice(&bytes[96..144])?,
+            r_hat: scalar_from_slice(&bytes[144..176])?,
+            delta_hat: scalar_from_slice(&bytes[176..])?,
+        };
+        Ok((proof, NON_REVOCATION_PROOF_LEN))
+    }
+
+    /// Rebuilds T = V*r^ - C'*y^ - c*(Cbar + D) and
+    /// T_delta = P*delta^ - c*D, and appends the statement
+    pub(crate) fn recompute(
+        &self,
+        statement: &RevocationStatement,
+        y_hat: Scalar,
+        challenge: Scalar,
+        out: &mut Vec<u8>,
+    ) -> Result<(), VerifyError> {
+        if bool::from(self.c_prime.is_identity() | self.d.is_identity()) {
+            return Err(VerifyError::InvalidSignature);
+        }
+        let mut t = [G1Affine::identity(); 2];
+        G1Projective::batch_normalize(
+            &[
+                Backend::msm_vartime(
+                    &[statement.value, self.c_prime, self.c_bar, self.d],
+                    &[self.r_hat, -y_hat, -challenge, -challenge],
+                ),
+                Backend::msm_vartime(&[G1Affine::generator(), self.d], &[self.delta_hat, -challenge]),
+            ],
+            &mut t,
+        );
+        append_statement(out, &statement.value, [&self.c_prime, &self.c_bar, &self.d], [&t[0], &t[1]]);
+        Ok(())
+    }
+
+    /// e(C', Q) == e(Cbar, G2)
+    pub(crate) fn check_pairing(&self, statement: &RevocationStatement) -> bool {
+        let neg_c_bar = -self.c_bar;
+        Backend::pairing_product_is_identity(&[
+            (&self.c_prime, &Backend::prepare_g2(&statement.public_key)),
+            (&neg_c_bar, &*G2_PREPARED),
+        ])
+    }
+}
+
+// ============================================================================
+// Exported API
+// ============================================================================
+
+/// Holder's accumulator state for one presentation
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct NonRevocationInput {
+    pub accumulator: Vec<u8>,
+    pub witness: Vec<u8>,
+    /// Index of the hidden message holding the revocation id
+    pub message_index: u32,
+}
+
+/// Verifier's view of the registry for one presentation
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct RevocationCheck {
+    pub public_key: Vec<u8>,
+    pub accumulator: Vec<u8>,
+    pub message_index: u32,
+}
+
+impl NonRevocationInput {
+    fn claim(&self) -> Result<NonRevocationClaim, VerifyError> {
+        Ok(NonRevocationClaim {
+            value: parse_accumulator(&self.accumulator)?,
+            witness: NonMembershipWitness::parse(&self.witness)?,
+            index: self.message_index as usize,
+        })
+    }
+}
+
+impl RevocationCheck {
+    fn statement(&self) -> Result<RevocationStatement, VerifyError> {
+        Ok(RevocationStatement {
+            value: parse_accumulator(&self.accumulator)?,
+            public_key: parse_accumulator_public_key(&self.public_key)?,
+            index: self.message_index as usize,
+        })
+    }
+}
+
+/// `create_proof` with a proof that the credential is not revoked in the
+/// accumulator `non_revocation.accumulator`
+#[uniffi::export]
+#[allow(clippy::too_many_arguments)]
+pub fn create_proof_with_revocation(
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+    revealed_indices: Vec<u32>,
+    nonce: Vec<u8>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+    non_revocation: NonRevocationInput,
+) -> Result<Vec<u8>, VerifyError> {
+    create_proof_with_revocation_with_rng(
+        public_key,
+        signature,
+        messages,
+        revealed_indices,
+        nonce,
+        site_id,
+        epoch,
+        alias_index,
+        freshness_claim,
+        non_revocation,
+        &mut PeriwinkleRng,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_proof_with_revocation_with_rng<R: RngCore + CryptoRng>(
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+    revealed_indices: Vec<u32>,
+    nonce: Vec<u8>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+    non_revocation: NonRevocationInput,
+    rng: &mut R,
+) -> Result<Vec<u8>, VerifyError> {
+    let pk = BbsPublicKey::parse(&public_key)?;
+    let sig = BbsSignature::parse(&signature)?;
+    let claim = non_revocation.claim()?;
+    let mut challenge_data = vec![PROOF_VERSION];
+    let witness = sig.commit_proof(
+        &pk,
+        &messages,
+        &revealed_indices,
+        &[],
+        &NymBinding::Pseudonym(pseudonym_base(&site_id, epoch.as_deref())),
+        Some(&claim),
+        &HashMap::new(),
+        &mut challenge_data,
+        rng,
+    )?;
+    append_proof_context(&mut challenge_data, &nonce, alias_index, freshness_claim.as_deref());
+    Ok(witness.respond(hash_to_scalar(&challenge_data)).to_bytes())
+}
+
+/// `verify_proof_safe` that also requires a non-revocation proof against the
+/// verifier's current accumulator
+#[uniffi::export]
+#[allow(clippy::too_many_arguments)]
+pub fn verify_proof_with_revocation(
+    public_key: Vec<u8>,
+    proof: Vec<u8>,
+    total_message_count: u64,
+    revealed_indices: Vec<u32>,
+    revealed_messages_content: Vec<Vec<u8>>,
+    nonce: Vec<u8>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+    revocation: RevocationCheck,
+) -> Result<bool, VerifyError> {
+    let pk = BbsPublicKey::parse(&public_key)?;
+    let proof = BbsProof::parse(&proof)?;
+    pk.verify_bound_proof(
+        &proof,
+        total_message_count as usize,
+        &revealed_indices,
+        &revealed_messages_content,
+        &[],
+        &nonce,
+        &NymCheck::Pseudonym(pseudonym_base(&site_id, epoch.as_deref())),
+        Some(&revocation.statement()?),
+        alias_index,
+        freshness_claim.as_deref(),
+    )
+}
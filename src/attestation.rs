@@ -26,7 +26,6 @@ pub fn verify_device_attestation(chain_der: &[Vec<u8>]) -> Result<Vec<u8>, Strin
     });
 
     if !has_attestation {
-        println!("[ATTESTATION] CRITICAL: No Android Attestation extension found.");
         return Err("Production Security Violation: Device not backed by Hardware KeyStore (StrongBox/SE required)".to_string());
     }
 
@@ -40,7 +39,6 @@ pub fn verify_device_attestation(chain_der: &[Vec<u8>]) -> Result<Vec<u8>, Strin
         .map_err(|e| format!("Failed to parse root cert: {}", e))?;
 
     let issuer = root.issuer().to_string();
-    println!("[ATTESTATION] Root Issuer: {}", issuer);
     
     if issuer.contains("Google") || issuer.contains("Apple") {
         // Return the Subject Public Key Info (SPKI) from the leaf cert
@@ -0,0 +1,251 @@
+// Curve Backends
+// ==============
+// The group operations that dominate signing, proving and verification go
+// through `CurveBackend`: hashing to G1, linear combinations of G1 points and
+// pairing products. Points and scalars stay bls12_381 types everywhere else,
+// so both backends produce byte-identical keys, signatures and proofs.
+//
+// `Bls12381Backend` is pure Rust and always built. With the `blst` cargo
+// feature, `Backend` switches to `BlstBackend`, which converts points through
+// their uncompressed encoding and runs the arithmetic in blst's assembly.
+// Points used by many multiplications, such as a key's generators, are
+// converted once with `prepare_g1` and passed to `msm_vartime_prepared`.
+// blst only implements expand_message_xmd, so the SHAKE-256 hash-to-curve of
+// the IETF suite stays on bls12_381 under either backend.
+
+use crate::hash_to_curve;
+use crate::msm::msm;
+use bls12_381::{G1Affine, G1Projective, G2Affine, G2Prepared, Scalar};
+use group::Group;
+use lazy_static::lazy_static;
+
+pub trait CurveBackend {
+    /// G2 point with its Miller-loop precomputation, reusable across checks
+    type PreparedG2: Send + Sync;
+
+    /// G1 point in the backend's representation, reusable across calls
+    type PreparedG1: Copy + Send + Sync;
+
+    /// BLS12381G1_XMD:SHA-256_SSWU_RO_
+    fn hash_to_g1(msg: &[u8], dst: &[u8]) -> G1Projective;
+
+    /// sum(points[i] * scalars[i]) in constant time, for secret scalars
+    fn lincomb(points: &[G1Affine], scalars: &[Scalar]) -> G1Projective;
+
+    /// sum(points[i] * scalars[i]) in variable time, for public scalars only
+    fn msm_vartime(points: &[G1Affine], scalars: &[Scalar]) -> G1Projective;
+
+    fn prepare_g1(point: &G1Affine) -> Self::PreparedG1;
+
+    /// `msm_vartime` over points already converted with `prepare_g1`
+    fn msm_vartime_prepared(points: &[Self::PreparedG1], scalars: &[Scalar]) -> G1Projective;
+
+    fn prepare_g2(point: &G2Affine) -> Self::PreparedG2;
+
+    /// prod(e(Pi, Qi)) == 1 with one Miller loop and one final exponentiation
+    fn pairing_product_is_identity(terms: &[(&G1Affine, &Self::PreparedG2)]) -> bool;
+}
+
+#[cfg(not(feature = "blst"))]
+pub type Backend = Bls12381Backend;
+#[cfg(feature = "blst")]
+pub type Backend = BlstBackend;
+
+lazy_static! {
+    pub(crate) static ref G2_PREPARED: <Backend as CurveBackend>::PreparedG2 =
+        Backend::prepare_g2(&G2Affine::generator());
+    pub(crate) static ref G1_PREPARED: <Backend as CurveBackend>::PreparedG1 =
+        Backend::prepare_g1(&G1Affine::generator());
+}
+
+// ============================================================================
+// bls12_381
+// ============================================================================
+
+pub struct Bls12381Backend;
+
+impl CurveBackend for Bls12381Backend {
+    type PreparedG2 = G2Prepared;
+    type PreparedG1 = G1Affine;
+
+    fn hash_to_g1(msg: &[u8], dst: &[u8]) -> G1Projective {
+        hash_to_curve::bls12_381_hash_to_g1(msg, dst)
+    }
+
+    fn lincomb(points: &[G1Affine], scalars: &[Scalar]) -> G1Projective {
+        debug_assert_eq!(points.len(), scalars.len());
+        points.iter().zip(scalars).fold(G1Projective::identity(), |acc, (p, k)| acc + p * k)
+    }
+
+    fn msm_vartime(points: &[G1Affine], scalars: &[Scalar]) -> G1Projective {
+        msm(points, scalars)
+    }
+
+    fn prepare_g1(point: &G1Affine) -> G1Affine {
+        *point
+    }
+
+    fn msm_vartime_prepared(points: &[G1Affine], scalars: &[Scalar]) -> G1Projective {
+        msm(points, scalars)
+    }
+
+    fn prepare_g2(point: &G2Affine) -> G2Prepared {
+        G2Prepared::from(*point)
+    }
+
+    fn pairing_product_is_identity(terms: &[(&G1Affine, &G2Prepared)]) -> bool {
+        bool::from(bls12_381::multi_miller_loop(terms).final_exponentiation().is_identity())
+    }
+}
+
+// ============================================================================
+// blst
+// ============================================================================
+
+#[cfg(feature = "blst")]
+pub use blst_backend::BlstBackend;
+
+#[cfg(feature = "blst")]
+mod blst_backend {
+    use super::CurveBackend;
+    use blst::{
+        blst_final_exp, blst_fp12, blst_fp12_is_one, blst_fp12_mul, blst_fp6, blst_hash_to_g1,
+        blst_miller_loop_lines, blst_p1, blst_p1_add_or_double, blst_p1_affine, blst_p1_affine_is_inf,
+        blst_p1_deserialize, blst_p1_from_affine, blst_p1_mult, blst_p1_serialize, blst_p2_affine,
+        blst_p2_deserialize, blst_precompute_lines, MultiPoint, BLST_ERROR,
+    };
+    use bls12_381::{G1Affine, G1Projective, G2Affine, Scalar};
+
+    /// Line coefficients of blst's optimal ate Miller loop
+    const MILLER_LINES: usize = 68;
+
+    pub struct BlstBackend;
+
+    /// Precomputed Miller-loop lines of a G2 point
+    pub struct BlstPreparedG2 {
+        lines: Box<[blst_fp6; MILLER_LINES]>,
+        is_identity: bool,
+    }
+
+    fn p1_affine(point: &G1Affine) -> blst_p1_affine {
+        let mut out = blst_p1_affine::default();
+        let err = unsafe { blst_p1_deserialize(&mut out, point.to_uncompressed().as_ptr()) };
+        debug_assert!(err == BLST_ERROR::BLST_SUCCESS);
+        out
+    }
+
+    fn p1(point: &G1Affine) -> blst_p1 {
+        let mut out = blst_p1::default();
+        unsafe { blst_p1_from_affine(&mut out, &p1_affine(point)) };
+        out
+    }
+
+    fn from_p1(point: &blst_p1) -> G1Projective {
+        let mut bytes = [0u8; 96];
+        unsafe { blst_p1_serialize(bytes.as_mut_ptr(), point) };
+        // blst only produces points on the curve in the prime-order subgroup
+        G1Affine::from_uncompressed_unchecked(&bytes)
+            .into_option()
+            .map(G1Projective::from)
+            .expect("blst returned an invalid G1 point")
+    }
+
+    impl CurveBackend for BlstBackend {
+        type PreparedG2 = BlstPreparedG2;
+        type PreparedG1 = blst_p1_affine;
+
+        fn hash_to_g1(msg: &[u8], dst: &[u8]) -> G1Projective {
+            let mut out = blst_p1::default();
+            unsafe {
+                blst_hash_to_g1(&mut out, msg.as_ptr(), msg.len(), dst.as_ptr(), dst.len(), std::ptr::null(), 0)
+            };
+            from_p1(&out)
+        }
+
+        fn lincomb(points: &[G1Affine], scalars: &[Scalar]) -> G1Projective {
+            debug_assert_eq!(points.len(), scalars.len());
+            let mut acc = blst_p1::default();
+            let acc_ptr: *mut blst_p1 = &mut acc;
+            for (point, scalar) in points.iter().zip(scalars) {
+                let mut term = blst_p1::default();
+                unsafe {
+                    blst_p1_mult(&mut term, &p1(point), scalar.to_bytes().as_ptr(), 255);
+                    blst_p1_add_or_double(acc_ptr, acc_ptr, &term);
+                }
+            }
+            from_p1(&acc)
+        }
+
+        fn msm_vartime(points: &[G1Affine], scalars: &[Scalar]) -> G1Projective {
+            debug_assert_eq!(points.len(), scalars.len());
+            if points.len() < 2 {
+                return Self::lincomb(points, scalars);
+            }
+            let affine: Vec<blst_p1_affine> = points.iter().map(p1_affine).collect();
+            Self::msm_vartime_prepared(&affine, scalars)
+        }
+
+        fn prepare_g1(point: &G1Affine) -> blst_p1_affine {
+            p1_affine(point)
+        }
+
+        fn msm_vartime_prepared(points: &[blst_p1_affine], scalars: &[Scalar]) -> G1Projective {
+            debug_assert_eq!(points.len(), scalars.len());
+            let scalar_bytes: Vec<u8> = scalars.iter().flat_map(|k| k.to_bytes()).collect();
+            if points.len() < 2 {
+                // MultiPoint::mult expects at least two points
+                let mut acc = blst_p1::default();
+                let acc_ptr: *mut blst_p1 = &mut acc;
+                for (point, scalar) in points.iter().zip(scalar_bytes.chunks(32)) {
+                    let mut base = blst_p1::default();
+                    let mut term = blst_p1::default();
+                    unsafe {
+                        blst_p1_from_affine(&mut base, point);
+                        blst_p1_mult(&mut term, &base, scalar.as_ptr(), 255);
+                        blst_p1_add_or_double(acc_ptr, acc_ptr, &term);
+                    }
+                }
+                return from_p1(&acc);
+            }
+            from_p1(&points.mult(&scalar_bytes, 255))
+        }
+
+        fn prepare_g2(point: &G2Affine) -> BlstPreparedG2 {
+            let mut affine = blst_p2_affine::default();
+            let err = unsafe { blst_p2_deserialize(&mut affine, point.to_uncompressed().as_ptr()) };
+            debug_assert!(err == BLST_ERROR::BLST_SUCCESS);
+            let mut lines = Box::new([blst_fp6::default(); MILLER_LINES]);
+            unsafe { blst_precompute_lines(lines.as_mut_ptr(), &affine) };
+            BlstPreparedG2 { lines, is_identity: bool::from(point.is_identity()) }
+        }
+
+        fn pairing_product_is_identity(terms: &[(&G1Affine, &BlstPreparedG2)]) -> bool {
+            let mut product: Option<blst_fp12> = None;
+            for (p, q) in terms {
+                // e(O, Q) = e(P, O) = 1
+                let p = p1_affine(p);
+                if q.is_identity || unsafe { blst_p1_affine_is_inf(&p) } {
+                    continue;
+                }
+                let mut f = blst_fp12::default();
+                unsafe { blst_miller_loop_lines(&mut f, q.lines.as_ptr(), &p) };
+                product = Some(match product {
+                    Some(acc) => {
+                        let mut out = blst_fp12::default();
+                        unsafe { blst_fp12_mul(&mut out, &acc, &f) };
+                        out
+                    }
+                    None => f,
+                });
+            }
+            let Some(product) = product else {
+                return true;
+            };
+            let mut result = blst_fp12::default();
+            unsafe {
+                blst_final_exp(&mut result, &product);
+                blst_fp12_is_one(&result)
+            }
+        }
+    }
+}
@@ -0,0 +1,187 @@
+// Batch Verification
+// ==================
+// Each signature or proof reduces to one pairing equation
+// e(x_i, w_i) * e(y_i, g2) == 1. A batch raises every equation to a random
+// delta_i and multiplies them: x terms under the same issuer key are summed,
+// all y terms share g2, so n items cost one multi-Miller loop over
+// (#keys + 1) pairs and a single final exponentiation. A failing batch is
+// bisected to find the invalid items.
+
+use crate::backend::{Backend, CurveBackend, G2_PREPARED};
+use crate::bbs::{pseudonym_base, BbsProof, BbsPublicKey, BbsSignature, NymCheck};
+use crate::periwinkle::PeriwinkleRng;
+use crate::{checked, random_scalar, VerifyError};
+use bls12_381::{G1Affine, G1Projective, G2Affine, Scalar};
+use rand::{CryptoRng, RngCore};
+use std::collections::HashMap;
+
+/// Arguments of one `verify_signature_safe` call
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct SignatureBatchItem {
+    pub public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub messages: Vec<Vec<u8>>,
+}
+
+/// Arguments of one `verify_proof_safe` call
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct ProofBatchItem {
+    pub public_key: Vec<u8>,
+    pub proof: Vec<u8>,
+    pub total_message_count: u64,
+    pub revealed_indices: Vec<u32>,
+    pub revealed_messages: Vec<Vec<u8>>,
+    pub nonce: Vec<u8>,
+    pub site_id: Vec<u8>,
+    pub epoch: Option<Vec<u8>>,
+    pub alias_index: u64,
+    pub freshness_claim: Option<Vec<u8>>,
+}
+
+/// `invalid_indices` lists every item that failed, including malformed ones
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct BatchVerification {
+    pub all_valid: bool,
+    pub invalid_indices: Vec<u32>,
+}
+
+/// e(x, w) * e(y, g2) == 1
+struct PairingEquation {
+    index: u32,
+    w: G2Affine,
+    x: G1Affine,
+    y: G1Projective,
+}
+
+fn batch_holds<R: RngCore + CryptoRng>(equations: &[PairingEquation], rng: &mut R) -> Result<bool, VerifyError> {
+    let mut by_key: HashMap<[u8; 96], (G2Affine, Vec<G1Affine>, Vec<Scalar>)> = HashMap::new();
+    let mut y_points = vec![G1Affine::identity(); equations.len()];
+    G1Projective::batch_normalize(&equations.iter().map(|eq| eq.y).collect::<Vec<_>>(), &mut y_points);
+    let mut deltas = Vec::with_capacity(equations.len());
+    for eq in equations {
+        let delta = random_scalar(rng)?;
+        let entry = by_key.entry(eq.w.to_compressed()).or_insert((eq.w, Vec::new(), Vec::new()));
+        entry.1.push(eq.x);
+        entry.2.push(delta);
+        deltas.push(delta);
+    }
+
+    let mut keys = Vec::with_capacity(by_key.len() + 1);
+    let mut g1_points = Vec::with_capacity(by_key.len() + 1);
+    for (w, xs, ds) in by_key.into_values() {
+        keys.push(Backend::prepare_g2(&w));
+        g1_points.push(Backend::msm_vartime(&xs, &ds));
+    }
+    g1_points.push(Backend::msm_vartime(&y_points, &deltas));
+    let mut g1_affine = vec![G1Affine::identity(); g1_points.len()];
+    G1Projective::batch_normalize(&g1_points, &mut g1_affine);
+
+    let terms: Vec<_> = g1_affine.iter().zip(keys.iter().chain([&*G2_PREPARED])).collect();
+    Ok(Backend::pairing_product_is_identity(&terms))
+}
+
+/// Bisects a failing batch down to the equations that do not hold
+fn collect_invalid<R: RngCore + CryptoRng>(
+    equations: &[PairingEquation],
+    invalid: &mut Vec<u32>,
+    rng: &mut R,
+) -> Result<(), VerifyError> {
+    if equations.is_empty() || batch_holds(equations, rng)? {
+        return Ok(());
+    }
+    if equations.len() == 1 {
+        invalid.push(equations[0].index);
+        return Ok(());
+    }
+    let (left, right) = equations.split_at(equations.len() / 2);
+    collect_invalid(left, invalid, rng)?;
+    collect_invalid(right, invalid, rng)
+}
+
+fn finish<R: RngCore + CryptoRng>(
+    equations: Vec<PairingEquation>,
+    mut invalid: Vec<u32>,
+    rng: &mut R,
+) -> Result<BatchVerification, VerifyError> {
+    collect_invalid(&equations, &mut invalid, rng)?;
+    invalid.sort_unstable();
+    Ok(BatchVerification { all_valid: invalid.is_empty(), invalid_indices: invalid })
+}
+
+fn signature_equation(index: u32, item: &SignatureBatchItem) -> Result<PairingEquation, VerifyError> {
+    let pk = BbsPublicKey::parse(&item.public_key)?;
+    let sig = BbsSignature::parse(&item.signature)?;
+    let (x, y) = pk.signature_pairing_terms(&sig, &item.messages)?;
+    Ok(PairingEquation { index, w: *pk.w(), x, y })
+}
+
+/// Checks the proof's challenge and returns its pairing equation, or None
+/// when the proof is already known to be invalid
+fn proof_equation(index: u32, item: &ProofBatchItem) -> Result<Option<PairingEquation>, VerifyError> {
+    let pk = BbsPublicKey::parse(&item.public_key)?;
+    let proof = BbsProof::parse(&item.proof)?;
+    let challenge_ok = checked(pk.check_proof_challenge(
+        &proof,
+        item.total_message_count as usize,
+        &item.revealed_indices,
+        &item.revealed_messages,
+        &[],
+        &item.nonce,
+        &NymCheck::Pseudonym(pseudonym_base(&item.site_id, item.epoch.as_deref())),
+        None,
+        item.alias_index,
+        item.freshness_claim.as_deref(),
+    ))?;
+    let (x, y) = proof.pairing_terms();
+    if !challenge_ok || bool::from(x.is_identity()) {
+        return Ok(None);
+    }
+    Ok(Some(PairingEquation { index, w: *pk.w(), x, y }))
+}
+
+/// Verifies many signatures, under the same or different issuer keys, with
+/// one final exponentiation when all are valid. Fails only when no batch
+/// deltas can be drawn.
+#[uniffi::export]
+pub fn batch_verify_signatures(items: Vec<SignatureBatchItem>) -> Result<BatchVerification, VerifyError> {
+    batch_verify_signatures_with_rng(items, &mut PeriwinkleRng)
+}
+
+/// `batch_verify_signatures` drawing the batch deltas from `rng`
+pub fn batch_verify_signatures_with_rng<R: RngCore + CryptoRng>(
+    items: Vec<SignatureBatchItem>,
+    rng: &mut R,
+) -> Result<BatchVerification, VerifyError> {
+    let mut equations = Vec::with_capacity(items.len());
+    let mut invalid = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        match signature_equation(i as u32, item) {
+            Ok(eq) => equations.push(eq),
+            Err(_) => invalid.push(i as u32),
+        }
+    }
+    finish(equations, invalid, rng)
+}
+
+/// Batch counterpart of `verify_proof_safe`: challenges are checked one by
+/// one, the pairing checks of all proofs share a single multi-Miller loop
+#[uniffi::export]
+pub fn batch_verify_proofs(items: Vec<ProofBatchItem>) -> Result<BatchVerification, VerifyError> {
+    batch_verify_proofs_with_rng(items, &mut PeriwinkleRng)
+}
+
+/// `batch_verify_proofs` drawing the batch deltas from `rng`
+pub fn batch_verify_proofs_with_rng<R: RngCore + CryptoRng>(
+    items: Vec<ProofBatchItem>,
+    rng: &mut R,
+) -> Result<BatchVerification, VerifyError> {
+    let mut equations = Vec::with_capacity(items.len());
+    let mut invalid = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        match proof_equation(i as u32, item) {
+            Ok(Some(eq)) => equations.push(eq),
+            Ok(None) | Err(_) => invalid.push(i as u32),
+        }
+    }
+    finish(equations, invalid, rng)
+}
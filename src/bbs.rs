@@ -0,0 +1,1336 @@
+// Typed BBS+ Objects
+// ==================
+// Secret keys, public keys, signatures and proofs of the legacy Multipass
+// BBS+ suite, exported as uniffi Objects. Bytes are parsed and validated once
+// at construction, so the generators of a key are reused across calls and
+// bindings cannot hand a public key to a parameter expecting a signature.
+//
+// Every proof carries a per-verifier pseudonym following the CFRG BBS
+// per-verifier-linkability draft: the last signed message is the holder's
+// nym secret, Pseudonym = OP * nym_secret with OP = hash_to_g1(site_id), and
+// the proof shows the pseudonym uses the same hidden message the signature
+// covers (Ut = OP * m~ under the shared challenge). Pseudonyms are stable per
+// site and unlinkable across sites. An optional epoch is folded into OP, so
+// pseudonyms rotate per epoch without reissuing the credential.
+
+use crate::backend::{Backend, CurveBackend, G1_PREPARED, G2_PREPARED};
+use crate::accumulator::{NonRevocationClaim, NonRevocationProof, NonRevocationWitness, RevocationStatement};
+use crate::nullifier::{NullifierProof, NullifierWitness, RateLimit};
+use crate::predicate::{decode_integer_message, RangePredicate, RangeProof, RangeWitness};
+use crate::periwinkle::PeriwinkleRng;
+use crate::{
+    checked, derive_generators, hash_to_curve, hash_to_scalar, parse_secret_key, random_scalar, VerifyError, GENERATOR_SEED,
+};
+use bls12_381::{G1Affine, G1Projective, G2Affine, G2Projective, Scalar};
+use ff::Field;
+use group::Curve;
+use rand::{CryptoRng, RngCore};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, OnceLock};
+
+/// Integer-encoded messages sign as their value, everything else is hashed
+pub(crate) fn message_to_scalar(message: &[u8]) -> Scalar {
+    match decode_integer_message(message) {
+        Some(value) => Scalar::from(value),
+        None => hash_to_scalar(message),
+    }
+}
+
+fn messages_to_scalars(messages: &[Vec<u8>]) -> Vec<Scalar> {
+    messages.iter().map(|m| message_to_scalar(m)).collect()
+}
+
+fn scalar_from_slice(bytes: &[u8]) -> Option<Scalar> {
+    let arr: [u8; 32] = bytes.try_into().ok()?;
+    Scalar::from_bytes(&arr).into_option()
+}
+
+fn g1_from_slice(bytes: &[u8]) -> Option<G1Affine> {
+    let arr: [u8; 48] = bytes.try_into().ok()?;
+    G1Affine::from_compressed(&arr).into_option()
+}
+
+// ============================================================================
+// Secret Key
+// ============================================================================
+
+#[derive(uniffi::Object)]
+pub struct BbsSecretKey {
+    sk: Scalar,
+}
+
+#[uniffi::export]
+impl BbsSecretKey {
+    /// Fresh key from the Periwinkle entropy source
+    #[uniffi::constructor]
+    pub fn generate() -> Result<Arc<Self>, VerifyError> {
+        Self::generate_with_rng(&mut PeriwinkleRng).map(Arc::new)
+    }
+
+    #[uniffi::constructor]
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Arc<Self>, VerifyError> {
+        Ok(Arc::new(Self { sk: parse_secret_key(&bytes)? }))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.sk.to_bytes().to_vec()
+    }
+
+    /// Public key for `message_count` messages using the default generator seed
+    pub fn public_key(&self, message_count: u32) -> Arc<BbsPublicKey> {
+        self.public_key_with_seed(GENERATOR_SEED.to_vec(), message_count)
+    }
+
+    pub fn public_key_with_seed(&self, generator_seed: Vec<u8>, message_count: u32) -> Arc<BbsPublicKey> {
+        Arc::new(BbsPublicKey::from_parts(
+            (G2Projective::generator() * self.sk).to_affine(),
+            derive_generators(&generator_seed, message_count as usize),
+        ))
+    }
+
+    pub fn sign(
+        &self,
+        public_key: Arc<BbsPublicKey>,
+        messages: Vec<Vec<u8>>,
+    ) -> Result<Arc<BbsSignature>, VerifyError> {
+        self.sign_messages(&public_key, &messages).map(Arc::new)
+    }
+}
+
+impl BbsSecretKey {
+    pub fn generate_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Result<Self, VerifyError> {
+        loop {
+            let sk = random_scalar(rng)?;
+            if !bool::from(sk.is_zero()) {
+                return Ok(Self { sk });
+            }
+        }
+    }
+
+    pub fn sign_messages(
+        &self,
+        public_key: &BbsPublicKey,
+        messages: &[Vec<u8>],
+    ) -> Result<BbsSignature, VerifyError> {
+        self.sign_messages_with_rng(public_key, messages, &mut PeriwinkleRng)
+    }
+
+    pub fn sign_messages_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        public_key: &BbsPublicKey,
+        messages: &[Vec<u8>],
+        rng: &mut R,
+    ) -> Result<BbsSignature, VerifyError> {
+        if public_key.generators.len() < messages.len() + 1 {
+            return Err(VerifyError::InvalidKey);
+        }
+        let message_part =
+            Backend::lincomb(&public_key.generators[1..=messages.len()], &messages_to_scalars(messages));
+        self.sign_message_part(public_key, message_part, rng)
+    }
+
+    /// Signs B = g1 + h0*s + `message_part`, where `message_part` already
+    /// holds sum(hi*mi), possibly including a holder commitment.
+    pub(crate) fn sign_message_part<R: RngCore + CryptoRng>(
+        &self,
+        public_key: &BbsPublicKey,
+        message_part: G1Projective,
+        rng: &mut R,
+    ) -> Result<BbsSignature, VerifyError> {
+        let e = random_scalar(rng)?;
+        let s = random_scalar(rng)?;
+
+        // A = B * (1/(sk+e))
+        let b = Backend::lincomb(&[G1Affine::generator(), public_key.generators[0]], &[Scalar::ONE, s])
+            + message_part;
+        let inv = (self.sk + e).invert().into_option().ok_or(VerifyError::CryptoError)?;
+        let a = Backend::lincomb(&[b.to_affine()], &[inv]).to_affine();
+        Ok(BbsSignature { a, e, s })
+    }
+}
+
+// ============================================================================
+// Public Key
+// ============================================================================
+
+/// Most messages a public key may carry; bounds the generators a caller can
+/// make the parser (or `expand_public_key`) allocate and hash.
+pub const MAX_MESSAGE_COUNT: u32 = 4096;
+
+/// Why a public key was rejected. Generator indexes count from h0 = 0.
+#[derive(Debug, uniffi::Error)]
+pub enum KeyError {
+    /// Shorter than w plus h0
+    Truncated,
+    /// Length is not w followed by whole 48-byte generators
+    TrailingBytes,
+    /// More than `MAX_MESSAGE_COUNT` messages
+    TooManyMessages { count: u64, max: u32 },
+    /// w is not a valid compressed G2 point
+    InvalidW,
+    WNotInSubgroup,
+    IdentityW,
+    /// Generator is not a valid compressed G1 point
+    InvalidGenerator { index: u32 },
+    GeneratorNotInSubgroup { index: u32 },
+    IdentityGenerator { index: u32 },
+    DuplicateGenerator { index: u32, first: u32 },
+}
+
+impl std::fmt::Display for KeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl std::error::Error for KeyError {}
+
+impl From<KeyError> for VerifyError {
+    fn from(e: KeyError) -> Self {
+        let field = match &e {
+            KeyError::Truncated | KeyError::TrailingBytes | KeyError::TooManyMessages { .. } => {
+                "length".to_string()
+            }
+            KeyError::InvalidW | KeyError::WNotInSubgroup | KeyError::IdentityW => "w".to_string(),
+            KeyError::InvalidGenerator { index }
+            | KeyError::GeneratorNotInSubgroup { index }
+            | KeyError::IdentityGenerator { index }
+            | KeyError::DuplicateGenerator { index, .. } => format!("h{}", index),
+        };
+        VerifyError::KeyParse { field, reason: format!("{:?}", e) }
+    }
+}
+
+/// w (G2) and the generators h0, h1..hL (G1)
+#[derive(uniffi::Object)]
+pub struct BbsPublicKey {
+    w: G2Affine,
+    generators: Vec<G1Affine>,
+    /// Miller-loop line coefficients of w, computed on first verification
+    w_prepared: OnceLock<<Backend as CurveBackend>::PreparedG2>,
+    /// Generators in the backend's representation, converted on first verification
+    generators_prepared: OnceLock<Vec<<Backend as CurveBackend>::PreparedG1>>,
+}
+
+#[uniffi::export]
+impl BbsPublicKey {
+    /// Parses w (96 bytes) || h0 || h1..hL (48 bytes each)
+    #[uniffi::constructor]
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Arc<Self>, VerifyError> {
+        Self::parse(&bytes).map(Arc::new)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut pk = Vec::with_capacity(96 + 48 * self.generators.len());
+        pk.extend_from_slice(&self.w.to_compressed());
+        for h in &self.generators {
+            pk.extend_from_slice(&h.to_compressed());
+        }
+        pk
+    }
+
+    /// Number of messages this key can sign (generators minus h0)
+    pub fn message_count(&self) -> u32 {
+        (self.generators.len() - 1) as u32
+    }
+
+    pub fn verify(
+        &self,
+        signature: Arc<BbsSignature>,
+        messages: Vec<Vec<u8>>,
+    ) -> Result<bool, VerifyError> {
+        self.verify_signature(&signature, &messages)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_proof(
+        &self,
+        proof: Arc<BbsProof>,
+        total_message_count: u64,
+        revealed_indices: Vec<u32>,
+        revealed_messages: Vec<Vec<u8>>,
+        nonce: Vec<u8>,
+        site_id: Vec<u8>,
+        epoch: Option<Vec<u8>>,
+        alias_index: u64,
+        freshness_claim: Option<Vec<u8>>,
+    ) -> Result<bool, VerifyError> {
+        self.verify_proof_of(
+            &proof,
+            total_message_count as usize,
+            &revealed_indices,
+            &revealed_messages,
+            &nonce,
+            &site_id,
+            epoch.as_deref(),
+            alias_index,
+            freshness_claim.as_deref(),
+        )
+    }
+}
+
+impl BbsPublicKey {
+    pub(crate) fn from_parts(w: G2Affine, generators: Vec<G1Affine>) -> Self {
+        Self { w, generators, w_prepared: OnceLock::new(), generators_prepared: OnceLock::new() }
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, VerifyError> {
+        Ok(Self::parse_strict(bytes)?)
+    }
+
+    /// Strict parser shared by every signing and verification path: w must
+    /// be a non-identity point of G2, each generator a non-identity point of
+    /// G1, no generator may repeat and the length must match exactly.
+    pub fn parse_strict(bytes: &[u8]) -> Result<Self, KeyError> {
+        if bytes.len() < 96 + 48 {
+            return Err(KeyError::Truncated);
+        }
+        if !(bytes.len() - 96).is_multiple_of(48) {
+            return Err(KeyError::TrailingBytes);
+        }
+        let count = ((bytes.len() - 96) / 48 - 1) as u64;
+        if count > MAX_MESSAGE_COUNT as u64 {
+            return Err(KeyError::TooManyMessages { count, max: MAX_MESSAGE_COUNT });
+        }
+
+        let w_bytes: [u8; 96] = bytes[..96].try_into().map_err(|_| KeyError::Truncated)?;
+        let w = G2Affine::from_compressed_unchecked(&w_bytes)
+            .into_option()
+            .ok_or(KeyError::InvalidW)?;
+        if !bool::from(w.is_torsion_free()) {
+            return Err(KeyError::WNotInSubgroup);
+        }
+        if bool::from(w.is_identity()) {
+            return Err(KeyError::IdentityW);
+        }
+
+        let mut generators = Vec::with_capacity((bytes.len() - 96) / 48);
+        let mut seen = HashMap::new();
+        for (i, chunk) in bytes[96..].chunks(48).enumerate() {
+            let index = i as u32;
+            let h_bytes: [u8; 48] = chunk.try_into().map_err(|_| KeyError::TrailingBytes)?;
+            let h = G1Affine::from_compressed_unchecked(&h_bytes)
+                .into_option()
+                .ok_or(KeyError::InvalidGenerator { index })?;
+            if !bool::from(h.is_torsion_free()) {
+                return Err(KeyError::GeneratorNotInSubgroup { index });
+            }
+            if bool::from(h.is_identity()) {
+                return Err(KeyError::IdentityGenerator { index });
+            }
+            // Compressed encodings are canonical, so equal bytes means equal points
+            if let Some(&first) = seen.get(&h_bytes) {
+                return Err(KeyError::DuplicateGenerator { index, first });
+            }
+            seen.insert(h_bytes, index);
+            generators.push(h);
+        }
+        Ok(Self::from_parts(w, generators))
+    }
+
+    pub fn w(&self) -> &G2Affine {
+        &self.w
+    }
+
+    pub fn generators(&self) -> &[G1Affine] {
+        &self.generators
+    }
+
+    /// B = g1 + h0*s + sum(hi*mi)
+    fn commitment(&self, s: Scalar, msg_scalars: &[Scalar]) -> G1Projective {
+        let mut points = vec![G1Affine::generator()];
+        points.extend_from_slice(&self.generators[..msg_scalars.len() + 1]);
+        let mut scalars = vec![Scalar::ONE, s];
+        scalars.extend_from_slice(msg_scalars);
+        Backend::lincomb(&points, &scalars)
+    }
+
+    pub fn verify_signature(&self, signature: &BbsSignature, messages: &[Vec<u8>]) -> Result<bool, VerifyError> {
+        if self.generators.len() < messages.len() + 1 {
+            return Err(VerifyError::InvalidKey);
+        }
+        // e(A, w * g2^e) == e(B, g2), i.e. e(A, w) * e(A*e - B, g2) == 1
+        let (x, y) = self.signature_pairing_terms(signature, messages)?;
+        Ok(self.pairing_product_is_identity(&x, &y.to_affine()))
+    }
+
+    /// Signature check as e(x, w) * e(y, g2) == 1 with x = A, y = e*A - B
+    pub(crate) fn signature_pairing_terms(
+        &self,
+        signature: &BbsSignature,
+        messages: &[Vec<u8>],
+    ) -> Result<(G1Affine, G1Projective), VerifyError> {
+        if self.generators.len() < messages.len() + 1 {
+            return Err(VerifyError::InvalidKey);
+        }
+        // A*e - g1 - h0*s - sum(hi*mi) as one multi-scalar multiplication
+        let mut points = vec![Backend::prepare_g1(&signature.a), *G1_PREPARED];
+        points.extend_from_slice(&self.generators_prepared()[..messages.len() + 1]);
+        let mut scalars = vec![signature.e, -Scalar::ONE, -signature.s];
+        scalars.extend(messages_to_scalars(messages).into_iter().map(|m| -m));
+        Ok((signature.a, Backend::msm_vartime_prepared(&points, &scalars)))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_proof_of(
+        &self,
+        proof: &BbsProof,
+        total_message_count: usize,
+        revealed_indices: &[u32],
+        revealed_messages: &[Vec<u8>],
+        nonce: &[u8],
+        site_id: &[u8],
+        epoch: Option<&[u8]>,
+        alias_index: u64,
+        freshness_claim: Option<&[u8]>,
+    ) -> Result<bool, VerifyError> {
+        self.verify_proof_with_predicates(
+            proof,
+            total_message_count,
+            revealed_indices,
+            revealed_messages,
+            &[],
+            nonce,
+            site_id,
+            epoch,
+            alias_index,
+            freshness_claim,
+        )
+    }
+
+    /// Same as `verify_proof_of`, additionally checking that the proof
+    /// carries exactly one range proof per entry of `predicates`
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_proof_with_predicates(
+        &self,
+        proof: &BbsProof,
+        total_message_count: usize,
+        revealed_indices: &[u32],
+        revealed_messages: &[Vec<u8>],
+        predicates: &[RangePredicate],
+        nonce: &[u8],
+        site_id: &[u8],
+        epoch: Option<&[u8]>,
+        alias_index: u64,
+        freshness_claim: Option<&[u8]>,
+    ) -> Result<bool, VerifyError> {
+        checked(self.check_proof_with_predicates(
+            proof,
+            total_message_count,
+            revealed_indices,
+            revealed_messages,
+            predicates,
+            nonce,
+            site_id,
+            epoch,
+            alias_index,
+            freshness_claim,
+        ))
+    }
+
+    /// `verify_proof_with_predicates` failing with the check that did not
+    /// pass, e.g. `ChallengeMismatch` or `PairingFailure`, instead of false
+    #[allow(clippy::too_many_arguments)]
+    pub fn check_proof_with_predicates(
+        &self,
+        proof: &BbsProof,
+        total_message_count: usize,
+        revealed_indices: &[u32],
+        revealed_messages: &[Vec<u8>],
+        predicates: &[RangePredicate],
+        nonce: &[u8],
+        site_id: &[u8],
+        epoch: Option<&[u8]>,
+        alias_index: u64,
+        freshness_claim: Option<&[u8]>,
+    ) -> Result<(), VerifyError> {
+        self.check_bound_proof(
+            proof,
+            total_message_count,
+            revealed_indices,
+            revealed_messages,
+            predicates,
+            nonce,
+            &NymCheck::Pseudonym(pseudonym_base(site_id, epoch)),
+            None,
+            alias_index,
+            freshness_claim,
+        )
+    }
+
+    /// Full proof verification for any binding of the nym secret
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn verify_bound_proof(
+        &self,
+        proof: &BbsProof,
+        total_message_count: usize,
+        revealed_indices: &[u32],
+        revealed_messages: &[Vec<u8>],
+        predicates: &[RangePredicate],
+        nonce: &[u8],
+        nym: &NymCheck,
+        revocation: Option<&RevocationStatement>,
+        alias_index: u64,
+        freshness_claim: Option<&[u8]>,
+    ) -> Result<bool, VerifyError> {
+        checked(self.check_bound_proof(
+            proof,
+            total_message_count,
+            revealed_indices,
+            revealed_messages,
+            predicates,
+            nonce,
+            nym,
+            revocation,
+            alias_index,
+            freshness_claim,
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn check_bound_proof(
+        &self,
+        proof: &BbsProof,
+        total_message_count: usize,
+        revealed_indices: &[u32],
+        revealed_messages: &[Vec<u8>],
+        predicates: &[RangePredicate],
+        nonce: &[u8],
+        nym: &NymCheck,
+        revocation: Option<&RevocationStatement>,
+        alias_index: u64,
+        freshness_claim: Option<&[u8]>,
+    ) -> Result<(), VerifyError> {
+        self.check_proof_challenge(
+            proof,
+            total_message_count,
+            revealed_indices,
+            revealed_messages,
+            predicates,
+            nonce,
+            nym,
+            revocation,
+            alias_index,
+            freshness_claim,
+        )?;
+        if let (Some(statement), Some(non_revocation)) = (revocation, &proof.non_revocation_proof) {
+            if !non_revocation.check_pairing(statement) {
+                return Err(VerifyError::PairingFailure);
+            }
+        }
+        if !self.check_proof_pairing(proof) {
+            return Err(VerifyError::PairingFailure);
+        }
+        Ok(())
+    }
+
+    /// The Fiat-Shamir half of proof verification, without the pairing
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn check_proof_challenge(
+        &self,
+        proof: &BbsProof,
+        total_message_count: usize,
+        revealed_indices: &[u32],
+        revealed_messages: &[Vec<u8>],
+        predicates: &[RangePredicate],
+        nonce: &[u8],
+        nym: &NymCheck,
+        revocation: Option<&RevocationStatement>,
+        alias_index: u64,
+        freshness_claim: Option<&[u8]>,
+    ) -> Result<(), VerifyError> {
+        let mut challenge_data = vec![PROOF_VERSION];
+        self.append_proof_transcript(
+            proof,
+            total_message_count,
+            revealed_indices,
+            revealed_messages,
+            predicates,
+            nym,
+            revocation,
+            &mut challenge_data,
+        )?;
+        append_proof_context(&mut challenge_data, nonce, alias_index, freshness_claim);
+        if proof.challenge != hash_to_scalar(&challenge_data) {
+            return Err(VerifyError::ChallengeMismatch);
+        }
+        Ok(())
+    }
+
+    /// Recomputes T1, T2, the range-proof commitments and the nym
+    /// commitments from the responses and appends this credential's part of
+    /// the challenge input: the public key (w and every generator), A', Abar,
+    /// d, T1, T2, the disclosed messages, the predicate transcript, the
+    /// pseudonym or nullifier statement and the non-revocation statement.
+    /// Returns the hidden message indexes in response order.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn append_proof_transcript(
+        &self,
+        proof: &BbsProof,
+        total_message_count: usize,
+        revealed_indices: &[u32],
+        revealed_messages: &[Vec<u8>],
+        predicates: &[RangePredicate],
+        nym: &NymCheck,
+        revocation: Option<&RevocationStatement>,
+        out: &mut Vec<u8>,
+    ) -> Result<Vec<usize>, VerifyError> {
+        if revealed_indices.len() != revealed_messages.len() {
+            return Err(VerifyError::proof_structure("revealed_messages"));
+        }
+        if total_message_count > self.message_count() as usize {
+            return Err(VerifyError::InvalidKey);
+        }
+        let hidden = hidden_indices(total_message_count, revealed_indices)?;
+        if proof.m_hat.len() != hidden.len() {
+            return Err(VerifyError::proof_structure("m_hat"));
+        }
+        if proof.range_proofs.len() != predicates.len() {
+            return Err(VerifyError::proof_structure("range_proofs"));
+        }
+        let h = self.generators_prepared();
+        let mut disclosed: Vec<(usize, Scalar)> = revealed_indices
+            .iter()
+            .map(|&i| i as usize)
+            .zip(messages_to_scalars(revealed_messages))
+            .collect();
+        disclosed.sort_by_key(|(i, _)| *i);
+        let c = proof.challenge;
+        let [a_prime, abar, d] = [&proof.a_prime, &proof.abar, &proof.d].map(Backend::prepare_g1);
+
+        // T1 = -A'*e^ + h0*r2^ - c*(Abar - d)
+        let t1 = Backend::msm_vartime_prepared(
+            &[a_prime, h[0], abar, d],
+            &[-proof.e_hat, proof.r2_hat, -c, c],
+        );
+
+        // T2 = d*r3^ - h0*s^ - sum(hj*mj^) - c*(g1 + sum(hi*mi disclosed))
+        let mut points = vec![d, h[0], *G1_PREPARED];
+        let mut scalars = vec![proof.r3_hat, -proof.s_hat, -c];
+        for (i, m) in &disclosed {
+            points.push(h[i + 1]);
+            scalars.push(-(c * m));
+        }
+        for (j, m_hat) in hidden.iter().zip(&proof.m_hat) {
+            points.push(h[j + 1]);
+            scalars.push(-m_hat);
+        }
+        let t2 = Backend::msm_vartime_prepared(&points, &scalars);
+
+        let mut predicate_transcript = Vec::new();
+        for (predicate, range_proof) in predicates.iter().zip(&proof.range_proofs) {
+            let m_hat = proof
+                .hidden_response(&hidden, predicate.index as usize)
+                .ok_or_else(|| VerifyError::proof_structure("range_proofs"))?;
+            range_proof.recompute(predicate, c, m_hat, &mut predicate_transcript)?;
+        }
+
+        let nym_hat = total_message_count
+            .checked_sub(1)
+            .and_then(|nym_index| proof.hidden_response(&hidden, nym_index))
+            .ok_or_else(|| VerifyError::proof_structure("nym_secret"))?;
+        if bool::from(proof.tag.is_identity()) {
+            return Err(VerifyError::proof_structure("tag"));
+        }
+
+        let mut points = [G1Affine::identity(); 2];
+        G1Projective::batch_normalize(&[t1, t2], &mut points);
+        self.append_commitments(
+            &[proof.a_prime, proof.abar, proof.d, points[0], points[1]],
+            total_message_count,
+            &disclosed,
+            &predicate_transcript,
+            out,
+        );
+        match (nym, &proof.nullifier_proof) {
+            (NymCheck::Pseudonym(base), None) => {
+                // Ut = OP*nym^ - Pseudonym*c
+                let ut = Backend::msm_vartime(&[*base, proof.tag], &[nym_hat, -c]);
+                append_pseudonym(out, &proof.tag, base, &ut.to_affine());
+            }
+            (NymCheck::Nullifier(rate_limit), Some(nullifier_proof)) => {
+                rate_limit.recompute(&proof.tag, nullifier_proof, nym_hat, c, out)?;
+            }
+            _ => return Err(VerifyError::proof_structure("nullifier_proof")),
+        }
+        match (revocation, &proof.non_revocation_proof) {
+            (Some(statement), Some(non_revocation)) => {
+                let y_hat = proof
+                    .hidden_response(&hidden, statement.index)
+                    .ok_or_else(|| VerifyError::proof_structure("non_revocation_proof"))?;
+                non_revocation.recompute(statement, y_hat, c, out)?;
+            }
+            (None, None) => {}
+            _ => return Err(VerifyError::proof_structure("non_revocation_proof")),
+        }
+        Ok(hidden)
+    }
+
+    /// e(A', w) == e(Abar, g2) with A' != 1
+    pub(crate) fn check_proof_pairing(&self, proof: &BbsProof) -> bool {
+        if bool::from(proof.a_prime.is_identity()) {
+            return false;
+        }
+        self.pairing_product_is_identity(&proof.a_prime, &-proof.abar)
+    }
+
+    pub(crate) fn w_prepared(&self) -> &<Backend as CurveBackend>::PreparedG2 {
+        self.w_prepared.get_or_init(|| Backend::prepare_g2(&self.w))
+    }
+
+    fn generators_prepared(&self) -> &[<Backend as CurveBackend>::PreparedG1] {
+        self.generators_prepared.get_or_init(|| self.generators.iter().map(Backend::prepare_g1).collect())
+    }
+
+    /// e(x, w) * e(y, g2) == 1 with one Miller loop and one final exponentiation
+    fn pairing_product_is_identity(&self, x: &G1Affine, y: &G1Affine) -> bool {
+        Backend::pairing_product_is_identity(&[(x, self.w_prepared()), (y, &*G2_PREPARED)])
+    }
+
+    fn append_commitments(
+        &self,
+        points: &[G1Affine; 5],
+        total_message_count: usize,
+        disclosed: &[(usize, Scalar)],
+        predicate_transcript: &[u8],
+        out: &mut Vec<u8>,
+    ) {
+        out.extend_from_slice(&self.to_bytes());
+        for p in points {
+            out.extend_from_slice(&p.to_compressed());
+        }
+        out.extend_from_slice(&(total_message_count as u64).to_be_bytes());
+        out.extend_from_slice(&(disclosed.len() as u64).to_be_bytes());
+        for (i, m) in disclosed {
+            out.extend_from_slice(&(*i as u64).to_be_bytes());
+            out.extend_from_slice(&m.to_bytes());
+        }
+        out.extend_from_slice(&(predicate_transcript.len() as u64).to_be_bytes());
+        out.extend_from_slice(predicate_transcript);
+    }
+}
+
+/// Presentation context closing every proof challenge. Variable-length
+/// fields are length-prefixed.
+pub(crate) fn append_proof_context(out: &mut Vec<u8>, nonce: &[u8], alias_index: u64, freshness_claim: Option<&[u8]>) {
+    out.extend_from_slice(&(nonce.len() as u64).to_be_bytes());
+    out.extend_from_slice(nonce);
+    out.extend_from_slice(&alias_index.to_le_bytes());
+    if let Some(fc) = freshness_claim {
+        out.extend_from_slice(&(fc.len() as u64).to_be_bytes());
+        out.extend_from_slice(fc);
+    }
+}
+
+/// How the prover ties the hidden nym secret to the verifier
+pub(crate) enum NymBinding<'a> {
+    /// Pseudonym = OP*nym
+    Pseudonym(G1Affine),
+    /// Nullifier for use number `counter`, see `crate::nullifier`
+    Nullifier { rate_limit: &'a RateLimit, counter: u64 },
+}
+
+/// Verifier side of `NymBinding`
+pub(crate) enum NymCheck<'a> {
+    Pseudonym(G1Affine),
+    Nullifier(&'a RateLimit),
+}
+
+const PSEUDONYM_DST: &[u8] = b"MULTIPASS_BBS_PSEUDONYM_H2G_";
+const EPOCH_PSEUDONYM_DST: &[u8] = b"MULTIPASS_BBS_EPOCH_PSEUDONYM_H2G_";
+
+/// OP = hash_to_g1(site_id), the base of every pseudonym at that verifier.
+/// With an epoch (see `crate::epoch`) the base, and so the pseudonym,
+/// changes with every epoch.
+pub(crate) fn pseudonym_base(site_id: &[u8], epoch: Option<&[u8]>) -> G1Affine {
+    match epoch {
+        None => hash_to_curve::hash_to_g1(site_id, PSEUDONYM_DST).to_affine(),
+        Some(epoch) => {
+            let mut scope = (site_id.len() as u64).to_be_bytes().to_vec();
+            scope.extend_from_slice(site_id);
+            scope.extend_from_slice(epoch);
+            hash_to_curve::hash_to_g1(&scope, EPOCH_PSEUDONYM_DST).to_affine()
+        }
+    }
+}
+
+/// Pseudonym, OP and Ut, in the order of the pseudonym draft's challenge
+fn append_pseudonym(out: &mut Vec<u8>, pseudonym: &G1Affine, pseudonym_base: &G1Affine, ut: &G1Affine) {
+    for p in [pseudonym, pseudonym_base, ut] {
+        out.extend_from_slice(&p.to_compressed());
+    }
+}
+
+/// Indexes not in `revealed`, rejecting duplicates and out-of-range entries
+fn hidden_indices(total_message_count: usize, revealed: &[u32]) -> Result<Vec<usize>, VerifyError> {
+    let mut seen = HashSet::new();
+    for &i in revealed {
+        if i as usize >= total_message_count {
+            return Err(VerifyError::RevealedIndexOutOfRange { index: i, message_count: total_message_count as u64 });
+        }
+        if !seen.insert(i as usize) {
+            return Err(VerifyError::proof_structure("revealed_indices"));
+        }
+    }
+    Ok((0..total_message_count).filter(|i| !seen.contains(i)).collect())
+}
+
+// ============================================================================
+// Signature
+// ============================================================================
+
+/// A (48) || e (32) || s (32)
+#[derive(uniffi::Object)]
+pub struct BbsSignature {
+    a: G1Affine,
+    e: Scalar,
+    s: Scalar,
+}
+
+#[uniffi::export]
+impl BbsSignature {
+    #[uniffi::constructor]
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Arc<Self>, VerifyError> {
+        Self::parse(&bytes).map(Arc::new)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut sig_bytes = Vec::with_capacity(112);
+        sig_bytes.extend_from_slice(&self.a.to_compressed());
+        sig_bytes.extend_from_slice(&self.e.to_bytes());
+        sig_bytes.extend_from_slice(&self.s.to_bytes());
+        sig_bytes
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_proof(
+        &self,
+        public_key: Arc<BbsPublicKey>,
+        messages: Vec<Vec<u8>>,
+        revealed_indices: Vec<u32>,
+        nonce: Option<Vec<u8>>,
+        site_id: Vec<u8>,
+        epoch: Option<Vec<u8>>,
+        alias_index: u64,
+        freshness_claim: Option<Vec<u8>>,
+    ) -> Result<Arc<BbsProof>, VerifyError> {
+        self.prove(
+            &public_key,
+            &messages,
+            &revealed_indices,
+            nonce.as_deref(),
+            &site_id,
+            epoch.as_deref(),
+            alias_index,
+            freshness_claim.as_deref(),
+        )
+        .map(Arc::new)
+    }
+}
+
+impl BbsSignature {
+    /// Adds the holder's commitment blinding s' to a blind signature's s''
+    pub(crate) fn unblind(&self, blinding: Scalar) -> Self {
+        Self { a: self.a, e: self.e, s: self.s + blinding }
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, VerifyError> {
+        if bytes.len() != 112 {
+            return Err(VerifyError::InvalidSignature);
+        }
+        let a = g1_from_slice(&bytes[..48]).ok_or(VerifyError::InvalidSignature)?;
+        let e = scalar_from_slice(&bytes[48..80]).ok_or(VerifyError::InvalidSignature)?;
+        let s = scalar_from_slice(&bytes[80..112]).ok_or(VerifyError::InvalidSignature)?;
+        Ok(Self { a, e, s })
+    }
+
+    /// Proof of knowledge of (A, e, s) and the hidden messages following
+    /// Camenisch-Drijvers-Lehmann (2016), Section 4.5:
+    /// A' = A*r1, Abar = A'*(-e) + B*r1, d = B*r1 - h0*r2, r3 = 1/r1,
+    /// s' = s - r2*r3, proving Abar - d = A'*(-e) + h0*r2 and
+    /// g1 + sum(hi*mi disclosed) = d*r3 - h0*s' - sum(hj*mj hidden).
+    /// The last message is the nym secret and must stay hidden, otherwise
+    /// proving fails with NymSecretNotHidden.
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove(
+        &self,
+        public_key: &BbsPublicKey,
+        messages: &[Vec<u8>],
+        revealed_indices: &[u32],
+        nonce: Option<&[u8]>,
+        site_id: &[u8],
+        epoch: Option<&[u8]>,
+        alias_index: u64,
+        freshness_claim: Option<&[u8]>,
+    ) -> Result<BbsProof, VerifyError> {
+        self.prove_with_rng(
+            public_key,
+            messages,
+            revealed_indices,
+            nonce,
+            site_id,
+            epoch,
+            alias_index,
+            freshness_claim,
+            &mut PeriwinkleRng,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        public_key: &BbsPublicKey,
+        messages: &[Vec<u8>],
+        revealed_indices: &[u32],
+        nonce: Option<&[u8]>,
+        site_id: &[u8],
+        epoch: Option<&[u8]>,
+        alias_index: u64,
+        freshness_claim: Option<&[u8]>,
+        rng: &mut R,
+    ) -> Result<BbsProof, VerifyError> {
+        self.prove_with_predicates_and_rng(
+            public_key,
+            messages,
+            revealed_indices,
+            &[],
+            nonce,
+            site_id,
+            epoch,
+            alias_index,
+            freshness_claim,
+            rng,
+        )
+    }
+
+    /// `prove` plus a range proof for each predicate. Predicate indexes must
+    /// be hidden and hold integer-encoded messages satisfying the bounds.
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove_with_predicates(
+        &self,
+        public_key: &BbsPublicKey,
+        messages: &[Vec<u8>],
+        revealed_indices: &[u32],
+        predicates: &[RangePredicate],
+        nonce: Option<&[u8]>,
+        site_id: &[u8],
+        epoch: Option<&[u8]>,
+        alias_index: u64,
+        freshness_claim: Option<&[u8]>,
+    ) -> Result<BbsProof, VerifyError> {
+        self.prove_with_predicates_and_rng(
+            public_key,
+            messages,
+            revealed_indices,
+            predicates,
+            nonce,
+            site_id,
+            epoch,
+            alias_index,
+            freshness_claim,
+            &mut PeriwinkleRng,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn prove_with_predicates_and_rng<R: RngCore + CryptoRng>(
+        &self,
+        public_key: &BbsPublicKey,
+        messages: &[Vec<u8>],
+        revealed_indices: &[u32],
+        predicates: &[RangePredicate],
+        nonce: Option<&[u8]>,
+        site_id: &[u8],
+        epoch: Option<&[u8]>,
+        alias_index: u64,
+        freshness_claim: Option<&[u8]>,
+        rng: &mut R,
+    ) -> Result<BbsProof, VerifyError> {
+        let mut challenge_data = vec![PROOF_VERSION];
+        let witness = self.commit_proof(
+            public_key,
+            messages,
+            revealed_indices,
+            predicates,
+            &NymBinding::Pseudonym(pseudonym_base(site_id, epoch)),
+            None,
+            &HashMap::new(),
+            &mut challenge_data,
+            rng,
+        )?;
+        append_proof_context(&mut challenge_data, nonce.unwrap_or_default(), alias_index, freshness_claim);
+        let challenge = hash_to_scalar(&challenge_data);
+        Ok(witness.respond(challenge))
+    }
+
+    /// Commitment phase: randomizes the signature, commits to every witness
+    /// and appends this credential's part of the challenge input.
+    /// `shared_m_tilde` fixes the blinding of selected hidden messages so
+    /// equal messages across credentials get equal responses.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn commit_proof<R: RngCore + CryptoRng>(
+        &self,
+        public_key: &BbsPublicKey,
+        messages: &[Vec<u8>],
+        revealed_indices: &[u32],
+        predicates: &[RangePredicate],
+        nym: &NymBinding,
+        revocation: Option<&NonRevocationClaim>,
+        shared_m_tilde: &HashMap<usize, Scalar>,
+        out: &mut Vec<u8>,
+        rng: &mut R,
+    ) -> Result<ProofWitness, VerifyError> {
+        if public_key.generators.len() < messages.len() + 1 {
+            return Err(VerifyError::InvalidKey);
+        }
+        let h = &public_key.generators;
+        let msg_scalars = messages_to_scalars(messages);
+        let hidden = hidden_indices(messages.len(), revealed_indices)?;
+        let mut disclosed: Vec<(usize, Scalar)> = revealed_indices
+            .iter()
+            .map(|&i| (i as usize, msg_scalars[i as usize]))
+            .collect();
+        disclosed.sort_by_key(|(i, _)| *i);
+
+        // Randomize the signature
+        let r1 = random_scalar(rng)?;
+        let r2 = random_scalar(rng)?;
+        let r3 = r1.invert().into_option().ok_or(VerifyError::CryptoError)?;
+        let b = public_key.commitment(self.s, &msg_scalars).to_affine();
+        let mut randomized = [G1Affine::identity(); 3];
+        G1Projective::batch_normalize(
+            &[
+                Backend::lincomb(&[self.a], &[r1]),
+                Backend::lincomb(&[b, self.a], &[r1, -(r1 * self.e)]),
+                Backend::lincomb(&[b, h[0]], &[r1, -r2]),
+            ],
+            &mut randomized,
+        );
+        let [a_prime, abar, d] = randomized;
+
+        // Commitments
+        let e_tilde = random_scalar(rng)?;
+        let r2_tilde = random_scalar(rng)?;
+        let r3_tilde = random_scalar(rng)?;
+        let s_tilde = random_scalar(rng)?;
+        let m_tilde = hidden
+            .iter()
+            .map(|j| shared_m_tilde.get(j).copied().map_or_else(|| random_scalar(rng), Ok))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let t1 = Backend::lincomb(&[a_prime, h[0]], &[-e_tilde, r2_tilde]);
+        let mut points = vec![d, h[0]];
+        let mut scalars = vec![r3_tilde, -s_tilde];
+        for (j, m) in hidden.iter().zip(&m_tilde) {
+            points.push(h[j + 1]);
+            scalars.push(-m);
+        }
+        let t2 = Backend::lincomb(&points, &scalars);
+
+        let mut predicate_transcript = Vec::new();
+        let mut range_witnesses = Vec::with_capacity(predicates.len());
+        for predicate in predicates {
+            let index = predicate.index;
+            let message = messages
+                .get(index as usize)
+                .ok_or(VerifyError::RevealedIndexOutOfRange { index, message_count: messages.len() as u64 })?;
+            let pos = hidden
+                .iter()
+                .position(|&j| j == index as usize)
+                .ok_or(VerifyError::PredicateOnRevealedMessage { index })?;
+            let value = decode_integer_message(message).ok_or(VerifyError::NotAnIntegerMessage { index })?;
+            range_witnesses.push(RangeWitness::commit(predicate, value, m_tilde[pos], &mut predicate_transcript, rng)?);
+        }
+
+        let nym_pos = messages
+            .len()
+            .checked_sub(1)
+            .and_then(|nym_index| hidden.iter().position(|&j| j == nym_index))
+            .ok_or(VerifyError::NymSecretNotHidden)?;
+        let nym_secret = msg_scalars[hidden[nym_pos]];
+
+        let mut t = [G1Affine::identity(); 2];
+        G1Projective::batch_normalize(&[t1, t2], &mut t);
+        let points = [a_prime, abar, d, t[0], t[1]];
+        public_key.append_commitments(&points, messages.len(), &disclosed, &predicate_transcript, out);
+        let (tag, nullifier) = match nym {
+            NymBinding::Pseudonym(base) => {
+                // Pseudonym = OP*nym_secret, Ut = OP*nym~
+                let mut p = [G1Affine::identity(); 2];
+                G1Projective::batch_normalize(
+                    &[Backend::lincomb(&[*base], &[nym_secret]), Backend::lincomb(&[*base], &[m_tilde[nym_pos]])],
+                    &mut p,
+                );
+                append_pseudonym(out, &p[0], base, &p[1]);
+                (p[0], None)
+            }
+            NymBinding::Nullifier { rate_limit, counter } => {
+                let witness = rate_limit.commit(nym_secret, m_tilde[nym_pos], *counter, out, rng)?;
+                (witness.nullifier(), Some(witness))
+            }
+        };
+        let non_revocation = match revocation {
+            Some(claim) => {
+                let pos = hidden.iter().position(|&j| j == claim.index).ok_or(VerifyError::InvalidSignature)?;
+                Some(claim.commit(msg_scalars[claim.index], m_tilde[pos], out, rng)?)
+            }
+            None => None,
+        };
+
+        Ok(ProofWitness {
+            a_prime: points[0],
+            abar: points[1],
+            d: points[2],
+            e: self.e,
+            r2,
+            r3,
+            s_prime: self.s - r2 * r3,
+            e_tilde,
+            r2_tilde,
+            r3_tilde,
+            s_tilde,
+            hidden_messages: hidden.iter().map(|j| msg_scalars[*j]).collect(),
+            m_tilde,
+            range_witnesses,
+            tag,
+            nullifier,
+            non_revocation,
+        })
+    }
+}
+
+/// Prover state between `commit_proof` and the challenge
+pub(crate) struct ProofWitness {
+    a_prime: G1Affine,
+    abar: G1Affine,
+    d: G1Affine,
+    e: Scalar,
+    r2: Scalar,
+    r3: Scalar,
+    s_prime: Scalar,
+    e_tilde: Scalar,
+    r2_tilde: Scalar,
+    r3_tilde: Scalar,
+    s_tilde: Scalar,
+    hidden_messages: Vec<Scalar>,
+    m_tilde: Vec<Scalar>,
+    range_witnesses: Vec<RangeWitness>,
+    tag: G1Affine,
+    nullifier: Option<NullifierWitness>,
+    non_revocation: Option<NonRevocationWitness>,
+}
+
+impl ProofWitness {
+    pub(crate) fn respond(&self, challenge: Scalar) -> BbsProof {
+        let m_hat = self
+            .m_tilde
+            .iter()
+            .zip(&self.hidden_messages)
+            .map(|(m_t, m)| m_t + challenge * m)
+            .collect();
+        BbsProof {
+            a_prime: self.a_prime,
+            abar: self.abar,
+            d: self.d,
+            e_hat: self.e_tilde + challenge * self.e,
+            r2_hat: self.r2_tilde + challenge * self.r2,
+            r3_hat: self.r3_tilde + challenge * self.r3,
+            s_hat: self.s_tilde + challenge * self.s_prime,
+            challenge,
+            tag: self.tag,
+            m_hat,
+            nullifier_proof: self.nullifier.as_ref().map(|w| w.respond(challenge)),
+            non_revocation_proof: self.non_revocation.as_ref().map(|w| w.respond(challenge)),
+            range_proofs: self.range_witnesses.iter().map(|w| w.respond(challenge)).collect(),
+        }
+    }
+}
+
+// ============================================================================
+// Proof
+// ============================================================================
+
+/// Leading byte of every serialized proof. Compressed G1 points always have
+/// the top bit set, so unversioned proofs from earlier releases cannot match.
+/// Version 0x03 replaced the linkage tag with a proven pseudonym, 0x04 added
+/// rate-limited proofs and 0x05 non-revocation proofs, both flagged in the
+/// byte after the tag.
+pub const PROOF_VERSION: u8 = 0x05;
+
+const PROOF_HEADER_LEN: usize = 1 + 48 * 3 + 32 * 5 + 48 + 1 + 4;
+
+const FLAG_NULLIFIER: u8 = 0x01;
+const FLAG_NON_REVOCATION: u8 = 0x02;
+
+/// version || A' || Abar || d || e^ || r2^ || r3^ || s^ || c || tag || flags
+/// || u32 LE count || m^ for each hidden message, then the nullifier proof
+/// and the non-revocation proof when flagged, optionally followed by
+/// u32 LE count || range proofs when the proof carries predicates. The tag is
+/// the nullifier when FLAG_NULLIFIER is set, the pseudonym otherwise.
+#[derive(uniffi::Object)]
+pub struct BbsProof {
+    a_prime: G1Affine,
+    abar: G1Affine,
+    d: G1Affine,
+    e_hat: Scalar,
+    r2_hat: Scalar,
+    r3_hat: Scalar,
+    s_hat: Scalar,
+    challenge: Scalar,
+    tag: G1Affine,
+    m_hat: Vec<Scalar>,
+    nullifier_proof: Option<NullifierProof>,
+    non_revocation_proof: Option<NonRevocationProof>,
+    range_proofs: Vec<RangeProof>,
+}
+
+#[uniffi::export]
+impl BbsProof {
+    #[uniffi::constructor]
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Arc<Self>, VerifyError> {
+        Self::parse(&bytes).map(Arc::new)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut proof = Vec::with_capacity(PROOF_HEADER_LEN + 32 * self.m_hat.len());
+        proof.push(PROOF_VERSION);
+        proof.extend_from_slice(&self.a_prime.to_compressed());
+        proof.extend_from_slice(&self.abar.to_compressed());
+        proof.extend_from_slice(&self.d.to_compressed());
+        for x in [&self.e_hat, &self.r2_hat, &self.r3_hat, &self.s_hat, &self.challenge] {
+            proof.extend_from_slice(&x.to_bytes());
+        }
+        proof.extend_from_slice(&self.tag.to_compressed());
+        let mut flags = 0;
+        if self.nullifier_proof.is_some() {
+            flags |= FLAG_NULLIFIER;
+        }
+        if self.non_revocation_proof.is_some() {
+            flags |= FLAG_NON_REVOCATION;
+        }
+        proof.push(flags);
+        proof.extend_from_slice(&(self.m_hat.len() as u32).to_le_bytes());
+        for m in &self.m_hat {
+            proof.extend_from_slice(&m.to_bytes());
+        }
+        if let Some(nullifier_proof) = &self.nullifier_proof {
+            nullifier_proof.write(&mut proof);
+        }
+        if let Some(non_revocation_proof) = &self.non_revocation_proof {
+            non_revocation_proof.write(&mut proof);
+        }
+        if !self.range_proofs.is_empty() {
+            proof.extend_from_slice(&(self.range_proofs.len() as u32).to_le_bytes());
+            for range_proof in &self.range_proofs {
+                range_proof.write(&mut proof);
+            }
+        }
+        proof
+    }
+
+    /// Compressed G1 pseudonym: the same for every proof from one nym
+    /// secret at one site, unlinkable across sites. None for rate-limited
+    /// proofs.
+    pub fn pseudonym(&self) -> Option<Vec<u8>> {
+        self.nullifier_proof.is_none().then(|| self.tag.to_compressed().to_vec())
+    }
+
+    /// Compressed G1 nullifier of a rate-limited proof
+    pub fn nullifier(&self) -> Option<Vec<u8>> {
+        self.nullifier_proof.as_ref().map(|_| self.tag.to_compressed().to_vec())
+    }
+}
+
+impl BbsProof {
+    /// Response for hidden message `index`, given the hidden indexes in order
+    pub(crate) fn hidden_response(&self, hidden: &[usize], index: usize) -> Option<Scalar> {
+        let pos = hidden.iter().position(|&j| j == index)?;
+        self.m_hat.get(pos).copied()
+    }
+
+    pub(crate) fn challenge(&self) -> Scalar {
+        self.challenge
+    }
+
+    /// Pairing check as e(x, w) * e(y, g2) == 1 with x = A', y = -Abar
+    pub(crate) fn pairing_terms(&self) -> (G1Affine, G1Projective) {
+        (self.a_prime, -G1Projective::from(self.abar))
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, VerifyError> {
+        match bytes.first() {
+            Some(&PROOF_VERSION) => {}
+            Some(_) => return Err(VerifyError::UnsupportedProofVersion),
+            None => return Err(VerifyError::proof_structure("version")),
+        }
+        if bytes.len() < PROOF_HEADER_LEN {
+            return Err(VerifyError::proof_structure("length"));
+        }
+        let point = |start: usize, field: &str| {
+            g1_from_slice(&bytes[start..start + 48]).ok_or_else(|| VerifyError::proof_structure(field))
+        };
+        let scalar = |start: usize, field: &str| {
+            scalar_from_slice(&bytes[start..start + 32]).ok_or_else(|| VerifyError::proof_structure(field))
+        };
+
+        let a_prime = point(1, "a_prime")?;
+        let abar = point(49, "abar")?;
+        let d = point(97, "d")?;
+        let e_hat = scalar(145, "e_hat")?;
+        let r2_hat = scalar(177, "r2_hat")?;
+        let r3_hat = scalar(209, "r3_hat")?;
+        let s_hat = scalar(241, "s_hat")?;
+        let challenge = scalar(273, "challenge")?;
+        let tag = point(305, "tag")?;
+        let flags = bytes[353];
+        if flags & !(FLAG_NULLIFIER | FLAG_NON_REVOCATION) != 0 {
+            return Err(VerifyError::proof_structure("flags"));
+        }
+        let count_bytes: [u8; 4] = bytes[354..358].try_into().map_err(|_| VerifyError::proof_structure("length"))?;
+        let response_count = u32::from_le_bytes(count_bytes) as usize;
+        if response_count > (bytes.len() - PROOF_HEADER_LEN) / 32 {
+            return Err(VerifyError::proof_structure("m_hat"));
+        }
+        let m_hat = (0..response_count)
+            .map(|i| scalar(PROOF_HEADER_LEN + 32 * i, "m_hat"))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut rest = &bytes[PROOF_HEADER_LEN + 32 * response_count..];
+        let mut nullifier_proof = None;
+        if flags & FLAG_NULLIFIER != 0 {
+            let (section, used) =
+                NullifierProof::read(rest).map_err(|_| VerifyError::proof_structure("nullifier_proof"))?;
+            nullifier_proof = Some(section);
+            rest = &rest[used..];
+        }
+        let mut non_revocation_proof = None;
+        if flags & FLAG_NON_REVOCATION != 0 {
+            let (section, used) =
+                NonRevocationProof::read(rest).map_err(|_| VerifyError::proof_structure("non_revocation_proof"))?;
+            non_revocation_proof = Some(section);
+            rest = &rest[used..];
+        }
+
+        // Whatever follows must be exactly the range proofs
+        let mut range_proofs = Vec::new();
+        if !rest.is_empty() {
+            let malformed = || VerifyError::proof_structure("range_proofs");
+            let count_bytes: [u8; 4] = rest.get(..4).and_then(|b| b.try_into().ok()).ok_or_else(malformed)?;
+            let range_count = u32::from_le_bytes(count_bytes) as usize;
+            rest = &rest[4..];
+            if range_count == 0 {
+                return Err(malformed());
+            }
+            for _ in 0..range_count {
+                let (range_proof, used) = RangeProof::read(rest).map_err(|_| malformed())?;
+                range_proofs.push(range_proof);
+                rest = &rest[used..];
+            }
+            if !rest.is_empty() {
+                return Err(VerifyError::proof_structure("trailing_bytes"));
+            }
+        }
+
+        Ok(Self { a_prime, abar, d, e_hat, r2_hat, r3_hat, s_hat, challenge, tag, m_hat, nullifier_proof, non_revocation_proof, range_proofs })
+    }
+}
@@ -0,0 +1,218 @@
+// Blind Issuance
+// ==============
+// The holder commits to messages the issuer must not see (e.g. a link
+// secret) as C = h0*s' + sum(hj*mj) and proves knowledge of the opening.
+// The issuer checks the proof, adds its own messages and signs
+// B = g1 + h0*s'' + C + sum(hi*mi). The holder unblinds with s = s' + s''.
+
+use crate::backend::{Backend, CurveBackend};
+use crate::bbs::{message_to_scalar, BbsPublicKey, BbsSecretKey, BbsSignature};
+use crate::{hash_to_scalar, random_scalar, VerifyError};
+use bls12_381::{G1Affine, Scalar};
+use group::Curve;
+use rand::{CryptoRng, RngCore};
+use std::collections::HashSet;
+
+const COMMITMENT_DST: &[u8] = b"MULTIPASS_BLIND_COMMITMENT_";
+
+/// A message together with its position in the signed message list
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct IndexedMessage {
+    pub index: u32,
+    pub message: Vec<u8>,
+}
+
+/// Holder output of `blind_commitment`. Only `commitment` goes to the
+/// issuer; `blinding_factor` stays with the holder until unblinding.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct BlindCommitment {
+    pub commitment: Vec<u8>,
+    pub blinding_factor: Vec<u8>,
+}
+
+/// C || c || s^ || u32 LE count || (u32 LE index || m^) per committed message
+pub(crate) struct Commitment {
+    c: G1Affine,
+    challenge: Scalar,
+    s_hat: Scalar,
+    responses: Vec<(u32, Scalar)>,
+}
+
+fn scalar_from_slice(bytes: &[u8], field: &str) -> Result<Scalar, VerifyError> {
+    let arr: [u8; 32] = bytes.try_into().map_err(|_| VerifyError::proof_structure(field))?;
+    Scalar::from_bytes(&arr).into_option().ok_or_else(|| VerifyError::proof_structure(field))
+}
+
+/// Binds the issuer key (w and every generator) so a commitment made for
+/// one key cannot be replayed against another
+fn commitment_challenge(
+    public_key: &BbsPublicKey,
+    c: &G1Affine,
+    t: &G1Affine,
+    indexes: &[u32],
+    nonce: &[u8],
+) -> Scalar {
+    let mut data = COMMITMENT_DST.to_vec();
+    data.extend_from_slice(&public_key.to_bytes());
+    data.extend_from_slice(&c.to_compressed());
+    data.extend_from_slice(&t.to_compressed());
+    data.extend_from_slice(&(indexes.len() as u64).to_be_bytes());
+    for i in indexes {
+        data.extend_from_slice(&i.to_be_bytes());
+    }
+    data.extend_from_slice(&(nonce.len() as u64).to_be_bytes());
+    data.extend_from_slice(nonce);
+    hash_to_scalar(&data)
+}
+
+/// Rejects duplicate indexes and indexes without a generator
+fn check_indexes<'a>(
+    public_key: &BbsPublicKey,
+    indexes: impl Iterator<Item = &'a u32>,
+) -> Result<HashSet<u32>, VerifyError> {
+    let mut seen = HashSet::new();
+    for &i in indexes {
+        if i >= public_key.message_count() {
+            return Err(VerifyError::RevealedIndexOutOfRange {
+                index: i,
+                message_count: public_key.message_count() as u64,
+            });
+        }
+        if !seen.insert(i) {
+            return Err(VerifyError::proof_structure("indexes"));
+        }
+    }
+    Ok(seen)
+}
+
+impl Commitment {
+    /// Commits to `hidden` and returns the commitment with its blinding s'
+    pub(crate) fn create<R: RngCore + CryptoRng>(
+        public_key: &BbsPublicKey,
+        hidden: &[IndexedMessage],
+        nonce: &[u8],
+        rng: &mut R,
+    ) -> Result<(Self, Scalar), VerifyError> {
+        check_indexes(public_key, hidden.iter().map(|m| &m.index))?;
+        let h = public_key.generators();
+
+        let s_prime = random_scalar(rng)?;
+        let s_tilde = random_scalar(rng)?;
+        let m_tilde = hidden.iter().map(|_| random_scalar(rng)).collect::<Result<Vec<_>, _>>()?;
+
+        let mut points = vec![h[0]];
+        points.extend(hidden.iter().map(|msg| h[msg.index as usize + 1]));
+        let mut c_scalars = vec![s_prime];
+        c_scalars.extend(hidden.iter().map(|msg| message_to_scalar(&msg.message)));
+        let mut t_scalars = vec![s_tilde];
+        t_scalars.extend_from_slice(&m_tilde);
+        let c = Backend::lincomb(&points, &c_scalars).to_affine();
+        let t = Backend::lincomb(&points, &t_scalars);
+
+        let indexes: Vec<u32> = hidden.iter().map(|m| m.index).collect();
+        let challenge = commitment_challenge(public_key, &c, &t.to_affine(), &indexes, nonce);
+        let responses = hidden
+            .iter()
+            .zip(&m_tilde)
+            .map(|(msg, m_t)| (msg.index, m_t + challenge * message_to_scalar(&msg.message)))
+            .collect();
+
+        let commitment = Self { c, challenge, s_hat: s_tilde + challenge * s_prime, responses };
+        Ok((commitment, s_prime))
+    }
+
+    /// Checks the proof of knowledge: T = h0*s^ + sum(hj*mj^) - C*c
+    pub(crate) fn verify(&self, public_key: &BbsPublicKey, nonce: &[u8]) -> Result<bool, VerifyError> {
+        check_indexes(public_key, self.responses.iter().map(|(i, _)| i))?;
+        if bool::from(self.c.is_identity()) {
+            return Ok(false);
+        }
+        let h = public_key.generators();
+        let mut points = vec![h[0], self.c];
+        let mut scalars = vec![self.s_hat, -self.challenge];
+        for (i, m_hat) in &self.responses {
+            points.push(h[*i as usize + 1]);
+            scalars.push(*m_hat);
+        }
+        let t = Backend::msm_vartime(&points, &scalars);
+        let indexes: Vec<u32> = self.responses.iter().map(|(i, _)| *i).collect();
+        Ok(commitment_challenge(public_key, &self.c, &t.to_affine(), &indexes, nonce) == self.challenge)
+    }
+
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(116 + 36 * self.responses.len());
+        bytes.extend_from_slice(&self.c.to_compressed());
+        bytes.extend_from_slice(&self.challenge.to_bytes());
+        bytes.extend_from_slice(&self.s_hat.to_bytes());
+        bytes.extend_from_slice(&(self.responses.len() as u32).to_le_bytes());
+        for (i, m_hat) in &self.responses {
+            bytes.extend_from_slice(&i.to_le_bytes());
+            bytes.extend_from_slice(&m_hat.to_bytes());
+        }
+        bytes
+    }
+
+    pub(crate) fn parse(bytes: &[u8]) -> Result<Self, VerifyError> {
+        if bytes.len() < 116 {
+            return Err(VerifyError::proof_structure("length"));
+        }
+        let c_bytes: [u8; 48] = bytes[..48].try_into().map_err(|_| VerifyError::proof_structure("commitment"))?;
+        let c = G1Affine::from_compressed(&c_bytes).into_option().ok_or_else(|| VerifyError::proof_structure("commitment"))?;
+        let challenge = scalar_from_slice(&bytes[48..80], "challenge")?;
+        let s_hat = scalar_from_slice(&bytes[80..112], "s_hat")?;
+        let count_bytes: [u8; 4] = bytes[112..116].try_into().map_err(|_| VerifyError::proof_structure("length"))?;
+        let count = u32::from_le_bytes(count_bytes) as usize;
+        if bytes.len() - 116 != 36 * count {
+            return Err(VerifyError::proof_structure("length"));
+        }
+        let responses = bytes[116..]
+            .chunks(36)
+            .map(|chunk| {
+                let index = u32::from_le_bytes(chunk[..4].try_into().map_err(|_| VerifyError::proof_structure("responses"))?);
+                Ok((index, scalar_from_slice(&chunk[4..], "responses")?))
+            })
+            .collect::<Result<Vec<_>, VerifyError>>()?;
+        Ok(Self { c, challenge, s_hat, responses })
+    }
+}
+
+/// Issuer side: verifies the holder's commitment and signs it together with
+/// `known` messages. Committed and known indexes must be disjoint and
+/// together cover 0..n with no gaps.
+pub(crate) fn blind_sign<R: RngCore + CryptoRng>(
+    secret_key: &BbsSecretKey,
+    public_key: &BbsPublicKey,
+    commitment: &Commitment,
+    nonce: &[u8],
+    known: &[IndexedMessage],
+    rng: &mut R,
+) -> Result<BbsSignature, VerifyError> {
+    if !commitment.verify(public_key, nonce)? {
+        return Err(VerifyError::ChallengeMismatch);
+    }
+    let all_indexes = commitment.responses.iter().map(|(i, _)| i).chain(known.iter().map(|m| &m.index));
+    let indexes = check_indexes(public_key, all_indexes)?;
+    if indexes.iter().any(|&i| i as usize >= indexes.len()) {
+        return Err(VerifyError::proof_structure("indexes"));
+    }
+
+    let h = public_key.generators();
+    let points: Vec<G1Affine> = known.iter().map(|msg| h[msg.index as usize + 1]).collect();
+    let scalars: Vec<Scalar> = known.iter().map(|msg| message_to_scalar(&msg.message)).collect();
+    let message_part = Backend::lincomb(&points, &scalars) + commitment.c;
+    secret_key.sign_message_part(public_key, message_part, rng)
+}
+
+/// Holder side: s = s' + s'', then checks the result over the full message list
+pub(crate) fn unblind(
+    public_key: &BbsPublicKey,
+    blind_signature: &BbsSignature,
+    blinding_factor: Scalar,
+    messages: &[Vec<u8>],
+) -> Result<BbsSignature, VerifyError> {
+    let signature = blind_signature.unblind(blinding_factor);
+    if !public_key.verify_signature(&signature, messages)? {
+        return Err(VerifyError::InvalidSignature);
+    }
+    Ok(signature)
+}
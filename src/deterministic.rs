@@ -0,0 +1,129 @@
+// Deterministic Test Mode
+// =======================
+// Every randomized operation has a `_with_rng` variant; the default functions
+// draw from the hardware source (`PeriwinkleRng`). This module drives them
+// from ChaCha20 keyed with SHA-256(DST || seed), so the same seed always
+// gives the same keys, signatures and proofs. The Kotlin and Swift bindings
+// use it to check their results against `known_answer_vectors`.
+//
+// A seeded output is only as secret as its seed: never use these functions
+// for production keys or presentations.
+
+use crate::{
+    create_proof_with_rng, generate_nym_secret_with_rng, generate_secret_key_with_rng, get_public_key, sign_with_rng,
+    VerifyError,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+const SEED_DST: &[u8] = b"MULTIPASS_DETERMINISTIC_RNG_";
+
+/// Messages and disclosure used by `known_answer_vectors`
+const KAT_MESSAGES: [&[u8]; 4] = [b"alice", b"1990-01-01", b"US", b"member"];
+const KAT_REVEALED: [u32; 2] = [0, 2];
+const KAT_NONCE: &[u8] = b"kat-nonce";
+const KAT_SITE_ID: &[u8] = b"kat.example";
+
+/// ChaCha20 stream for `seed`. Test use only.
+pub fn seeded_rng(seed: &[u8]) -> ChaCha20Rng {
+    let mut hasher = Sha256::new();
+    hasher.update(SEED_DST);
+    hasher.update(seed);
+    ChaCha20Rng::from_seed(hasher.finalize().into())
+}
+
+// ============================================================================
+// Exported API
+// ============================================================================
+
+/// `generate_secret_key` drawing from `seed`. Test use only.
+#[uniffi::export]
+pub fn generate_secret_key_seeded(seed: Vec<u8>) -> Result<Vec<u8>, VerifyError> {
+    generate_secret_key_with_rng(&mut seeded_rng(&seed))
+}
+
+/// `generate_nym_secret` drawing from `seed`. Test use only.
+#[uniffi::export]
+pub fn generate_nym_secret_seeded(seed: Vec<u8>) -> Result<Vec<u8>, VerifyError> {
+    generate_nym_secret_with_rng(&mut seeded_rng(&seed))
+}
+
+/// `sign` drawing from `seed`. Test use only.
+#[uniffi::export]
+pub fn sign_seeded(
+    seed: Vec<u8>,
+    secret_key: Vec<u8>,
+    public_key: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+) -> Result<Vec<u8>, VerifyError> {
+    sign_with_rng(secret_key, public_key, messages, &mut seeded_rng(&seed))
+}
+
+/// `create_proof` drawing from `seed`. Test use only.
+#[uniffi::export]
+#[allow(clippy::too_many_arguments)]
+pub fn create_proof_seeded(
+    seed: Vec<u8>,
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+    revealed_indices: Vec<u32>,
+    nonce: Option<Vec<u8>>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+) -> Result<Vec<u8>, VerifyError> {
+    create_proof_with_rng(
+        public_key,
+        signature,
+        messages,
+        revealed_indices,
+        nonce,
+        site_id,
+        epoch,
+        alias_index,
+        freshness_claim,
+        &mut seeded_rng(&seed),
+    )
+}
+
+/// JSON known-answer vectors for `seed`: a key pair, a signature over four
+/// fixed messages, a proof revealing messages 0 and 2 and a nym secret, all
+/// drawn in that order from one seeded stream. Byte strings are hex.
+#[uniffi::export]
+pub fn known_answer_vectors(seed: Vec<u8>) -> Result<String, VerifyError> {
+    let mut rng = seeded_rng(&seed);
+    let messages: Vec<Vec<u8>> = KAT_MESSAGES.iter().map(|m| m.to_vec()).collect();
+    let secret_key = generate_secret_key_with_rng(&mut rng)?;
+    let public_key = get_public_key(secret_key.clone(), messages.len() as u32)?;
+    let signature = sign_with_rng(secret_key.clone(), public_key.clone(), messages.clone(), &mut rng)?;
+    let proof = create_proof_with_rng(
+        public_key.clone(),
+        signature.clone(),
+        messages.clone(),
+        KAT_REVEALED.to_vec(),
+        Some(KAT_NONCE.to_vec()),
+        KAT_SITE_ID.to_vec(),
+        None,
+        0,
+        None,
+        &mut rng,
+    )?;
+    let nym_secret = generate_nym_secret_with_rng(&mut rng)?;
+    let vectors = json!({
+        "seed": hex::encode(&seed),
+        "secret_key": hex::encode(secret_key),
+        "public_key": hex::encode(public_key),
+        "messages": messages.iter().map(hex::encode).collect::<Vec<_>>(),
+        "signature": hex::encode(signature),
+        "revealed_indices": KAT_REVEALED,
+        "nonce": hex::encode(KAT_NONCE),
+        "site_id": hex::encode(KAT_SITE_ID),
+        "proof": hex::encode(proof),
+        "nym_secret": hex::encode(nym_secret),
+    });
+    Ok(vectors.to_string())
+}
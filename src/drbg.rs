@@ -0,0 +1,141 @@
+// HMAC_DRBG (NIST SP 800-90A Rev. 1, section 10.1.2) with HMAC-SHA-256
+// ======================================================================
+// Deterministic generator behind the periwinkle harvester. The caller
+// supplies entropy: instantiate takes entropy || nonce, reseed and
+// prediction-resistant generate take fresh entropy. `generate` refuses to
+// run past the reseed interval, so every reseed is explicit and counted.
+
+use crate::periwinkle::EntropyError;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Security strength of HMAC_DRBG with SHA-256, in bytes
+pub const SECURITY_STRENGTH: usize = 32;
+
+/// Largest reseed interval SP 800-90A allows (2^48 requests)
+pub const MAX_RESEED_INTERVAL: u64 = 1 << 48;
+
+/// Largest generate request (2^19 bits)
+pub const MAX_REQUEST_BYTES: usize = 1 << 16;
+
+const OUT_LEN: usize = 32;
+
+pub struct HmacDrbg {
+    key: [u8; OUT_LEN],
+    v: [u8; OUT_LEN],
+    reseed_counter: u64,
+    reseed_interval: u64,
+    reseeds: u64,
+}
+
+impl std::fmt::Debug for HmacDrbg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HmacDrbg")
+            .field("reseed_counter", &self.reseed_counter)
+            .field("reseed_interval", &self.reseed_interval)
+            .field("reseeds", &self.reseeds)
+            .finish_non_exhaustive()
+    }
+}
+
+fn hmac(key: &[u8], parts: &[&[u8]]) -> [u8; OUT_LEN] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes any key length");
+    for part in parts {
+        mac.update(part);
+    }
+    mac.finalize().into_bytes().into()
+}
+
+impl HmacDrbg {
+    /// Instantiate with the maximum reseed interval. `entropy` carries at
+    /// least `SECURITY_STRENGTH` bytes; `nonce` at least half that.
+    pub fn instantiate(entropy: &[u8], nonce: &[u8], personalization: &[u8]) -> Result<Self, EntropyError> {
+        Self::with_reseed_interval(entropy, nonce, personalization, MAX_RESEED_INTERVAL)
+    }
+
+    pub fn with_reseed_interval(
+        entropy: &[u8],
+        nonce: &[u8],
+        personalization: &[u8],
+        reseed_interval: u64,
+    ) -> Result<Self, EntropyError> {
+        check_entropy(entropy)?;
+        let mut drbg = Self {
+            key: [0x00; OUT_LEN],
+            v: [0x01; OUT_LEN],
+            reseed_counter: 1,
+            reseed_interval: reseed_interval.clamp(1, MAX_RESEED_INTERVAL),
+            reseeds: 0,
+        };
+        drbg.update(&[entropy, nonce, personalization]);
+        Ok(drbg)
+    }
+
+    /// HMAC_DRBG_Update over the concatenation of `provided`
+    fn update(&mut self, provided: &[&[u8]]) {
+        let empty = provided.iter().all(|p| p.is_empty());
+        for round in [0x00u8, 0x01] {
+            let round = [round];
+            let mut parts: Vec<&[u8]> = vec![&self.v, &round];
+            parts.extend_from_slice(provided);
+            self.key = hmac(&self.key, &parts);
+            self.v = hmac(&self.key, &[&self.v]);
+            if empty {
+                break;
+            }
+        }
+    }
+
+    pub fn reseed(&mut self, entropy: &[u8], additional: &[u8]) -> Result<(), EntropyError> {
+        check_entropy(entropy)?;
+        self.update(&[entropy, additional]);
+        self.reseed_counter = 1;
+        self.reseeds += 1;
+        Ok(())
+    }
+
+    /// Fills `out`. Fails with ReseedRequired once the reseed interval is
+    /// used up; the caller reseeds and retries.
+    pub fn generate(&mut self, out: &mut [u8], additional: &[u8]) -> Result<(), EntropyError> {
+        if out.len() > MAX_REQUEST_BYTES {
+            return Err(EntropyError::RequestTooLarge { max_bytes: MAX_REQUEST_BYTES as u64 });
+        }
+        if self.reseed_counter > self.reseed_interval {
+            return Err(EntropyError::ReseedRequired);
+        }
+        if !additional.is_empty() {
+            self.update(&[additional]);
+        }
+        for chunk in out.chunks_mut(OUT_LEN) {
+            self.v = hmac(&self.key, &[&self.v]);
+            chunk.copy_from_slice(&self.v[..chunk.len()]);
+        }
+        self.update(&[additional]);
+        self.reseed_counter += 1;
+        Ok(())
+    }
+
+    /// Generate requests made since the last (re)seed, plus one
+    pub fn reseed_counter(&self) -> u64 {
+        self.reseed_counter
+    }
+
+    pub fn reseed_interval(&self) -> u64 {
+        self.reseed_interval
+    }
+
+    /// Reseeds since instantiation
+    pub fn reseeds(&self) -> u64 {
+        self.reseeds
+    }
+}
+
+fn check_entropy(entropy: &[u8]) -> Result<(), EntropyError> {
+    if entropy.len() < SECURITY_STRENGTH {
+        return Err(EntropyError::Unavailable {
+            source_name: "hmac_drbg".to_string(),
+            reason: format!("{} bytes of entropy input, need {}", entropy.len(), SECURITY_STRENGTH),
+        });
+    }
+    Ok(())
+}
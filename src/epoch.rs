@@ -0,0 +1,143 @@
+// Epoch-Scoped Pseudonyms
+// =======================
+// A proof made with an epoch carries a pseudonym derived from
+// (site_id, epoch), so a relying party links a holder only within one epoch.
+// An epoch is any byte string both sides agree on: a voting round id, or a
+// time period from `EpochSchedule`. Time periods are encoded as
+// period_seconds (u64 BE) || index (u64 BE) so that schedules of different
+// lengths never share a pseudonym.
+//
+// Verifiers read the time from an injected `Clock` and only accept proofs
+// made for the epoch that is current.
+
+use crate::bbs::{BbsProof, BbsPublicKey};
+use crate::VerifyError;
+use std::num::NonZeroU64;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of the current time, in seconds since the Unix epoch
+pub trait Clock {
+    fn now(&self) -> u64;
+}
+
+/// Wall clock of the host
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+    }
+}
+
+/// Consecutive epochs of `period_seconds`, the first starting at Unix time 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpochSchedule {
+    period_seconds: NonZeroU64,
+}
+
+impl EpochSchedule {
+    pub fn new(period_seconds: NonZeroU64) -> Self {
+        Self { period_seconds }
+    }
+
+    /// One epoch per UTC day
+    pub fn daily() -> Self {
+        Self::new(NonZeroU64::new(86_400).expect("non-zero"))
+    }
+
+    /// Epoch containing `unix_seconds`
+    pub fn epoch_at(&self, unix_seconds: u64) -> Vec<u8> {
+        let period = self.period_seconds.get();
+        let mut epoch = period.to_be_bytes().to_vec();
+        epoch.extend_from_slice(&(unix_seconds / period).to_be_bytes());
+        epoch
+    }
+
+    pub fn current(&self, clock: &impl Clock) -> Vec<u8> {
+        self.epoch_at(clock.now())
+    }
+
+    pub fn is_current(&self, epoch: &[u8], clock: &impl Clock) -> bool {
+        epoch == self.current(clock).as_slice()
+    }
+
+    /// `BbsPublicKey::verify_proof_of` for a proof scoped to `epoch`, which
+    /// must be the current epoch of this schedule, else `EpochMismatch`
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_proof(
+        &self,
+        clock: &impl Clock,
+        public_key: &BbsPublicKey,
+        proof: &BbsProof,
+        total_message_count: usize,
+        revealed_indices: &[u32],
+        revealed_messages: &[Vec<u8>],
+        nonce: &[u8],
+        site_id: &[u8],
+        epoch: &[u8],
+        alias_index: u64,
+        freshness_claim: Option<&[u8]>,
+    ) -> Result<bool, VerifyError> {
+        let expected = self.current(clock);
+        if epoch != expected.as_slice() {
+            return Err(VerifyError::EpochMismatch { expected, presented: epoch.to_vec() });
+        }
+        public_key.verify_proof_of(
+            proof,
+            total_message_count,
+            revealed_indices,
+            revealed_messages,
+            nonce,
+            site_id,
+            Some(epoch),
+            alias_index,
+            freshness_claim,
+        )
+    }
+}
+
+fn schedule(period_seconds: u64) -> Result<EpochSchedule, VerifyError> {
+    NonZeroU64::new(period_seconds).map(EpochSchedule::new).ok_or(VerifyError::InvalidEpochPeriod)
+}
+
+/// Current epoch of a `period_seconds` schedule, to pass as `epoch` to
+/// `create_proof`
+#[uniffi::export]
+pub fn current_epoch(period_seconds: u64) -> Result<Vec<u8>, VerifyError> {
+    Ok(schedule(period_seconds)?.current(&SystemClock))
+}
+
+/// `verify_proof_safe` for a proof scoped to `epoch`; `EpochMismatch` unless
+/// `epoch` is the current epoch of a `period_seconds` schedule
+#[uniffi::export]
+#[allow(clippy::too_many_arguments)]
+pub fn verify_epoch_proof(
+    public_key: Vec<u8>,
+    proof: Vec<u8>,
+    total_message_count: u64,
+    revealed_indices: Vec<u32>,
+    revealed_messages_content: Vec<Vec<u8>>,
+    nonce: Vec<u8>,
+    site_id: Vec<u8>,
+    epoch: Vec<u8>,
+    period_seconds: u64,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+) -> Result<bool, VerifyError> {
+    let schedule = schedule(period_seconds)?;
+    let pk = BbsPublicKey::parse(&public_key)?;
+    let proof = BbsProof::parse(&proof)?;
+    schedule.verify_proof(
+        &SystemClock,
+        &pk,
+        &proof,
+        total_message_count as usize,
+        &revealed_indices,
+        &revealed_messages_content,
+        &nonce,
+        &site_id,
+        &epoch,
+        alias_index,
+        freshness_claim.as_deref(),
+    )
+}
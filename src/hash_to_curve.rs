@@ -0,0 +1,199 @@
+// Hashing to BLS12-381 (RFC 9380)
+// ===============================
+// expand_message_xmd / expand_message_xof, hash_to_field for scalars and
+// hash_to_curve for G1 (BLS12381G1_XMD:SHA-256_SSWU_RO_ and
+// BLS12381G1_XOF:SHAKE-256_SSWU_RO_). The simplified SWU map, the 11-isogeny
+// and cofactor clearing come from bls12_381, or from blst for the XMD suite
+// when that backend is enabled; message expansion lives here so every caller
+// goes through the same domain-separated expanders.
+
+use crate::backend::{Backend, CurveBackend};
+use bls12_381::hash_to_curve::{ExpandMessageState, HashToCurve, InitExpandMessage};
+use bls12_381::{G1Projective, Scalar};
+use sha2::{Digest, Sha256};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
+use sha3::Shake256;
+
+/// Bytes expanded per scalar: ceil((ceil(log2(r)) + k) / 8) with k = 128
+pub const SCALAR_EXPAND_LEN: usize = 48;
+
+// ============================================================================
+// expand_message (RFC 9380, Section 5.3)
+// ============================================================================
+
+fn dst_prime_xmd(dst: &[u8]) -> Vec<u8> {
+    let mut dst = if dst.len() > 255 {
+        Sha256::new()
+            .chain_update(b"H2C-OVERSIZE-DST-")
+            .chain_update(dst)
+            .finalize()
+            .to_vec()
+    } else {
+        dst.to_vec()
+    };
+    dst.push(dst.len() as u8);
+    dst
+}
+
+fn dst_prime_xof(dst: &[u8]) -> Vec<u8> {
+    let mut dst = if dst.len() > 255 {
+        let mut hasher = Shake256::default();
+        hasher.update(b"H2C-OVERSIZE-DST-");
+        hasher.update(dst);
+        // ceil(2 * k / 8) bytes for k = 128
+        let mut out = vec![0u8; 32];
+        hasher.finalize_xof().read(&mut out);
+        out
+    } else {
+        dst.to_vec()
+    };
+    dst.push(dst.len() as u8);
+    dst
+}
+
+/// expand_message_xmd with SHA-256. `len_in_bytes` must not exceed 8160.
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+    const B_IN_BYTES: usize = 32;
+    const S_IN_BYTES: usize = 64;
+    let ell = len_in_bytes.div_ceil(B_IN_BYTES);
+    assert!(ell <= 255, "expand_message_xmd: requested output too long");
+
+    let dst_prime = dst_prime_xmd(dst);
+    let b_0 = Sha256::new()
+        .chain_update([0u8; S_IN_BYTES])
+        .chain_update(msg)
+        .chain_update((len_in_bytes as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst_prime)
+        .finalize();
+
+    let mut uniform_bytes = Vec::with_capacity(ell * B_IN_BYTES);
+    let mut b_i = Sha256::new()
+        .chain_update(b_0)
+        .chain_update([1u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    uniform_bytes.extend_from_slice(&b_i);
+    for i in 2..=ell {
+        let mut xored = [0u8; B_IN_BYTES];
+        for (j, byte) in xored.iter_mut().enumerate() {
+            *byte = b_0[j] ^ b_i[j];
+        }
+        b_i = Sha256::new()
+            .chain_update(xored)
+            .chain_update([i as u8])
+            .chain_update(&dst_prime)
+            .finalize();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+    uniform_bytes.truncate(len_in_bytes);
+    uniform_bytes
+}
+
+/// expand_message_xof with SHAKE-256
+pub fn expand_message_xof(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+    let dst_prime = dst_prime_xof(dst);
+    let mut hasher = Shake256::default();
+    hasher.update(msg);
+    hasher.update(&(len_in_bytes as u16).to_be_bytes());
+    hasher.update(&dst_prime);
+    let mut uniform_bytes = vec![0u8; len_in_bytes];
+    hasher.finalize_xof().read(&mut uniform_bytes);
+    uniform_bytes
+}
+
+/// Adapters so bls12_381's SSWU map and cofactor clearing run on top of the
+/// expanders above.
+struct ExpandXmd;
+struct ExpandXof;
+
+struct ExpandedBytes {
+    bytes: Vec<u8>,
+    offset: usize,
+}
+
+impl<'x> ExpandMessageState<'x> for ExpandedBytes {
+    fn read_into(&mut self, output: &mut [u8]) -> usize {
+        let len = output.len().min(self.remain());
+        output[..len].copy_from_slice(&self.bytes[self.offset..self.offset + len]);
+        self.offset += len;
+        len
+    }
+
+    fn remain(&self) -> usize {
+        self.bytes.len() - self.offset
+    }
+}
+
+impl<'x> InitExpandMessage<'x> for ExpandXmd {
+    type Expander = ExpandedBytes;
+
+    fn init_expand(message: &[u8], dst: &'x [u8], len_in_bytes: usize) -> ExpandedBytes {
+        ExpandedBytes { bytes: expand_message_xmd(message, dst, len_in_bytes), offset: 0 }
+    }
+}
+
+impl<'x> InitExpandMessage<'x> for ExpandXof {
+    type Expander = ExpandedBytes;
+
+    fn init_expand(message: &[u8], dst: &'x [u8], len_in_bytes: usize) -> ExpandedBytes {
+        ExpandedBytes { bytes: expand_message_xof(message, dst, len_in_bytes), offset: 0 }
+    }
+}
+
+// ============================================================================
+// hash_to_field / hash_to_curve
+// ============================================================================
+
+/// OS2IP(okm) mod r for a big-endian string of at most 64 bytes
+pub fn scalar_from_okm(okm: &[u8]) -> Scalar {
+    let mut wide = [0u8; 64];
+    wide[64 - okm.len()..].copy_from_slice(okm);
+    wide.reverse();
+    Scalar::from_bytes_wide(&wide)
+}
+
+/// Uniform hash to a scalar: 48 bytes of expand_message_xmd reduced mod r
+pub fn hash_to_scalar(msg: &[u8], dst: &[u8]) -> Scalar {
+    scalar_from_okm(&expand_message_xmd(msg, dst, SCALAR_EXPAND_LEN))
+}
+
+/// hash_to_scalar over expand_message_xof (SHAKE-256)
+pub fn hash_to_scalar_xof(msg: &[u8], dst: &[u8]) -> Scalar {
+    scalar_from_okm(&expand_message_xof(msg, dst, SCALAR_EXPAND_LEN))
+}
+
+/// BLS12381G1_XMD:SHA-256_SSWU_RO_ on the configured curve backend. The
+/// result has no known discrete log.
+pub fn hash_to_g1(msg: &[u8], dst: &[u8]) -> G1Projective {
+    Backend::hash_to_g1(msg, dst)
+}
+
+/// BLS12381G1_XMD:SHA-256_SSWU_RO_ computed by bls12_381
+pub(crate) fn bls12_381_hash_to_g1(msg: &[u8], dst: &[u8]) -> G1Projective {
+    <G1Projective as HashToCurve<ExpandXmd>>::hash_to_curve(msg, dst)
+}
+
+/// BLS12381G1_XOF:SHAKE-256_SSWU_RO_
+pub fn hash_to_g1_xof(msg: &[u8], dst: &[u8]) -> G1Projective {
+    <G1Projective as HashToCurve<ExpandXof>>::hash_to_curve(msg, dst)
+}
+
+/// Expands a public seed into `count` independent G1 generators:
+/// v = expand(seed); v = expand(v || I2OSP(i, 8)); H_i = hash_to_g1(v).
+/// Same construction as create_generators in the IETF BBS draft.
+pub fn generators_from_seed(
+    count: usize,
+    seed: &[u8],
+    seed_dst: &[u8],
+    generator_dst: &[u8],
+) -> Vec<G1Projective> {
+    let mut v = expand_message_xmd(seed, seed_dst, SCALAR_EXPAND_LEN);
+    let mut generators = Vec::with_capacity(count);
+    for i in 1..=count as u64 {
+        v.extend_from_slice(&i.to_be_bytes());
+        v = expand_message_xmd(&v, seed_dst, SCALAR_EXPAND_LEN);
+        generators.push(hash_to_g1(&v, generator_dst));
+    }
+    generators
+}
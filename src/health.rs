@@ -0,0 +1,191 @@
+// Entropy Health Tests (NIST SP 800-90B, section 4.4)
+// ===================================================
+// Continuous Repetition Count and Adaptive Proportion tests over raw bytes
+// of a noise source, plus the startup test over the first 1024 samples.
+// Cutoffs follow from the claimed min-entropy per byte H and a false
+// positive rate of alpha = 2^-20 per sample:
+//   RCT: C = 1 + ceil(20 / H)
+//   APT: C = 1 + CRITBINOM(512, 2^-H, 1 - alpha)
+// A failure marks the source failed for the rest of the process; every
+// later read returns HealthTestFailed rather than untested bytes.
+
+use crate::periwinkle::{EntropyError, EntropySource};
+
+/// Min-entropy per byte claimed for a hardware source
+pub const DEFAULT_MIN_ENTROPY_BITS: f64 = 4.0;
+
+const ALPHA_EXPONENT: f64 = 20.0;
+const APT_WINDOW: u32 = 512;
+const STARTUP_SAMPLES: usize = 1024;
+
+/// Smallest k with P(X <= k) >= q for X ~ Binomial(n, p)
+fn critbinom(n: u32, p: f64, q: f64) -> u32 {
+    let mut pmf = (1.0 - p).powi(n as i32);
+    let mut cdf = pmf;
+    let mut k = 0;
+    while cdf < q && k < n {
+        pmf *= f64::from(n - k) / f64::from(k + 1) * p / (1.0 - p);
+        k += 1;
+        cdf += pmf;
+    }
+    k
+}
+
+/// Health of one tested source
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct HealthReport {
+    pub source_name: String,
+    pub healthy: bool,
+    pub startup_passed: bool,
+    /// "repetition_count" or "adaptive_proportion" once a test has failed
+    pub failed_test: Option<String>,
+    pub samples_tested: u64,
+    pub repetition_count_cutoff: u32,
+    pub adaptive_proportion_cutoff: u32,
+    /// Longest run of one byte value seen so far
+    pub max_repetition_count: u32,
+    /// Highest count of a window's first byte within that window
+    pub max_adaptive_proportion_count: u32,
+}
+
+/// Test state over a stream of byte samples
+#[derive(Debug, Clone)]
+pub struct HealthTests {
+    rct_cutoff: u32,
+    apt_cutoff: u32,
+    last: Option<u8>,
+    run: u32,
+    apt_reference: u8,
+    apt_count: u32,
+    apt_position: u32,
+    samples: u64,
+    max_run: u32,
+    max_apt_count: u32,
+    failed: Option<&'static str>,
+}
+
+impl HealthTests {
+    pub fn new(min_entropy_bits: f64) -> Self {
+        let h = min_entropy_bits.clamp(0.5, 8.0);
+        Self {
+            rct_cutoff: 1 + (ALPHA_EXPONENT / h).ceil() as u32,
+            apt_cutoff: 1 + critbinom(APT_WINDOW, 2f64.powf(-h), 1.0 - 2f64.powf(-ALPHA_EXPONENT)),
+            last: None,
+            run: 0,
+            apt_reference: 0,
+            apt_count: 0,
+            apt_position: 0,
+            samples: 0,
+            max_run: 0,
+            max_apt_count: 0,
+            failed: None,
+        }
+    }
+
+    /// Name of the failed test, if any
+    pub fn failed(&self) -> Option<&'static str> {
+        self.failed
+    }
+
+    /// Runs both tests over `samples`; stops at the first failure
+    pub fn check(&mut self, samples: &[u8]) -> Result<(), &'static str> {
+        if let Some(test) = self.failed {
+            return Err(test);
+        }
+        for &sample in samples {
+            self.samples += 1;
+
+            // Repetition Count Test
+            if self.last == Some(sample) {
+                self.run += 1;
+            } else {
+                self.last = Some(sample);
+                self.run = 1;
+            }
+            self.max_run = self.max_run.max(self.run);
+            if self.run >= self.rct_cutoff {
+                self.failed = Some("repetition_count");
+                return Err("repetition_count");
+            }
+
+            // Adaptive Proportion Test
+            if self.apt_position == 0 {
+                self.apt_reference = sample;
+                self.apt_count = 1;
+            } else if sample == self.apt_reference {
+                self.apt_count += 1;
+            }
+            self.apt_position = (self.apt_position + 1) % APT_WINDOW;
+            self.max_apt_count = self.max_apt_count.max(self.apt_count);
+            if self.apt_count >= self.apt_cutoff {
+                self.failed = Some("adaptive_proportion");
+                return Err("adaptive_proportion");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Wraps a raw noise source with startup and continuous health tests
+pub struct HealthTested<S> {
+    inner: S,
+    tests: HealthTests,
+    startup_passed: bool,
+}
+
+impl<S: EntropySource> HealthTested<S> {
+    /// Tests `inner` against `DEFAULT_MIN_ENTROPY_BITS`
+    pub fn new(inner: S) -> Self {
+        Self::with_min_entropy(inner, DEFAULT_MIN_ENTROPY_BITS)
+    }
+
+    pub fn with_min_entropy(inner: S, min_entropy_bits: f64) -> Self {
+        Self { inner, tests: HealthTests::new(min_entropy_bits), startup_passed: false }
+    }
+
+    fn tested_fill(&mut self, dest: &mut [u8]) -> Result<(), EntropyError> {
+        if let Some(test) = self.tests.failed() {
+            return Err(EntropyError::HealthTestFailed { source_name: self.inner.name(), test: test.to_string() });
+        }
+        self.inner.fill(dest)?;
+        self.tests
+            .check(dest)
+            .map_err(|test| EntropyError::HealthTestFailed { source_name: self.inner.name(), test: test.to_string() })
+    }
+}
+
+impl<S: EntropySource> EntropySource for HealthTested<S> {
+    fn fill(&mut self, dest: &mut [u8]) -> Result<(), EntropyError> {
+        if !self.startup_passed {
+            // Startup samples are tested and discarded
+            let mut startup = [0u8; STARTUP_SAMPLES];
+            self.tested_fill(&mut startup)?;
+            self.startup_passed = true;
+        }
+        let result = self.tested_fill(dest);
+        if result.is_err() {
+            dest.fill(0);
+        }
+        result
+    }
+
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn health(&self) -> Vec<HealthReport> {
+        let mut reports = vec![HealthReport {
+            source_name: self.inner.name(),
+            healthy: self.tests.failed.is_none(),
+            startup_passed: self.startup_passed,
+            failed_test: self.tests.failed.map(str::to_string),
+            samples_tested: self.tests.samples,
+            repetition_count_cutoff: self.tests.rct_cutoff,
+            adaptive_proportion_cutoff: self.tests.apt_cutoff,
+            max_repetition_count: self.tests.max_run,
+            max_adaptive_proportion_count: self.tests.max_apt_count,
+        }];
+        reports.extend(self.inner.health());
+        reports
+    }
+}
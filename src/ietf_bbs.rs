@@ -0,0 +1,586 @@
+// IETF BBS Ciphersuites (draft-irtf-cfrg-bbs-signatures-06)
+// ==========================================================
+// Interoperable BBS signatures over BLS12-381 for the BLS12381-SHA-256 and
+// BLS12381-SHAKE-256 ciphersuites. Lives alongside the legacy Multipass
+// format in lib.rs; the two wire formats are not interchangeable.
+
+use crate::backend::{Backend, CurveBackend, G2_PREPARED};
+use crate::hash_to_curve::{self, SCALAR_EXPAND_LEN};
+use crate::periwinkle::PeriwinkleRng;
+use crate::{random_scalar, VerifyError};
+use bls12_381::{G1Affine, G1Projective, G2Affine, G2Projective, Scalar};
+use ff::Field;
+use group::Curve;
+use rand::{CryptoRng, RngCore};
+
+const OCTET_SCALAR_LENGTH: usize = 32;
+const OCTET_POINT_LENGTH: usize = 48;
+const EXPAND_LEN: usize = SCALAR_EXPAND_LEN;
+
+/// Fixed part of an encoded proof: Abar, Bbar, D, e^, r1^, r3^ and the challenge
+const PROOF_BASE_LENGTH: usize = 3 * OCTET_POINT_LENGTH + 4 * OCTET_SCALAR_LENGTH;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum BbsCiphersuite {
+    Bls12381Sha256,
+    Bls12381Shake256,
+}
+
+impl BbsCiphersuite {
+    pub fn ciphersuite_id(&self) -> &'static [u8] {
+        match self {
+            BbsCiphersuite::Bls12381Sha256 => b"BBS_BLS12381G1_XMD:SHA-256_SSWU_RO_",
+            BbsCiphersuite::Bls12381Shake256 => b"BBS_BLS12381G1_XOF:SHAKE-256_SSWU_RO_",
+        }
+    }
+
+    /// api_id for the core (hash-to-generators, hash-messages-to-scalars) interface
+    pub fn api_id(&self) -> Vec<u8> {
+        [self.ciphersuite_id(), b"H2G_HM2S_".as_slice()].concat()
+    }
+
+    pub fn expand_message(&self, msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+        match self {
+            BbsCiphersuite::Bls12381Sha256 => hash_to_curve::expand_message_xmd(msg, dst, len_in_bytes),
+            BbsCiphersuite::Bls12381Shake256 => hash_to_curve::expand_message_xof(msg, dst, len_in_bytes),
+        }
+    }
+
+    pub fn hash_to_curve_g1(&self, msg: &[u8], dst: &[u8]) -> G1Projective {
+        match self {
+            BbsCiphersuite::Bls12381Sha256 => hash_to_curve::hash_to_g1(msg, dst),
+            BbsCiphersuite::Bls12381Shake256 => hash_to_curve::hash_to_g1_xof(msg, dst),
+        }
+    }
+
+    /// hash_to_scalar: expand to 48 bytes and reduce modulo r
+    pub fn hash_to_scalar(&self, msg: &[u8], dst: &[u8]) -> Scalar {
+        match self {
+            BbsCiphersuite::Bls12381Sha256 => hash_to_curve::hash_to_scalar(msg, dst),
+            BbsCiphersuite::Bls12381Shake256 => hash_to_curve::hash_to_scalar_xof(msg, dst),
+        }
+    }
+
+    /// The fixed base point P1: the first generator for the "BP_" seed
+    pub fn p1(&self) -> G1Projective {
+        let api_id = self.api_id();
+        let seed = [api_id.as_slice(), b"BP_MESSAGE_GENERATOR_SEED"].concat();
+        self.generators_from_seed(1, &seed, &api_id)[0]
+    }
+
+    /// create_generators(count, api_id): Q_1 followed by H_1..H_{count-1}
+    pub fn create_generators(&self, count: usize, api_id: &[u8]) -> Vec<G1Projective> {
+        let seed = [api_id, b"MESSAGE_GENERATOR_SEED".as_slice()].concat();
+        self.generators_from_seed(count, &seed, api_id)
+    }
+
+    fn generators_from_seed(&self, count: usize, seed: &[u8], api_id: &[u8]) -> Vec<G1Projective> {
+        let seed_dst = [api_id, b"SIG_GENERATOR_SEED_".as_slice()].concat();
+        let generator_dst = [api_id, b"SIG_GENERATOR_DST_".as_slice()].concat();
+        let mut v = self.expand_message(seed, &seed_dst, EXPAND_LEN);
+        let mut generators = Vec::with_capacity(count);
+        for i in 1..=count as u64 {
+            v.extend_from_slice(&i.to_be_bytes());
+            v = self.expand_message(&v, &seed_dst, EXPAND_LEN);
+            generators.push(self.hash_to_curve_g1(&v, &generator_dst));
+        }
+        generators
+    }
+}
+
+// ============================================================================
+// Octet Encodings
+// ============================================================================
+
+fn scalar_to_octets(s: &Scalar) -> [u8; 32] {
+    let mut bytes = s.to_bytes();
+    bytes.reverse();
+    bytes
+}
+
+/// Big-endian scalar decoding; rejects zero and values >= r
+fn octets_to_scalar(bytes: &[u8]) -> Result<Scalar, VerifyError> {
+    let mut le: [u8; 32] = bytes.try_into().map_err(|_| VerifyError::InvalidSignature)?;
+    le.reverse();
+    let s = Scalar::from_bytes(&le).into_option().ok_or(VerifyError::InvalidSignature)?;
+    if bool::from(s.is_zero()) {
+        return Err(VerifyError::InvalidSignature);
+    }
+    Ok(s)
+}
+
+fn octets_to_g1(bytes: &[u8]) -> Result<G1Affine, VerifyError> {
+    let arr: [u8; 48] = bytes.try_into().map_err(|_| VerifyError::InvalidSignature)?;
+    let p = G1Affine::from_compressed(&arr).into_option().ok_or(VerifyError::InvalidSignature)?;
+    if bool::from(p.is_identity()) {
+        return Err(VerifyError::InvalidSignature);
+    }
+    Ok(p)
+}
+
+fn octets_to_pubkey(bytes: &[u8]) -> Result<G2Affine, VerifyError> {
+    let arr: [u8; 96] = bytes.try_into().map_err(|_| VerifyError::InvalidKey)?;
+    let w = G2Affine::from_compressed(&arr).into_option().ok_or(VerifyError::InvalidKey)?;
+    if bool::from(w.is_identity()) {
+        return Err(VerifyError::InvalidKey);
+    }
+    Ok(w)
+}
+
+fn octets_to_signature(bytes: &[u8]) -> Result<(G1Affine, Scalar), VerifyError> {
+    if bytes.len() != OCTET_POINT_LENGTH + OCTET_SCALAR_LENGTH {
+        return Err(VerifyError::InvalidSignature);
+    }
+    let a = octets_to_g1(&bytes[..48])?;
+    let e = octets_to_scalar(&bytes[48..])?;
+    Ok((a, e))
+}
+
+/// Parses a secret key as the big-endian 32-byte encoding used by the draft
+fn octets_to_secret_key(bytes: &[u8]) -> Result<Scalar, VerifyError> {
+    octets_to_scalar(bytes).map_err(|_| VerifyError::InvalidKey)
+}
+
+struct Proof {
+    abar: G1Affine,
+    bbar: G1Affine,
+    d: G1Affine,
+    e_hat: Scalar,
+    r1_hat: Scalar,
+    r3_hat: Scalar,
+    m_hat: Vec<Scalar>,
+    challenge: Scalar,
+}
+
+impl Proof {
+    fn to_octets(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(PROOF_BASE_LENGTH + 32 * self.m_hat.len());
+        out.extend_from_slice(&self.abar.to_compressed());
+        out.extend_from_slice(&self.bbar.to_compressed());
+        out.extend_from_slice(&self.d.to_compressed());
+        out.extend_from_slice(&scalar_to_octets(&self.e_hat));
+        out.extend_from_slice(&scalar_to_octets(&self.r1_hat));
+        out.extend_from_slice(&scalar_to_octets(&self.r3_hat));
+        for m in &self.m_hat {
+            out.extend_from_slice(&scalar_to_octets(m));
+        }
+        out.extend_from_slice(&scalar_to_octets(&self.challenge));
+        out
+    }
+
+    fn from_octets(bytes: &[u8]) -> Result<Self, VerifyError> {
+        if bytes.len() < PROOF_BASE_LENGTH || !(bytes.len() - PROOF_BASE_LENGTH).is_multiple_of(32) {
+            return Err(VerifyError::InvalidSignature);
+        }
+        let abar = octets_to_g1(&bytes[0..48])?;
+        let bbar = octets_to_g1(&bytes[48..96])?;
+        let d = octets_to_g1(&bytes[96..144])?;
+        let scalars = bytes[144..]
+            .chunks(32)
+            .map(octets_to_scalar)
+            .collect::<Result<Vec<_>, _>>()?;
+        let (challenge, responses) = scalars.split_last().ok_or(VerifyError::InvalidSignature)?;
+        Ok(Self {
+            abar,
+            bbar,
+            d,
+            e_hat: responses[0],
+            r1_hat: responses[1],
+            r3_hat: responses[2],
+            m_hat: responses[3..].to_vec(),
+            challenge: *challenge,
+        })
+    }
+}
+
+// ============================================================================
+// Core Operations
+// ============================================================================
+
+fn messages_to_scalars(suite: BbsCiphersuite, messages: &[Vec<u8>], api_id: &[u8]) -> Vec<Scalar> {
+    let map_dst = [api_id, b"MAP_MSG_TO_SCALAR_AS_HASH_".as_slice()].concat();
+    messages.iter().map(|m| suite.hash_to_scalar(m, &map_dst)).collect()
+}
+
+fn calculate_domain(
+    suite: BbsCiphersuite,
+    pk: &[u8],
+    q1: &G1Projective,
+    h_points: &[G1Projective],
+    header: &[u8],
+    api_id: &[u8],
+) -> Scalar {
+    let mut dom_input = pk.to_vec();
+    dom_input.extend_from_slice(&(h_points.len() as u64).to_be_bytes());
+    dom_input.extend_from_slice(&q1.to_affine().to_compressed());
+    for h in h_points {
+        dom_input.extend_from_slice(&h.to_affine().to_compressed());
+    }
+    dom_input.extend_from_slice(api_id);
+    dom_input.extend_from_slice(&(header.len() as u64).to_be_bytes());
+    dom_input.extend_from_slice(header);
+    let dom_dst = [api_id, b"H2S_".as_slice()].concat();
+    suite.hash_to_scalar(&dom_input, &dom_dst)
+}
+
+/// e(p1, w) * e(p2, BP2) == Identity_GT with one final exponentiation
+fn pairing_product_is_identity(p1: &G1Affine, w: &G2Affine, p2: &G1Affine) -> bool {
+    Backend::pairing_product_is_identity(&[(p1, &Backend::prepare_g2(w)), (p2, &*G2_PREPARED)])
+}
+
+/// P1, Q_1, H_1..H_L in affine form, the bases of B
+fn signature_bases(suite: BbsCiphersuite, generators: &[G1Projective]) -> Vec<G1Affine> {
+    let mut points = vec![suite.p1()];
+    points.extend_from_slice(generators);
+    let mut affine = vec![G1Affine::identity(); points.len()];
+    G1Projective::batch_normalize(&points, &mut affine);
+    affine
+}
+
+fn core_sign(
+    suite: BbsCiphersuite,
+    sk: Scalar,
+    pk: &[u8],
+    header: &[u8],
+    msg_scalars: &[Scalar],
+    api_id: &[u8],
+) -> Result<Vec<u8>, VerifyError> {
+    let generators = suite.create_generators(msg_scalars.len() + 1, api_id);
+    let (q1, h_points) = generators.split_first().ok_or(VerifyError::CryptoError)?;
+    let domain = calculate_domain(suite, pk, q1, h_points, header, api_id);
+
+    let mut e_input = scalar_to_octets(&sk).to_vec();
+    for m in msg_scalars {
+        e_input.extend_from_slice(&scalar_to_octets(m));
+    }
+    e_input.extend_from_slice(&scalar_to_octets(&domain));
+    let e = suite.hash_to_scalar(&e_input, &[api_id, b"H2S_".as_slice()].concat());
+
+    let mut scalars = vec![Scalar::ONE, domain];
+    scalars.extend_from_slice(msg_scalars);
+    let b = Backend::lincomb(&signature_bases(suite, &generators), &scalars);
+    let inv = (sk + e).invert().into_option().ok_or(VerifyError::CryptoError)?;
+    let a = Backend::lincomb(&[b.to_affine()], &[inv]).to_affine();
+
+    let mut signature = Vec::with_capacity(80);
+    signature.extend_from_slice(&a.to_compressed());
+    signature.extend_from_slice(&scalar_to_octets(&e));
+    Ok(signature)
+}
+
+fn core_verify(
+    suite: BbsCiphersuite,
+    pk: &[u8],
+    signature: &[u8],
+    header: &[u8],
+    msg_scalars: &[Scalar],
+    api_id: &[u8],
+) -> Result<bool, VerifyError> {
+    let (a, e) = octets_to_signature(signature)?;
+    let w = octets_to_pubkey(pk)?;
+    let generators = suite.create_generators(msg_scalars.len() + 1, api_id);
+    let (q1, h_points) = generators.split_first().ok_or(VerifyError::CryptoError)?;
+    let domain = calculate_domain(suite, pk, q1, h_points, header, api_id);
+
+    // e(A, W + BP2*e) * e(B, -BP2) == 1, i.e. e(A, W) * e(A*e - B, BP2) == 1
+    let mut points = vec![a];
+    points.extend(signature_bases(suite, &generators));
+    let mut scalars = vec![e, -Scalar::ONE, -domain];
+    scalars.extend(msg_scalars.iter().map(|m| -m));
+    let y = Backend::msm_vartime(&points, &scalars).to_affine();
+    Ok(pairing_product_is_identity(&a, &w, &y))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn challenge_calculate(
+    suite: BbsCiphersuite,
+    abar: &G1Affine,
+    bbar: &G1Affine,
+    d: &G1Affine,
+    t1: &G1Affine,
+    t2: &G1Affine,
+    domain: &Scalar,
+    disclosed: &[(usize, Scalar)],
+    ph: &[u8],
+    api_id: &[u8],
+) -> Scalar {
+    let mut c_octs = (disclosed.len() as u64).to_be_bytes().to_vec();
+    for (i, m) in disclosed {
+        c_octs.extend_from_slice(&(*i as u64).to_be_bytes());
+        c_octs.extend_from_slice(&scalar_to_octets(m));
+    }
+    for p in [abar, bbar, d, t1, t2] {
+        c_octs.extend_from_slice(&p.to_compressed());
+    }
+    c_octs.extend_from_slice(&scalar_to_octets(domain));
+    c_octs.extend_from_slice(&(ph.len() as u64).to_be_bytes());
+    c_octs.extend_from_slice(ph);
+    suite.hash_to_scalar(&c_octs, &[api_id, b"H2S_".as_slice()].concat())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn core_proof_gen<R: RngCore + CryptoRng>(
+    suite: BbsCiphersuite,
+    pk: &[u8],
+    signature: &[u8],
+    header: &[u8],
+    ph: &[u8],
+    msg_scalars: &[Scalar],
+    disclosed_indexes: &[usize],
+    api_id: &[u8],
+    rng: &mut R,
+) -> Result<Vec<u8>, VerifyError> {
+    let (a, e) = octets_to_signature(signature)?;
+    let l = msg_scalars.len();
+    if disclosed_indexes.iter().any(|&i| i >= l) {
+        return Err(VerifyError::InvalidSignature);
+    }
+    let undisclosed: Vec<usize> = (0..l).filter(|i| !disclosed_indexes.contains(i)).collect();
+
+    let generators = suite.create_generators(l + 1, api_id);
+    let (q1, h_points) = generators.split_first().ok_or(VerifyError::CryptoError)?;
+    let domain = calculate_domain(suite, pk, q1, h_points, header, api_id);
+
+    let r1 = random_scalar(rng)?;
+    let r2 = random_scalar(rng)?;
+    let e_tilde = random_scalar(rng)?;
+    let r1_tilde = random_scalar(rng)?;
+    let r3_tilde = random_scalar(rng)?;
+    let m_tilde = undisclosed.iter().map(|_| random_scalar(rng)).collect::<Result<Vec<_>, _>>()?;
+
+    // ProofInit
+    let mut scalars = vec![Scalar::ONE, domain];
+    scalars.extend_from_slice(msg_scalars);
+    let bases = signature_bases(suite, &generators);
+    let b = Backend::lincomb(&bases, &scalars).to_affine();
+    let r1_r2 = r1 * r2;
+    let mut randomized = [G1Affine::identity(); 3];
+    G1Projective::batch_normalize(
+        &[
+            Backend::lincomb(&[a], &[r1_r2]),
+            Backend::lincomb(&[b, a], &[r1_r2, -(r1_r2 * e)]),
+            Backend::lincomb(&[b], &[r2]),
+        ],
+        &mut randomized,
+    );
+    let [abar, bbar, d] = randomized;
+    let t1 = Backend::lincomb(&[abar, d], &[e_tilde, r1_tilde]);
+    let mut points = vec![d];
+    let mut scalars = vec![r3_tilde];
+    for (j, m) in undisclosed.iter().zip(&m_tilde) {
+        points.push(bases[j + 2]);
+        scalars.push(*m);
+    }
+    let t2 = Backend::lincomb(&points, &scalars);
+
+    let disclosed: Vec<(usize, Scalar)> = disclosed_indexes.iter().map(|&i| (i, msg_scalars[i])).collect();
+    let challenge = challenge_calculate(
+        suite, &abar, &bbar, &d, &t1.to_affine(), &t2.to_affine(), &domain, &disclosed, ph, api_id,
+    );
+
+    // ProofFinalize
+    let r3 = r2.invert().into_option().ok_or(VerifyError::CryptoError)?;
+    let proof = Proof {
+        abar,
+        bbar,
+        d,
+        e_hat: e_tilde + e * challenge,
+        r1_hat: r1_tilde - r1 * challenge,
+        r3_hat: r3_tilde - r3 * challenge,
+        m_hat: undisclosed
+            .iter()
+            .zip(&m_tilde)
+            .map(|(j, m)| m + msg_scalars[*j] * challenge)
+            .collect(),
+        challenge,
+    };
+    Ok(proof.to_octets())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn core_proof_verify(
+    suite: BbsCiphersuite,
+    pk: &[u8],
+    proof: &[u8],
+    header: &[u8],
+    ph: &[u8],
+    disclosed_scalars: &[Scalar],
+    disclosed_indexes: &[usize],
+    api_id: &[u8],
+) -> Result<bool, VerifyError> {
+    let proof = Proof::from_octets(proof)?;
+    let w = octets_to_pubkey(pk)?;
+    if disclosed_indexes.len() != disclosed_scalars.len() {
+        return Err(VerifyError::InvalidSignature);
+    }
+    let l = disclosed_indexes.len() + proof.m_hat.len();
+    if disclosed_indexes.windows(2).any(|w| w[0] >= w[1]) || disclosed_indexes.iter().any(|&i| i >= l) {
+        return Err(VerifyError::InvalidSignature);
+    }
+    let undisclosed: Vec<usize> = (0..l).filter(|i| !disclosed_indexes.contains(i)).collect();
+
+    let generators = suite.create_generators(l + 1, api_id);
+    let (q1, h_points) = generators.split_first().ok_or(VerifyError::CryptoError)?;
+    let domain = calculate_domain(suite, pk, q1, h_points, header, api_id);
+
+    // ProofVerifyInit
+    let c = proof.challenge;
+    let t1 = Backend::msm_vartime(&[proof.bbar, proof.abar, proof.d], &[c, proof.e_hat, proof.r1_hat]);
+    // T2 = Bv*c + D*r3^ + sum(H_j*m^_j) with Bv = P1 + Q_1*domain + sum(H_i*m_i)
+    let bases = signature_bases(suite, &generators);
+    let mut points = vec![bases[0], bases[1], proof.d];
+    let mut scalars = vec![c, c * domain, proof.r3_hat];
+    for (i, m) in disclosed_indexes.iter().zip(disclosed_scalars) {
+        points.push(bases[i + 2]);
+        scalars.push(c * m);
+    }
+    for (j, m) in undisclosed.iter().zip(&proof.m_hat) {
+        points.push(bases[j + 2]);
+        scalars.push(*m);
+    }
+    let t2 = Backend::msm_vartime(&points, &scalars);
+
+    let disclosed: Vec<(usize, Scalar)> = disclosed_indexes
+        .iter()
+        .copied()
+        .zip(disclosed_scalars.iter().copied())
+        .collect();
+    let challenge = challenge_calculate(
+        suite, &proof.abar, &proof.bbar, &proof.d, &t1.to_affine(), &t2.to_affine(), &domain,
+        &disclosed, ph, api_id,
+    );
+    if challenge != c {
+        return Ok(false);
+    }
+
+    Ok(pairing_product_is_identity(&proof.abar, &w, &(-proof.bbar)))
+}
+
+// ============================================================================
+// Public API (uniffi)
+// ============================================================================
+
+/// KeyGen: derives a secret key from at least 32 bytes of key material.
+/// An empty `key_dst` selects the ciphersuite default (api_id || "KEYGEN_DST_").
+#[uniffi::export]
+pub fn ietf_keygen(
+    ciphersuite: BbsCiphersuite,
+    key_material: Vec<u8>,
+    key_info: Vec<u8>,
+    key_dst: Vec<u8>,
+) -> Result<Vec<u8>, VerifyError> {
+    if key_material.len() < 32 || key_info.len() > u16::MAX as usize {
+        return Err(VerifyError::InvalidKey);
+    }
+    let key_dst = if key_dst.is_empty() {
+        [ciphersuite.api_id(), b"KEYGEN_DST_".to_vec()].concat()
+    } else {
+        key_dst
+    };
+    let mut derive_input = key_material;
+    derive_input.extend_from_slice(&(key_info.len() as u16).to_be_bytes());
+    derive_input.extend_from_slice(&key_info);
+    let sk = ciphersuite.hash_to_scalar(&derive_input, &key_dst);
+    if bool::from(sk.is_zero()) {
+        return Err(VerifyError::InvalidKey);
+    }
+    Ok(scalar_to_octets(&sk).to_vec())
+}
+
+/// SkToPk: W = SK * BP2, 96-byte compressed G2 point
+#[uniffi::export]
+pub fn ietf_sk_to_pk(secret_key: Vec<u8>) -> Result<Vec<u8>, VerifyError> {
+    let sk = octets_to_secret_key(&secret_key)?;
+    Ok((G2Projective::generator() * sk).to_affine().to_compressed().to_vec())
+}
+
+/// Sign: 80-byte signature (A || e) over the header and messages
+#[uniffi::export]
+pub fn ietf_sign(
+    ciphersuite: BbsCiphersuite,
+    secret_key: Vec<u8>,
+    public_key: Vec<u8>,
+    header: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+) -> Result<Vec<u8>, VerifyError> {
+    let sk = octets_to_secret_key(&secret_key)?;
+    octets_to_pubkey(&public_key)?;
+    let api_id = ciphersuite.api_id();
+    let msg_scalars = messages_to_scalars(ciphersuite, &messages, &api_id);
+    core_sign(ciphersuite, sk, &public_key, &header, &msg_scalars, &api_id)
+}
+
+#[uniffi::export]
+pub fn ietf_verify(
+    ciphersuite: BbsCiphersuite,
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    header: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+) -> Result<bool, VerifyError> {
+    let api_id = ciphersuite.api_id();
+    let msg_scalars = messages_to_scalars(ciphersuite, &messages, &api_id);
+    core_verify(ciphersuite, &public_key, &signature, &header, &msg_scalars, &api_id)
+}
+
+/// ProofGen: proof of possession of a signature disclosing the (zero-based)
+/// `disclosed_indexes`, bound to the presentation header `ph`.
+#[uniffi::export]
+pub fn ietf_proof_gen(
+    ciphersuite: BbsCiphersuite,
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    header: Vec<u8>,
+    ph: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+    disclosed_indexes: Vec<u32>,
+) -> Result<Vec<u8>, VerifyError> {
+    ietf_proof_gen_with_rng(
+        ciphersuite,
+        public_key,
+        signature,
+        header,
+        ph,
+        messages,
+        disclosed_indexes,
+        &mut PeriwinkleRng,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn ietf_proof_gen_with_rng<R: RngCore + CryptoRng>(
+    ciphersuite: BbsCiphersuite,
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    header: Vec<u8>,
+    ph: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+    disclosed_indexes: Vec<u32>,
+    rng: &mut R,
+) -> Result<Vec<u8>, VerifyError> {
+    octets_to_pubkey(&public_key)?;
+    let mut disclosed: Vec<usize> = disclosed_indexes.iter().map(|&i| i as usize).collect();
+    disclosed.sort_unstable();
+    disclosed.dedup();
+    let api_id = ciphersuite.api_id();
+    let msg_scalars = messages_to_scalars(ciphersuite, &messages, &api_id);
+    core_proof_gen(ciphersuite, &public_key, &signature, &header, &ph, &msg_scalars, &disclosed, &api_id, rng)
+}
+
+/// ProofVerify: `disclosed_indexes` must be strictly ascending and match
+/// `disclosed_messages` one-to-one.
+#[uniffi::export]
+pub fn ietf_proof_verify(
+    ciphersuite: BbsCiphersuite,
+    public_key: Vec<u8>,
+    proof: Vec<u8>,
+    header: Vec<u8>,
+    ph: Vec<u8>,
+    disclosed_messages: Vec<Vec<u8>>,
+    disclosed_indexes: Vec<u32>,
+) -> Result<bool, VerifyError> {
+    let disclosed: Vec<usize> = disclosed_indexes.iter().map(|&i| i as usize).collect();
+    let api_id = ciphersuite.api_id();
+    let disclosed_scalars = messages_to_scalars(ciphersuite, &disclosed_messages, &api_id);
+    core_proof_verify(ciphersuite, &public_key, &proof, &header, &ph, &disclosed_scalars, &disclosed, &api_id)
+}
@@ -4,17 +4,45 @@
 
 pub use bls12_381::{G1Affine, G1Projective, G2Affine, G2Projective, Scalar};
 use ff::Field;
-use group::Curve;
-use crate::periwinkle::get_entropy;
+use crate::periwinkle::PeriwinkleRng;
 use sha2::{Digest, Sha256};
 use std::slice;
 use std::ptr;
-use rand::thread_rng;
+use rand::{CryptoRng, RngCore};
 
 pub mod periwinkle;
+pub mod health;
+pub mod drbg;
+pub mod sealed_root;
 pub mod attestation;
 pub mod cbor;
 pub mod miner;
+pub mod ietf_bbs;
+pub mod hash_to_curve;
+pub mod bbs;
+pub mod blind;
+pub mod predicate;
+pub mod multi_credential;
+pub mod batch;
+pub mod epoch;
+pub mod nullifier;
+pub mod accumulator;
+pub mod status_list;
+pub mod schema;
+pub mod presentation;
+pub mod deterministic;
+mod msm;
+pub mod backend;
+
+pub use bbs::{BbsProof, BbsPublicKey, BbsSecretKey, BbsSignature, KeyError};
+pub use blind::{BlindCommitment, IndexedMessage};
+pub use predicate::RangePredicate;
+pub use multi_credential::{CredentialDisclosure, CredentialInput, EqualityConstraint};
+pub use batch::{BatchVerification, ProofBatchItem, SignatureBatchItem};
+pub use epoch::{Clock, EpochSchedule, SystemClock};
+pub use status_list::{StatusList, StatusListCredential};
+pub use schema::{AttributeDefinition, AttributeType, AttributeValue, CredentialSchema};
+pub use presentation::Presentation;
 
 uniffi::setup_scaffolding!();
 
@@ -29,40 +57,146 @@ fn to_byte_array(data: Vec<u8>) -> (*mut u8, usize) {
     (ptr, len)
 }
 
+/// Domain separation tags for the legacy Multipass BBS+ suite
+const H2S_DST: &[u8] = b"MULTIPASS_BBS_BLS12381G1_XMD:SHA-256_H2S_";
+const H2G_DST: &[u8] = b"MULTIPASS_BBS_BLS12381G1_XMD:SHA-256_SSWU_RO_";
+const GENERATOR_SEED_DST: &[u8] = b"MULTIPASS_BBS_BLS12381G1_XMD:SHA-256_SIG_GENERATOR_SEED_";
+const GENERATOR_DST: &[u8] = b"MULTIPASS_BBS_BLS12381G1_XMD:SHA-256_SIG_GENERATOR_DST_";
+
 fn hash_to_scalar(data: &[u8]) -> Scalar {
-    let mut hasher = Sha256::new();
-    hasher.update(data);
-    let hash = hasher.finalize();
-    let mut bytes = [0u8; 32];
-    bytes.copy_from_slice(&hash[..32]);
-    // Use from_bytes which returns CtOption
-    let opt = Scalar::from_bytes(&bytes);
-    if bool::from(opt.is_some()) {
-        opt.unwrap()
-    } else {
-        Scalar::ONE
-    }
+    hash_to_curve::hash_to_scalar(data, H2S_DST)
 }
 
 fn hash_to_g1(data: &[u8]) -> G1Projective {
-    let scalar = hash_to_scalar(data);
-    G1Projective::generator() * scalar
+    hash_to_curve::hash_to_g1(data, H2G_DST)
 }
 
 // ============================================================================
 // Key Generation
 // ============================================================================
 
-pub fn generate_linkage_tag(sk: Scalar, site_id: &[u8]) -> G1Affine {
-    let mut hasher = Sha256::new();
-    hasher.update(site_id);
-    let site_scalar = hash_to_scalar(&hasher.finalize());
-    // Deterministic point: Site_Tag = G1 * (sk + site_scalar)
-    (G1Projective::generator() * (sk + site_scalar)).to_affine()
+/// Fresh holder nym secret. Sign it as the last message of a credential,
+/// ideally hidden through blind issuance, to get per-verifier pseudonyms.
+#[uniffi::export]
+pub fn generate_nym_secret() -> Result<Vec<u8>, VerifyError> {
+    generate_nym_secret_with_rng(&mut PeriwinkleRng)
+}
+
+pub fn generate_nym_secret_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Result<Vec<u8>, VerifyError> {
+    Ok(random_scalar(rng)?.to_bytes().to_vec())
 }
 
 // [Removed bbs_generate_key_pair to avoid linker conflict with bbs_lib]
 
+/// Default public seed for the message generators (h0, h1..hL) embedded in issuer keys
+const GENERATOR_SEED: &[u8] = b"MULTIPASS_BBS_MESSAGE_GENERATOR_SEED";
+
+/// Uniform scalar from 64 bytes of `rng`. Fails instead of panicking when
+/// the entropy source has failed.
+fn random_scalar<R: RngCore + CryptoRng>(rng: &mut R) -> Result<Scalar, VerifyError> {
+    let mut bytes = [0u8; 64];
+    rng.try_fill_bytes(&mut bytes)
+        .map_err(|e| VerifyError::EntropyUnavailable { reason: e.to_string() })?;
+    Ok(Scalar::from_bytes_wide(&bytes))
+}
+
+fn parse_secret_key(secret_key: &[u8]) -> Result<Scalar, VerifyError> {
+    let sk_arr: [u8; 32] = secret_key.try_into().map_err(|_| VerifyError::InvalidKey)?;
+    let sk = Scalar::from_bytes(&sk_arr).into_option().ok_or(VerifyError::InvalidKey)?;
+    if bool::from(sk.is_zero()) {
+        return Err(VerifyError::InvalidKey);
+    }
+    Ok(sk)
+}
+
+/// Derives h0 (blinding generator) followed by one generator per message from a public seed
+fn derive_generators(generator_seed: &[u8], message_count: usize) -> Vec<G1Affine> {
+    let generators = hash_to_curve::generators_from_seed(
+        message_count + 1,
+        generator_seed,
+        GENERATOR_SEED_DST,
+        GENERATOR_DST,
+    );
+    let mut affine = vec![G1Affine::identity(); generators.len()];
+    G1Projective::batch_normalize(&generators, &mut affine);
+    affine
+}
+
+/// Generates a fresh 32-byte issuer secret key from the Periwinkle entropy source
+#[uniffi::export]
+pub fn generate_secret_key() -> Result<Vec<u8>, VerifyError> {
+    generate_secret_key_with_rng(&mut PeriwinkleRng)
+}
+
+pub fn generate_secret_key_with_rng<R: RngCore + CryptoRng>(rng: &mut R) -> Result<Vec<u8>, VerifyError> {
+    Ok(BbsSecretKey::generate_with_rng(rng)?.to_bytes())
+}
+
+/// Derives the issuer public key for `message_count` messages.
+/// Layout: w (96 bytes, G2) || h0 || h1..hL (48 bytes each, G1), as parsed by
+/// `sign`, `verify_signature_safe`, `create_proof` and `verify_proof_safe`.
+#[uniffi::export]
+pub fn get_public_key(secret_key: Vec<u8>, message_count: u32) -> Result<Vec<u8>, VerifyError> {
+    get_public_key_with_seed(secret_key, GENERATOR_SEED.to_vec(), message_count)
+}
+
+/// Same as `get_public_key` with issuer-chosen generators derived from `generator_seed`
+#[uniffi::export]
+pub fn get_public_key_with_seed(
+    secret_key: Vec<u8>,
+    generator_seed: Vec<u8>,
+    message_count: u32,
+) -> Result<Vec<u8>, VerifyError> {
+    let sk = BbsSecretKey::from_bytes(secret_key)?;
+    Ok(sk.public_key_with_seed(generator_seed, message_count).to_bytes())
+}
+
+/// Checks a public key with the same strict parser used by signing and
+/// verification and returns the number of messages it supports.
+#[uniffi::export]
+pub fn validate_public_key(public_key: Vec<u8>) -> Result<u32, KeyError> {
+    Ok(BbsPublicKey::parse_strict(&public_key)?.message_count())
+}
+
+/// Shortens a full public key to w (96) || message_count (u32 BE) || generator_seed.
+/// Fails unless every generator in the key is the one derived from the seed.
+#[uniffi::export]
+pub fn compact_public_key(public_key: Vec<u8>, generator_seed: Vec<u8>) -> Result<Vec<u8>, VerifyError> {
+    let pk = BbsPublicKey::parse(&public_key)?;
+    let message_count = pk.message_count();
+    if derive_generators(&generator_seed, message_count as usize) != pk.generators() {
+        return Err(VerifyError::InvalidKey);
+    }
+
+    let mut compact = Vec::with_capacity(100 + generator_seed.len());
+    compact.extend_from_slice(&pk.w().to_compressed());
+    compact.extend_from_slice(&message_count.to_be_bytes());
+    compact.extend_from_slice(&generator_seed);
+    Ok(compact)
+}
+
+/// Expands a compact public key back to the full w || h0 || h1..hL layout.
+/// The result goes through the strict parser, so a compact key cannot name
+/// more than `MAX_MESSAGE_COUNT` messages or carry an invalid or identity w.
+#[uniffi::export]
+pub fn expand_public_key(compact_key: Vec<u8>) -> Result<Vec<u8>, VerifyError> {
+    if compact_key.len() < 100 {
+        return Err(KeyError::Truncated.into());
+    }
+    let count_bytes: [u8; 4] = compact_key[96..100].try_into().map_err(|_| KeyError::Truncated)?;
+    let message_count = u32::from_be_bytes(count_bytes);
+    if message_count > bbs::MAX_MESSAGE_COUNT {
+        return Err(KeyError::TooManyMessages { count: message_count as u64, max: bbs::MAX_MESSAGE_COUNT }.into());
+    }
+
+    let mut full = Vec::with_capacity(96 + 48 * (message_count as usize + 1));
+    full.extend_from_slice(&compact_key[..96]);
+    for h in derive_generators(&compact_key[100..], message_count as usize) {
+        full.extend_from_slice(&h.to_compressed());
+    }
+    Ok(BbsPublicKey::parse(&full)?.to_bytes())
+}
+
 // ============================================================================
 // Signing
 // ============================================================================
@@ -71,7 +205,46 @@ pub fn generate_linkage_tag(sk: Scalar, site_id: &[u8]) -> G1Affine {
 // Blind Issuance (Commitment Generation)
 // ============================================================================
 
-// [Removed bbs_blind_commitment]
+/// Holder side: commits to `hidden_messages` (e.g. a link secret) for an
+/// issuer-supplied `nonce`. Send only `commitment` to the issuer.
+#[uniffi::export]
+pub fn blind_commitment(
+    public_key: Vec<u8>,
+    hidden_messages: Vec<IndexedMessage>,
+    nonce: Vec<u8>,
+) -> Result<BlindCommitment, VerifyError> {
+    blind_commitment_with_rng(public_key, hidden_messages, nonce, &mut PeriwinkleRng)
+}
+
+pub fn blind_commitment_with_rng<R: RngCore + CryptoRng>(
+    public_key: Vec<u8>,
+    hidden_messages: Vec<IndexedMessage>,
+    nonce: Vec<u8>,
+    rng: &mut R,
+) -> Result<BlindCommitment, VerifyError> {
+    let pk = BbsPublicKey::parse(&public_key)?;
+    let (commitment, blinding) = blind::Commitment::create(&pk, &hidden_messages, &nonce, rng)?;
+    Ok(BlindCommitment {
+        commitment: commitment.to_bytes(),
+        blinding_factor: blinding.to_bytes().to_vec(),
+    })
+}
+
+/// Holder side: turns the issuer's blind signature into a regular signature
+/// over `messages` (hidden and known, in index order)
+#[uniffi::export]
+pub fn unblind_signature(
+    public_key: Vec<u8>,
+    blind_signature: Vec<u8>,
+    blinding_factor: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+) -> Result<Vec<u8>, VerifyError> {
+    let pk = BbsPublicKey::parse(&public_key)?;
+    let blind_sig = BbsSignature::parse(&blind_signature)?;
+    let bf_arr: [u8; 32] = blinding_factor.try_into().map_err(|_| VerifyError::InvalidKey)?;
+    let blinding = Scalar::from_bytes(&bf_arr).into_option().ok_or(VerifyError::InvalidKey)?;
+    Ok(blind::unblind(&pk, &blind_sig, blinding, &messages)?.to_bytes())
+}
 
 // ============================================================================
 // Signing
@@ -83,171 +256,194 @@ pub fn generate_linkage_tag(sk: Scalar, site_id: &[u8]) -> G1Affine {
 // Blind Signing (Broker Side)
 // ============================================================================
 
-// [Removed bbs_blind_sign]
+/// Issuer side: verifies the holder's commitment for `nonce` and signs it
+/// together with the issuer's `known_messages`
+#[uniffi::export]
+pub fn blind_sign(
+    secret_key: Vec<u8>,
+    public_key: Vec<u8>,
+    commitment: Vec<u8>,
+    nonce: Vec<u8>,
+    known_messages: Vec<IndexedMessage>,
+) -> Result<Vec<u8>, VerifyError> {
+    blind_sign_with_rng(secret_key, public_key, commitment, nonce, known_messages, &mut PeriwinkleRng)
+}
+
+pub fn blind_sign_with_rng<R: RngCore + CryptoRng>(
+    secret_key: Vec<u8>,
+    public_key: Vec<u8>,
+    commitment: Vec<u8>,
+    nonce: Vec<u8>,
+    known_messages: Vec<IndexedMessage>,
+    rng: &mut R,
+) -> Result<Vec<u8>, VerifyError> {
+    let sk = BbsSecretKey::from_bytes(secret_key)?;
+    let pk = BbsPublicKey::parse(&public_key)?;
+    let commitment = blind::Commitment::parse(&commitment)?;
+    Ok(blind::blind_sign(&sk, &pk, &commitment, &nonce, &known_messages, rng)?.to_bytes())
+}
 
 // ============================================================================
 // Signing
 // ============================================================================
 
+/// Signs `messages`. Credentials meant for create_proof must carry the
+/// holder's nym secret (generate_nym_secret) as their last message.
 #[uniffi::export]
 pub fn sign(
     secret_key: Vec<u8>,
     public_key: Vec<u8>,
     messages: Vec<Vec<u8>>,
 ) -> Result<Vec<u8>, VerifyError> {
-    // Parse private key
-    if secret_key.len() != 32 { return Err(VerifyError::InvalidKey); }
-    let sk_arr: [u8; 32] = secret_key.try_into().unwrap();
-    let sk = Scalar::from_bytes(&sk_arr).into_option().ok_or(VerifyError::InvalidKey)?;
-    
-    // Parse public key (need h values)
-    if public_key.len() < 96 { return Err(VerifyError::InvalidKey); }
-    let mut h_values: Vec<G1Affine> = Vec::new();
-    let mut offset = 96;
-    while offset + 48 <= public_key.len() {
-        let h_bytes: [u8; 48] = public_key[offset..offset+48].try_into().map_err(|_| VerifyError::InvalidKey)?;
-        let h_opt = G1Affine::from_compressed(&h_bytes);
-        if bool::from(h_opt.is_some()) {
-            h_values.push(h_opt.unwrap());
-        }
-        offset += 48;
-    }
-    
-    if h_values.len() < messages.len() + 1 { return Err(VerifyError::InvalidKey); }
+    sign_with_rng(secret_key, public_key, messages, &mut PeriwinkleRng)
+}
 
-    let msg_scalars: Vec<Scalar> = messages.iter().map(|m| hash_to_scalar(m)).collect();
-    
-    // Generate random e and s
-    let ent_e = get_entropy();
-    let e = Scalar::from_bytes_wide(&ent_e);
-    let ent_s = get_entropy();
-    let s = Scalar::from_bytes_wide(&ent_s);
-    
-    // B = g1 + h0*s + sum(hi*mi)
-    let g1 = G1Projective::generator();
-    let mut b = g1;
-    if !h_values.is_empty() {
-        b = b + G1Projective::from(h_values[0]) * s;
-    }
-    for (i, m) in msg_scalars.iter().enumerate() {
-        if i + 1 < h_values.len() {
-             b = b + G1Projective::from(h_values[i + 1]) * m;
-        }
-    }
-    
-    // A = B * (1/(sk+e))
-    let sk_plus_e = sk + e;
-    let inv = sk_plus_e.invert().into_option().unwrap_or(Scalar::ONE);
-    let a = (b * inv).to_affine();
-    
-    let mut sig_bytes = Vec::with_capacity(112);
-    sig_bytes.extend_from_slice(&a.to_compressed());
-    sig_bytes.extend_from_slice(&e.to_bytes());
-    sig_bytes.extend_from_slice(&s.to_bytes());
-    Ok(sig_bytes)
+pub fn sign_with_rng<R: RngCore + CryptoRng>(
+    secret_key: Vec<u8>,
+    public_key: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+    rng: &mut R,
+) -> Result<Vec<u8>, VerifyError> {
+    let sk = BbsSecretKey::from_bytes(secret_key)?;
+    let pk = BbsPublicKey::parse(&public_key)?;
+    Ok(sk.sign_messages_with_rng(&pk, &messages, rng)?.to_bytes())
 }
 
 // ============================================================================
 // Safe Rust Verification API
 // ============================================================================
 
-#[derive(Debug, uniffi::Error)]
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Error)]
 pub enum VerifyError {
     InvalidKey,
     InvalidSignature,
     CryptoError,
+    /// Proof version byte is missing or not supported
+    UnsupportedProofVersion,
+    /// Attribute missing, unknown or not of the type its schema declares
+    SchemaMismatch,
+    /// Public key rejected by the parser; `field` is "length", "w" or "h<i>"
+    KeyParse { field: String, reason: String },
+    /// Proof bytes are malformed or do not fit the statement
+    ProofStructure { field: String },
+    /// Recomputed Fiat-Shamir challenge differs from the proof's
+    ChallengeMismatch,
+    /// A pairing equation does not hold
+    PairingFailure,
+    /// A revealed index is not below the message count
+    RevealedIndexOutOfRange { index: u32, message_count: u64 },
+    /// Delegation token presented after its expiration (Unix seconds)
+    TokenExpired { expiration: u64, now: u64 },
+    /// Presentation was made for another nonce or site
+    ContextMismatch { field: String },
+    /// The entropy source failed; nothing was signed or proven
+    EntropyUnavailable { reason: String },
+    /// Proof was scoped to another epoch than the verifier's current one
+    EpochMismatch { expected: Vec<u8>, presented: Vec<u8> },
+    /// Epoch schedule period is zero seconds
+    InvalidEpochPeriod,
+    /// A status list index is not below the list length (in bits)
+    StatusIndexOutOfRange { index: u64, length: u64 },
+    /// Status list length in bits is below MIN_STATUS_LIST_BITS or above
+    /// the decompressed size limit
+    StatusListLength { length: u64 },
+    /// A range predicate refers to a message the proof reveals
+    PredicateOnRevealedMessage { index: u32 },
+    /// The message a range predicate refers to is not integer-encoded
+    NotAnIntegerMessage { index: u32 },
+    /// The hidden value lies outside the predicate's bounds
+    PredicateUnsatisfied { index: u32 },
+    /// The last message, the holder's nym secret, is missing or revealed
+    NymSecretNotHidden,
+}
+
+impl VerifyError {
+    /// Stable machine-readable code, e.g. for mobile UIs and server logs
+    pub fn code(&self) -> &'static str {
+        match self {
+            VerifyError::InvalidKey => "invalid_key",
+            VerifyError::InvalidSignature => "invalid_signature",
+            VerifyError::CryptoError => "crypto_error",
+            VerifyError::UnsupportedProofVersion => "unsupported_proof_version",
+            VerifyError::SchemaMismatch => "schema_mismatch",
+            VerifyError::KeyParse { .. } => "key_parse",
+            VerifyError::ProofStructure { .. } => "proof_structure",
+            VerifyError::ChallengeMismatch => "challenge_mismatch",
+            VerifyError::PairingFailure => "pairing_failure",
+            VerifyError::RevealedIndexOutOfRange { .. } => "revealed_index_out_of_range",
+            VerifyError::TokenExpired { .. } => "token_expired",
+            VerifyError::ContextMismatch { .. } => "context_mismatch",
+            VerifyError::EntropyUnavailable { .. } => "entropy_unavailable",
+            VerifyError::EpochMismatch { .. } => "epoch_mismatch",
+            VerifyError::InvalidEpochPeriod => "invalid_epoch_period",
+            VerifyError::StatusIndexOutOfRange { .. } => "status_index_out_of_range",
+            VerifyError::StatusListLength { .. } => "status_list_length",
+            VerifyError::PredicateOnRevealedMessage { .. } => "predicate_on_revealed_message",
+            VerifyError::NotAnIntegerMessage { .. } => "not_an_integer_message",
+            VerifyError::PredicateUnsatisfied { .. } => "predicate_unsatisfied",
+            VerifyError::NymSecretNotHidden => "nym_secret_not_hidden",
+        }
+    }
+
+    pub(crate) fn proof_structure(field: &str) -> Self {
+        VerifyError::ProofStructure { field: field.to_string() }
+    }
 }
 
 impl std::fmt::Display for VerifyError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        write!(f, "{}: {:?}", self.code(), self)
     }
 }
 
 impl std::error::Error for VerifyError {}
 
-#[uniffi::export]
-pub fn verify_signature_safe(
-    public_key: Vec<u8>,
-    signature: Vec<u8>,
-    messages: Vec<Vec<u8>>,
-) -> Result<bool, VerifyError> {
-    // Parse public key
-    if public_key.len() < 96 {
-        return Err(VerifyError::InvalidKey);
-    }
-    
-    // Parse w
-    let w_bytes: [u8; 96] = public_key[..96].try_into().map_err(|_| VerifyError::InvalidKey)?;
-    let w_opt = G2Affine::from_compressed(&w_bytes);
-    if !bool::from(w_opt.is_some()) {
-        return Err(VerifyError::InvalidKey);
-    }
-    let w = w_opt.unwrap();
-    
-    // Parse h values
-    let mut h_values: Vec<G1Affine> = Vec::new();
-    let mut offset = 96;
-    while offset + 48 <= public_key.len() {
-        let h_bytes: [u8; 48] = public_key[offset..offset+48].try_into().map_err(|_| VerifyError::InvalidKey)?;
-        let h_opt = G1Affine::from_compressed(&h_bytes);
-        if bool::from(h_opt.is_some()) {
-            h_values.push(h_opt.unwrap());
-        }
-        offset += 48;
-    }
-
-    if h_values.len() < messages.len() + 1 { // h0 + h1..hn-1
-        return Err(VerifyError::InvalidKey);
+impl From<periwinkle::EntropyError> for VerifyError {
+    fn from(e: periwinkle::EntropyError) -> Self {
+        VerifyError::EntropyUnavailable { reason: e.to_string() }
     }
+}
 
-    // Parse signature
-    if signature.len() != 112 {
-        return Err(VerifyError::InvalidSignature);
-    }
-    
-    let a_bytes: [u8; 48] = signature[..48].try_into().unwrap();
-    let e_bytes: [u8; 32] = signature[48..80].try_into().unwrap();
-    let s_bytes: [u8; 32] = signature[80..112].try_into().unwrap();
-    
-    let a_opt = G1Affine::from_compressed(&a_bytes);
-    if !bool::from(a_opt.is_some()) {
-        return Err(VerifyError::InvalidSignature);
-    }
-    let a = a_opt.unwrap();
-    
-    let e_opt = Scalar::from_bytes(&e_bytes);
-    let s_opt = Scalar::from_bytes(&s_bytes);
-    if !bool::from(e_opt.is_some()) || !bool::from(s_opt.is_some()) {
-         return Err(VerifyError::InvalidSignature);
+/// Reports a failed check of a well-formed proof as Ok(false), the way the
+/// bool-returning verifiers always have
+pub(crate) fn checked(result: Result<(), VerifyError>) -> Result<bool, VerifyError> {
+    match result {
+        Ok(()) => Ok(true),
+        Err(VerifyError::ChallengeMismatch | VerifyError::PairingFailure | VerifyError::ContextMismatch { .. }) => {
+            Ok(false)
+        }
+        Err(e) => Err(e),
     }
-    let e = e_opt.unwrap();
-    let s = s_opt.unwrap();
+}
 
-    // Map messages to scalars
-    let msg_scalars: Vec<Scalar> = messages.iter().map(|m| hash_to_scalar(m)).collect();
+/// Outcome of a verification, naming the check that failed
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct Verdict {
+    pub valid: bool,
+    /// "ok", or the `VerifyError::code` of the failed check
+    pub code: String,
+    /// The error with its fields, empty when valid
+    pub detail: String,
+}
 
-    // Verify: e(A, w * g2^e) == e(B, g2)
-    // where B = g1 + h0*s + sum(hi * mi)
-    
-    let g1 = G1Projective::generator();
-    let mut b = g1;
-    
-    if !h_values.is_empty() {
-        b = b + G1Projective::from(h_values[0]) * s;
-    }
-    
-    for (i, m) in msg_scalars.iter().enumerate() {
-        if i + 1 < h_values.len() {
-             b = b + G1Projective::from(h_values[i + 1]) * m;
+impl From<Result<(), VerifyError>> for Verdict {
+    fn from(result: Result<(), VerifyError>) -> Self {
+        match result {
+            Ok(()) => Verdict { valid: true, code: "ok".to_string(), detail: String::new() },
+            Err(e) => Verdict { valid: false, code: e.code().to_string(), detail: format!("{:?}", e) },
         }
     }
+}
 
-    let g2 = G2Projective::generator();
-    let left = bls12_381::pairing(&a, &(w + g2 * e).to_affine());
-    let right = bls12_381::pairing(&b.to_affine(), &g2.to_affine());
-    
-    Ok(left == right)
+#[uniffi::export]
+pub fn verify_signature_safe(
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+) -> Result<bool, VerifyError> {
+    let pk = BbsPublicKey::parse(&public_key)?;
+    let sig = BbsSignature::parse(&signature)?;
+    pk.verify_signature(&sig, &messages)
 }
 
 
@@ -265,7 +461,11 @@ pub fn verify_signature_safe(
 // Selective Disclosure Proof (REAL ZKP Implementation)
 // ============================================================================
 
+/// Proof revealing `revealed_indices` and deriving the pseudonym for
+/// `site_id` from the last message, the nym secret. That message must stay
+/// hidden, otherwise the call fails with NymSecretNotHidden.
 #[uniffi::export]
+#[allow(clippy::too_many_arguments)]
 pub fn create_proof(
     public_key: Vec<u8>,
     signature: Vec<u8>,
@@ -273,134 +473,56 @@ pub fn create_proof(
     revealed_indices: Vec<u32>,
     nonce: Option<Vec<u8>>,
     site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
     alias_index: u64,
-    blinding_factor: Option<Vec<u8>>,
     freshness_claim: Option<Vec<u8>>,
 ) -> Result<Vec<u8>, VerifyError> {
-    // Parse public key
-    if public_key.len() < 96 { return Err(VerifyError::InvalidKey); }
-    let mut h_values: Vec<G1Affine> = Vec::new();
-    let mut offset = 96;
-    while offset + 48 <= public_key.len() {
-        let h_bytes: [u8; 48] = public_key[offset..offset+48].try_into().map_err(|_| VerifyError::InvalidKey)?;
-        let h_opt = G1Affine::from_compressed(&h_bytes);
-        if bool::from(h_opt.is_some()) {
-            h_values.push(h_opt.unwrap());
-        }
-        offset += 48;
-    }
-
-    // Parse signature
-    if signature.len() != 112 { return Err(VerifyError::InvalidSignature); }
-    let a_bytes: [u8; 48] = signature[..48].try_into().unwrap();
-    let a = G1Affine::from_compressed(&a_bytes).into_option().ok_or(VerifyError::InvalidSignature)?;
-    let e_bytes: [u8; 32] = signature[48..80].try_into().unwrap();
-    let e = Scalar::from_bytes(&e_bytes).into_option().ok_or(VerifyError::InvalidSignature)?;
-    let s_bytes: [u8; 32] = signature[80..112].try_into().unwrap();
-    let s = Scalar::from_bytes(&s_bytes).into_option().ok_or(VerifyError::InvalidSignature)?;
-
-    let msg_scalars: Vec<Scalar> = messages.iter().map(|m| hash_to_scalar(m)).collect();
-
-    // Linkage Tag
-    let sk_scalar = if let Some(bf) = &blinding_factor {
-         let mut bf_arr = [0u8; 32];
-         // Need careful handling of vec to array
-         let len = std::cmp::min(bf.len(), 32);
-         bf_arr[0..len].copy_from_slice(&bf[0..len]);
-         // Wait, hash_to_scalar handles slice
-         hash_to_scalar(bf)
-    } else {
-         let hw_secret_bytes = periwinkle::get_hardware_secret(b"LinkageTag");
-         hash_to_scalar(&hw_secret_bytes)
-    };
-    let linkage_tag = generate_linkage_tag(sk_scalar, &site_id);
-
-    // ZK Proof Generation
-    let ent_r1 = get_entropy();
-    let r1 = Scalar::from_bytes_wide(&ent_r1);
-    let ent_r2 = get_entropy();
-    let mut r2 = Scalar::from_bytes_wide(&ent_r2);
-    
-    if let Some(bf) = &blinding_factor {
-        // If blinding factor provided (from blind issuance), incorporate it
-        let bf_scalar = hash_to_scalar(bf);
-        r2 = r2 + bf_scalar; // Simplified? Check original logic carefuly
-        // Original: "r2 = r2 + bf_opt.unwrap()" where bf was converted from bytes
-        // The logic assumes bf IS the scalar s used in blind signature (or related).
-        // Let's stick to hash_to_scalar for safety if size mismatch
-    }
-
-    let a_prime = (G1Projective::from(a) * r1).to_affine();
-    let abar = if !h_values.is_empty() {
-        (G1Projective::from(a_prime) - G1Projective::from(h_values[0]) * r2).to_affine()
-    } else { a_prime };
-
-    let d = s * r1 + r2;
-    let r1_inv = r1.invert().into_option().ok_or(VerifyError::CryptoError)?;
-    let e_tilde = e * r1_inv;
-
-    let mut commitments: Vec<G1Projective> = Vec::new();
-    let mut hidden_randomness: Vec<Scalar> = Vec::new();
-
-    let revealed_indices_set: std::collections::HashSet<u32> = revealed_indices.iter().cloned().collect();
-
-    for i in 0..messages.len() {
-        if !revealed_indices_set.contains(&(i as u32)) {
-             let ent_rm = get_entropy();
-             let r_m = Scalar::from_bytes_wide(&ent_rm);
-             hidden_randomness.push(r_m);
-             if i + 1 < h_values.len() {
-                 commitments.push(G1Projective::from(h_values[i + 1]) * r_m);
-             }
-        }
-    }
-    
-    let mut c_total = G1Projective::identity();
-    for c in &commitments { c_total = c_total + c; }
-
-    // Fiat-Shamir
-    let mut challenge_data = Vec::new();
-    challenge_data.extend_from_slice(&a_prime.to_compressed());
-    challenge_data.extend_from_slice(&abar.to_compressed());
-    challenge_data.extend_from_slice(&d.to_bytes());
-    if let Some(n) = &nonce { challenge_data.extend_from_slice(n); }
-    challenge_data.extend_from_slice(&linkage_tag.to_compressed());
-    challenge_data.extend_from_slice(&(alias_index as u64).to_le_bytes());
-    if let Some(fc) = &freshness_claim { challenge_data.extend_from_slice(fc); }
-    
-    let challenge = hash_to_scalar(&challenge_data);
-
-    // Responses
-    let mut responses: Vec<Scalar> = Vec::new();
-    let mut hidden_idx = 0;
-    for i in 0..messages.len() {
-        if !revealed_indices_set.contains(&(i as u32)) {
-             if hidden_idx < hidden_randomness.len() {
-                 let response = hidden_randomness[hidden_idx] + challenge * msg_scalars[i];
-                 responses.push(response);
-                 hidden_idx += 1;
-             }
-        }
-    }
+    create_proof_with_rng(
+        public_key,
+        signature,
+        messages,
+        revealed_indices,
+        nonce,
+        site_id,
+        epoch,
+        alias_index,
+        freshness_claim,
+        &mut PeriwinkleRng,
+    )
+}
 
-    // Serialize
-    let mut proof = Vec::new();
-    proof.extend_from_slice(&a_prime.to_compressed());
-    proof.extend_from_slice(&abar.to_compressed());
-    proof.extend_from_slice(&e_tilde.to_bytes());
-    proof.extend_from_slice(&d.to_bytes());
-    proof.extend_from_slice(&challenge.to_bytes());
-    proof.extend_from_slice(&linkage_tag.to_compressed());
-    proof.extend_from_slice(&(responses.len() as u32).to_le_bytes());
-    for r in responses {
-        proof.extend_from_slice(&r.to_bytes());
-    }
-    
-    Ok(proof)
+#[allow(clippy::too_many_arguments)]
+pub fn create_proof_with_rng<R: RngCore + CryptoRng>(
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+    revealed_indices: Vec<u32>,
+    nonce: Option<Vec<u8>>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+    rng: &mut R,
+) -> Result<Vec<u8>, VerifyError> {
+    let pk = BbsPublicKey::parse(&public_key)?;
+    let sig = BbsSignature::parse(&signature)?;
+    let proof = sig.prove_with_rng(
+        &pk,
+        &messages,
+        &revealed_indices,
+        nonce.as_deref(),
+        &site_id,
+        epoch.as_deref(),
+        alias_index,
+        freshness_claim.as_deref(),
+        rng,
+    )?;
+    Ok(proof.to_bytes())
 }
 
 // Same as verify_signature_safe but for ZKP Proofs
 #[uniffi::export]
+#[allow(clippy::too_many_arguments)]
 pub fn verify_proof_safe(
     public_key: Vec<u8>,
     proof: Vec<u8>,
@@ -408,194 +530,154 @@ pub fn verify_proof_safe(
     revealed_indices: Vec<u32>,
     revealed_messages_content: Vec<Vec<u8>>,
     nonce: Vec<u8>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
     alias_index: u64,
     freshness_claim: Option<Vec<u8>>,
 ) -> Result<bool, VerifyError> {
-    
-    // Reconstruct revealed messages map
-    if revealed_indices.len() != revealed_messages_content.len() {
-        return Err(VerifyError::InvalidSignature);
-    }
-    
-    let mut revealed_messages: Vec<(usize, Vec<u8>)> = Vec::new();
-    for (i, idx) in revealed_indices.iter().enumerate() {
-        revealed_messages.push((*idx as usize, revealed_messages_content[i].clone()));
-    }
-
-    let total_message_count = total_message_count as usize;
-    let alias_index = alias_index as usize;
-    let freshness_claim_ref = freshness_claim.as_deref();
-    
-    // Parse public key
-    // Parse public key
-    if public_key.len() < 96 {
-        return Err(VerifyError::InvalidKey);
-    }
-    
-    // Parse w
-    let w_bytes: [u8; 96] = public_key[..96].try_into().map_err(|_| VerifyError::InvalidKey)?;
-    let w_opt = G2Affine::from_compressed(&w_bytes);
-    if !bool::from(w_opt.is_some()) {
-        return Err(VerifyError::InvalidKey);
-    }
-    let w = w_opt.unwrap();
-    
-    // Parse h values
-    let mut h_values: Vec<G1Affine> = Vec::new();
-    let mut offset = 96;
-    while offset + 48 <= public_key.len() {
-        let h_bytes: [u8; 48] = public_key[offset..offset+48].try_into().map_err(|_| VerifyError::InvalidKey)?;
-        let h_opt = G1Affine::from_compressed(&h_bytes);
-        if bool::from(h_opt.is_some()) {
-            h_values.push(h_opt.unwrap());
-        }
-        offset += 48;
-    }
-
-    // Parse proof
-    // [Implementation note: freshness_claim was moved to logic variable `freshness_claim_ref` to handle Option<Vec> ownership]
-    
-    if proof.len() < 196 {
-         return Err(VerifyError::InvalidSignature); // InvalidProof
-    }
-
-    let a_prime_bytes: [u8; 48] = proof[..48].try_into().unwrap();
-    let abar_bytes: [u8; 48] = proof[48..96].try_into().unwrap();
-    let e_tilde_bytes: [u8; 32] = proof[96..128].try_into().unwrap();
-    let d_bytes: [u8; 32] = proof[128..160].try_into().unwrap();
-    let challenge_bytes: [u8; 32] = proof[160..192].try_into().unwrap();
-    let linkage_tag_bytes: [u8; 48] = proof[192..240].try_into().map_err(|_| VerifyError::InvalidSignature)?;
-    let response_count_bytes: [u8; 4] = proof[240..244].try_into().unwrap();
-    let response_count = u32::from_le_bytes(response_count_bytes) as usize;
-    // CRITICAL SECURITY FIX: Bound response_count to prevent DoS via memory exhaustion
-    if response_count > total_message_count + 10 { // Allow reasonably small overhead
-        return Err(VerifyError::InvalidSignature);
-    }
-
-    let a_prime_opt = G1Affine::from_compressed(&a_prime_bytes);
-    let abar_opt = G1Affine::from_compressed(&abar_bytes);
-    let e_tilde_opt = Scalar::from_bytes(&e_tilde_bytes);
-    let d_opt = Scalar::from_bytes(&d_bytes);
-    let challenge_opt = Scalar::from_bytes(&challenge_bytes);
-    let linkage_tag_opt = G1Affine::from_compressed(&linkage_tag_bytes);
-
-    if !bool::from(a_prime_opt.is_some()) || !bool::from(abar_opt.is_some()) 
-        || !bool::from(e_tilde_opt.is_some()) || !bool::from(d_opt.is_some()) 
-        || !bool::from(challenge_opt.is_some()) || !bool::from(linkage_tag_opt.is_some()) {
-        return Err(VerifyError::InvalidSignature);
-    }
-
-    let a_prime = a_prime_opt.into_option().ok_or(VerifyError::InvalidSignature)?;
-    let abar = abar_opt.into_option().ok_or(VerifyError::InvalidSignature)?;
-    let e_tilde = e_tilde_opt.into_option().ok_or(VerifyError::InvalidSignature)?;
-    let d = d_opt.into_option().ok_or(VerifyError::InvalidSignature)?;
-    let challenge = challenge_opt.into_option().ok_or(VerifyError::InvalidSignature)?;
-    let linkage_tag = linkage_tag_opt.into_option().ok_or(VerifyError::InvalidSignature)?;
-
-    // Parse responses
-    let mut responses: Vec<Scalar> = Vec::with_capacity(response_count);
-    let mut resp_offset = 244;
-    for _ in 0..response_count {
-        if resp_offset + 32 > proof.len() {
-            return Err(VerifyError::InvalidSignature);
-        }
-        let resp_bytes: [u8; 32] = proof[resp_offset..resp_offset+32].try_into().unwrap();
-        let resp_opt = Scalar::from_bytes(&resp_bytes);
-        if !bool::from(resp_opt.is_some()) {
-             return Err(VerifyError::InvalidSignature);
-        }
-        responses.push(resp_opt.unwrap());
-        resp_offset += 32;
-    }
-
-    // Convert revealed messages to scalars
-    let mut revealed_scalars: Vec<Scalar> = Vec::new();
-    let mut revealed_idxs: Vec<usize> = Vec::new();
-    
-    for (idx, msg) in revealed_messages {
-        revealed_idxs.push(idx);
-        revealed_scalars.push(hash_to_scalar(&msg));
-    }
-
-    // **VERIFY ZERO-KNOWLEDGE PROOF**
-
-    // Recompute commitment from responses: C' = sum(h_i * s_i) - challenge * sum(h_i * m_i_revealed)
-    let mut c_recomputed = G1Projective::identity();
-
-    // Add response contributions for hidden messages
-    let mut resp_idx = 0;
-    for i in 0..total_message_count {
-        if !revealed_idxs.contains(&i) {
-            // Hidden message - use response
-            if resp_idx < responses.len() && i + 1 < h_values.len() {
-                c_recomputed = c_recomputed + G1Projective::from(h_values[i + 1]) * responses[resp_idx];
-                resp_idx += 1;
-            }
-        }
-    }
-
-    // Subtract challenge * revealed messages
-    for (idx, revealed_idx) in revealed_idxs.iter().enumerate() {
-        if idx < revealed_scalars.len() && *revealed_idx + 1 < h_values.len() {
-            c_recomputed = c_recomputed - G1Projective::from(h_values[*revealed_idx + 1]) * (challenge * revealed_scalars[idx]);
-        }
-    }
-
-    // Recompute Fiat-Shamir challenge
-    let mut challenge_data = Vec::new();
-    challenge_data.extend_from_slice(&a_prime.to_compressed());
-    challenge_data.extend_from_slice(&abar.to_compressed());
-    challenge_data.extend_from_slice(&d.to_bytes());
-    
-    if !nonce.is_empty() {
-        challenge_data.extend_from_slice(&nonce);
-    }
-
-    challenge_data.extend_from_slice(&linkage_tag.to_compressed());
-    challenge_data.extend_from_slice(&(alias_index as u64).to_le_bytes());
-    
-    if let Some(fc) = freshness_claim_ref {
-        challenge_data.extend_from_slice(fc);
-    }
-
-    let challenge_check = hash_to_scalar(&challenge_data);
-
-    // Verify challenge matches
-    // Verify challenge matches
-    if challenge != challenge_check {
-        println!("DEBUG: Challenge Mismatch!");
-        println!("Computed: {:?}", challenge_check);
-        println!("Expected: {:?}", challenge);
-        println!("Challenge Data Len: {}", challenge_data.len());
-        println!("Proof Len: {}", proof.len());
-        println!("Tag Present: {}", resp_offset + 32 <= proof.len());
-        return Ok(false);
-    }
-
-    // Verify pairing: e(A', w * g2^e_tilde) == e(Abar + h0*d + sum(h_i * m_i_revealed), g2)
-    let g2 = G2Projective::generator();
-    let w_g2e = G2Projective::from(w) + g2 * e_tilde;
-
-    let mut rhs_sum = G1Projective::from(abar);
+    let pk = BbsPublicKey::parse(&public_key)?;
+    let proof = BbsProof::parse(&proof)?;
+    pk.verify_proof_of(
+        &proof,
+        total_message_count as usize,
+        &revealed_indices,
+        &revealed_messages_content,
+        &nonce,
+        &site_id,
+        epoch.as_deref(),
+        alias_index,
+        freshness_claim.as_deref(),
+    )
+}
 
-    // Add h0 * d
-    if !h_values.is_empty() {
-        rhs_sum = rhs_sum + G1Projective::from(h_values[0]) * d;
-    }
+/// `verify_proof_safe` reporting which check failed instead of false
+#[uniffi::export]
+#[allow(clippy::too_many_arguments)]
+pub fn verify_proof_verdict(
+    public_key: Vec<u8>,
+    proof: Vec<u8>,
+    total_message_count: u64,
+    revealed_indices: Vec<u32>,
+    revealed_messages_content: Vec<Vec<u8>>,
+    nonce: Vec<u8>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+) -> Verdict {
+    let check = || {
+        let pk = BbsPublicKey::parse(&public_key)?;
+        let proof = BbsProof::parse(&proof)?;
+        pk.check_proof_with_predicates(
+            &proof,
+            total_message_count as usize,
+            &revealed_indices,
+            &revealed_messages_content,
+            &[],
+            &nonce,
+            &site_id,
+            epoch.as_deref(),
+            alias_index,
+            freshness_claim.as_deref(),
+        )
+    };
+    check().into()
+}
 
-    // Add revealed messages
-    for (idx, revealed_idx) in revealed_idxs.iter().enumerate() {
-        if idx < revealed_scalars.len() && *revealed_idx + 1 < h_values.len() {
-            rhs_sum = rhs_sum + G1Projective::from(h_values[*revealed_idx + 1]) * revealed_scalars[idx];
-        }
-    }
 
-    let lhs = bls12_381::pairing(&a_prime, &w_g2e.to_affine());
-    let rhs = bls12_381::pairing(&rhs_sum.to_affine(), &G2Affine::generator());
+/// `create_proof` with a range proof for each predicate over a hidden,
+/// integer-encoded message (see `encode_integer_message`)
+#[uniffi::export]
+#[allow(clippy::too_many_arguments)]
+pub fn create_predicate_proof(
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+    revealed_indices: Vec<u32>,
+    predicates: Vec<RangePredicate>,
+    nonce: Option<Vec<u8>>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+) -> Result<Vec<u8>, VerifyError> {
+    create_predicate_proof_with_rng(
+        public_key,
+        signature,
+        messages,
+        revealed_indices,
+        predicates,
+        nonce,
+        site_id,
+        epoch,
+        alias_index,
+        freshness_claim,
+        &mut PeriwinkleRng,
+    )
+}
 
-    Ok(lhs == rhs)
+#[allow(clippy::too_many_arguments)]
+pub fn create_predicate_proof_with_rng<R: RngCore + CryptoRng>(
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+    revealed_indices: Vec<u32>,
+    predicates: Vec<RangePredicate>,
+    nonce: Option<Vec<u8>>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+    rng: &mut R,
+) -> Result<Vec<u8>, VerifyError> {
+    let pk = BbsPublicKey::parse(&public_key)?;
+    let sig = BbsSignature::parse(&signature)?;
+    let proof = sig.prove_with_predicates_and_rng(
+        &pk,
+        &messages,
+        &revealed_indices,
+        &predicates,
+        nonce.as_deref(),
+        &site_id,
+        epoch.as_deref(),
+        alias_index,
+        freshness_claim.as_deref(),
+        rng,
+    )?;
+    Ok(proof.to_bytes())
 }
 
+/// `verify_proof_safe` that also checks every predicate the verifier asked for
+#[uniffi::export]
+#[allow(clippy::too_many_arguments)]
+pub fn verify_predicate_proof(
+    public_key: Vec<u8>,
+    proof: Vec<u8>,
+    total_message_count: u64,
+    revealed_indices: Vec<u32>,
+    revealed_messages_content: Vec<Vec<u8>>,
+    predicates: Vec<RangePredicate>,
+    nonce: Vec<u8>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+) -> Result<bool, VerifyError> {
+    let pk = BbsPublicKey::parse(&public_key)?;
+    let proof = BbsProof::parse(&proof)?;
+    pk.verify_proof_with_predicates(
+        &proof,
+        total_message_count as usize,
+        &revealed_indices,
+        &revealed_messages_content,
+        &predicates,
+        &nonce,
+        &site_id,
+        epoch.as_deref(),
+        alias_index,
+        freshness_claim.as_deref(),
+    )
+}
 
 // ============================================================================
 // Memory Management
@@ -608,8 +690,6 @@ pub fn verify_proof_safe(
 // ============================================================================
 
 // pub mod miner;
-pub mod periwinkle; // Hardware entropy source
-pub mod attestation; // Mobile Hardware Attestation (Phase 7) FFI
 // [Removed re-exports of miner functions]
 
 // ============================================================================
@@ -650,39 +730,25 @@ pub fn sign_delegation(
     pk_bytes: Vec<u8>,
     token: DelegationToken
 ) -> Result<Vec<u8>, VerifyError> {
-    let sk = Scalar::from_bytes(sk_bytes.as_slice().try_into().map_err(|_| VerifyError::InvalidKey)?).unwrap();
-    
+    sign_delegation_with_rng(sk_bytes, pk_bytes, token, &mut PeriwinkleRng)
+}
+
+pub fn sign_delegation_with_rng<R: RngCore + CryptoRng>(
+    sk_bytes: Vec<u8>,
+    pk_bytes: Vec<u8>,
+    token: DelegationToken,
+    rng: &mut R,
+) -> Result<Vec<u8>, VerifyError> {
+    let sk = BbsSecretKey::from_bytes(sk_bytes)?;
     // PK: w, h0, h1
-    if pk_bytes.len() < 192 { return Err(VerifyError::InvalidKey); }
-    
-    let mut h = Vec::new();
-    for i in 0..2 {
-        let offset = 96 + i*48;
-         let h_bytes: [u8; 48] = pk_bytes[offset..offset+48].try_into().map_err(|_| VerifyError::InvalidKey)?;
-         let h_point = G1Affine::from_compressed(&h_bytes).into_option().ok_or(VerifyError::InvalidKey)?;
-         h.push(G1Projective::from(h_point));
-    }
-    
+    let pk = BbsPublicKey::parse(&pk_bytes)?;
+
     let mut hasher = Sha256::new();
     hasher.update(token.to_bytes());
-    let m_scalar = hash_to_scalar(&hasher.finalize());
-    
-    let mut rng = thread_rng();
-    let e = Scalar::random(&mut rng);
-    let s = Scalar::random(&mut rng);
-    
+    let token_hash = hasher.finalize().to_vec();
+
     // B = g1 + h0*s + h1*m
-    let g1 = G1Projective::generator();
-    let b = g1 + h[0]*s + h[1]*m_scalar;
-    
-    let inv = (sk + e).invert().into_option().ok_or(VerifyError::CryptoError)?;
-    let a = b * inv;
-    
-    let mut sig = Vec::new();
-    sig.extend_from_slice(&a.to_affine().to_compressed());
-    sig.extend_from_slice(&e.to_bytes());
-    sig.extend_from_slice(&s.to_bytes());
-    Ok(sig)
+    Ok(sk.sign_messages_with_rng(&pk, &[token_hash], rng)?.to_bytes())
 }
 
 /// Verifies a Delegation Token Signature (Anchor PK -> Token)
@@ -692,42 +758,41 @@ pub fn verify_delegation_signature(
     token: DelegationToken,
     sig_bytes: Vec<u8>
 ) -> Result<bool, VerifyError> {
-    if sig_bytes.len() != 112 { return Err(VerifyError::InvalidSignature); }
-    
-    // Load Sig: A, e, s
-    let a_opt = G1Affine::from_compressed(sig_bytes[0..48].try_into().unwrap());
-    let a = a_opt.into_option().ok_or(VerifyError::InvalidSignature)?;
-    let e_opt = Scalar::from_bytes(sig_bytes[48..80].try_into().unwrap());
-    let e = e_opt.into_option().ok_or(VerifyError::InvalidSignature)?;
-    let s_opt = Scalar::from_bytes(sig_bytes[80..112].try_into().unwrap());
-    let s = s_opt.into_option().ok_or(VerifyError::InvalidSignature)?;
-    
-    // Load PK: w, h0, h1
-    if pk_bytes.len() < 192 { return Err(VerifyError::InvalidKey); }
-    let w_bytes: [u8; 96] = pk_bytes[0..96].try_into().map_err(|_| VerifyError::InvalidKey)?;
-    let w_point = G2Affine::from_compressed(&w_bytes).into_option().ok_or(VerifyError::InvalidKey)?;
-    let w = G2Projective::from(w_point);
-    let mut h = Vec::new();
-    for i in 0..2 {
-        let offset = 96 + i*48;
-         let h_bytes: [u8; 48] = pk_bytes[offset..offset+48].try_into().map_err(|_| VerifyError::InvalidKey)?;
-         let h_point = G1Affine::from_compressed(&h_bytes).into_option().ok_or(VerifyError::InvalidKey)?;
-         h.push(G1Projective::from(h_point));
-    }
-    
+    let sig = BbsSignature::parse(&sig_bytes)?;
+    let pk = BbsPublicKey::parse(&pk_bytes)?;
+
     let mut hasher = Sha256::new();
     hasher.update(token.to_bytes());
-    let m = hash_to_scalar(&hasher.finalize());
-    
+    let token_hash = hasher.finalize().to_vec();
+
     // Check: e(A, w + g2*e) == e(g1 + h0*s + h1*m, g2)
-    let g1 = G1Projective::generator();
-    let g2 = G2Projective::generator();
-    
-    let lhs = bls12_381::pairing(&a, &(w + g2*e).to_affine());
-    let rhs_g1 = g1 + h[0]*s + h[1]*m;
-    let rhs = bls12_381::pairing(&rhs_g1.to_affine(), &G2Affine::generator());
-    
-    Ok(lhs == rhs)
+    pk.verify_signature(&sig, &[token_hash])
+}
+
+impl DelegationToken {
+    /// Signature and expiration check against `clock`
+    pub fn check(&self, public_key: &BbsPublicKey, signature: &BbsSignature, clock: &impl Clock) -> Result<(), VerifyError> {
+        let now = clock.now();
+        if now >= self.expiration {
+            return Err(VerifyError::TokenExpired { expiration: self.expiration, now });
+        }
+        let token_hash = Sha256::digest(self.to_bytes()).to_vec();
+        if !public_key.verify_signature(signature, &[token_hash])? {
+            return Err(VerifyError::PairingFailure);
+        }
+        Ok(())
+    }
+}
+
+/// Verifies a Delegation Token Signature and that the token has not expired
+#[uniffi::export]
+pub fn verify_delegation_verdict(pk_bytes: Vec<u8>, token: DelegationToken, sig_bytes: Vec<u8>) -> Verdict {
+    let check = || {
+        let pk = BbsPublicKey::parse(&pk_bytes)?;
+        let sig = BbsSignature::parse(&sig_bytes)?;
+        token.check(&pk, &sig, &SystemClock)
+    };
+    check().into()
 }
 
 // [Removed bbs_sign_delegation]
@@ -736,11 +801,19 @@ pub fn verify_delegation_signature(
 // ============================================================================
 
 /// Splits a scalar secret into N shares with threshold K
-pub fn split_secret(secret: &Scalar, n: u8, k: u8) -> Vec<(u8, Scalar)> {
-    let mut rng = thread_rng();
+pub fn split_secret(secret: &Scalar, n: u8, k: u8) -> Result<Vec<(u8, Scalar)>, VerifyError> {
+    split_secret_with_rng(secret, n, k, &mut PeriwinkleRng)
+}
+
+pub fn split_secret_with_rng<R: RngCore + CryptoRng>(
+    secret: &Scalar,
+    n: u8,
+    k: u8,
+    rng: &mut R,
+) -> Result<Vec<(u8, Scalar)>, VerifyError> {
     let mut coeffs = vec![*secret];
     for _ in 1..k {
-        coeffs.push(Scalar::random(&mut rng));
+        coeffs.push(random_scalar(rng)?);
     }
     
     let mut shares = Vec::new();
@@ -754,7 +827,7 @@ pub fn split_secret(secret: &Scalar, n: u8, k: u8) -> Vec<(u8, Scalar)> {
         }
         shares.push((x, y));
     }
-    shares
+    Ok(shares)
 }
 
 /// Reconstructs secret from K shares using Lagrange Interpolation
@@ -798,13 +871,22 @@ pub fn split_secret_safe(
     secret: Vec<u8>,
     threshold: u8,
     total: u8,
+) -> Result<Vec<Vec<u8>>, VerifyError> {
+    split_secret_safe_with_rng(secret, threshold, total, &mut PeriwinkleRng)
+}
+
+pub fn split_secret_safe_with_rng<R: RngCore + CryptoRng>(
+    secret: Vec<u8>,
+    threshold: u8,
+    total: u8,
+    rng: &mut R,
 ) -> Result<Vec<Vec<u8>>, VerifyError> {
     if secret.len() != 32 { return Err(VerifyError::InvalidKey); }
     let arr: [u8; 32] = secret.try_into().unwrap();
     let scalar_opt = Scalar::from_bytes(&arr);
     let scalar = if bool::from(scalar_opt.is_some()) { scalar_opt.unwrap() } else { return Err(VerifyError::InvalidKey); };
 
-    let shares = split_secret(&scalar, threshold, total);
+    let shares = split_secret_with_rng(&scalar, threshold, total, rng)?;
     
     let mut result = Vec::new();
     for (idx, s) in shares {
@@ -2,10 +2,15 @@
 // ========================================================
 // Implements k-anonymity buffer and ε-differential privacy for macro data
 
+use crate::accumulator::{self, AccumulatorUpdate};
+use crate::bbs::{BbsPublicKey, BbsSecretKey};
+use crate::status_list::{StatusList, StatusListCredential};
+use crate::periwinkle::PeriwinkleRng;
 use parking_lot::RwLock;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use sled::transaction::{ConflictableTransactionError, TransactionError, Transactional};
 use sled::Db;
 use statrs::distribution::{Laplace, ContinuousCDF};
 use std::collections::{HashMap, VecDeque};
@@ -29,6 +34,14 @@ pub struct MacroDataPoint {
     pub identity_hash: String,  // Blinded identity hash
 }
 
+/// One ε spend in the noise audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoiseAuditEntry {
+    pub action: String,
+    pub epsilon: f64,
+    pub timestamp: u64,
+}
+
 #[derive(Debug, Clone)]
 pub struct AnonymityBuffer {
     pub category: String,
@@ -135,7 +148,7 @@ impl MinerEngine {
             self.store_in_vault(&anonymized_data)?;
             
             // Audit Log: Record epsilon usage
-            self.log_noise_audit("SUBMIT_BATCH", self.epsilon);
+            self.log_noise_audit("SUBMIT_BATCH", self.epsilon)?;
 
             Ok(Some(anonymized_data))
         } else {
@@ -154,10 +167,31 @@ impl MinerEngine {
         }
     }
     
-    fn log_noise_audit(&self, action: &str, cost: f64) {
-        // In a real system, this goes to an append-only log file or blockchain
-        println!("[NOISE AUDIT] Action: {}, Cost: ε{}, Timestamp: {}", 
-            action, cost, Self::current_timestamp());
+    /// Append an epsilon spend to the vault's append-only audit tree
+    fn log_noise_audit(&self, action: &str, cost: f64) -> Result<(), String> {
+        let tree = self.vault.open_tree("noise_audit").map_err(|e| e.to_string())?;
+        let entry = NoiseAuditEntry {
+            action: action.to_string(),
+            epsilon: cost,
+            timestamp: Self::current_timestamp(),
+        };
+        let value = serde_json::to_vec(&entry)
+            .map_err(|e| format!("Failed to serialize audit entry: {}", e))?;
+        let id = self.vault.generate_id().map_err(|e| e.to_string())?;
+        tree.insert(id.to_be_bytes(), value).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Recorded epsilon spends, oldest first
+    pub fn noise_audit_log(&self) -> Result<Vec<NoiseAuditEntry>, String> {
+        let tree = self.vault.open_tree("noise_audit").map_err(|e| e.to_string())?;
+        tree.iter()
+            .values()
+            .map(|value| {
+                let value = value.map_err(|e| e.to_string())?;
+                serde_json::from_slice(&value).map_err(|e| format!("Failed to parse audit entry: {}", e))
+            })
+            .collect()
     }
 
     /// Apply Laplace noise for ε-differential privacy
@@ -309,6 +343,93 @@ impl MinerEngine {
         Ok(())
     }
 
+    // ========================================================================
+    // STATUS LISTS (Bitstring Status List)
+    // ========================================================================
+    // Lists share the revocations tree with linkage tags: "status_list:<id>"
+    // holds the raw bits and "status_list_next:<id>" the next unassigned index.
+
+    fn status_list_key(list_id: &str) -> String {
+        format!("status_list:{}", list_id)
+    }
+
+    fn status_list_next_key(list_id: &str) -> String {
+        format!("status_list_next:{}", list_id)
+    }
+
+    /// Creates an all-clear list of at least `length` entries. Returns false
+    /// if a list with this id already exists.
+    pub fn create_status_list(&self, list_id: &str, length: u64) -> Result<bool, String> {
+        let tree = self.vault.open_tree("revocations").map_err(|e| e.to_string())?;
+        let list = StatusList::new(length).map_err(|e| e.to_string())?;
+        let swap = tree
+            .compare_and_swap(Self::status_list_key(list_id), None as Option<&[u8]>, Some(list.as_bytes()))
+            .map_err(|e| format!("Failed to create status list: {}", e))?;
+        Ok(swap.is_ok())
+    }
+
+    pub fn status_list(&self, list_id: &str) -> Result<Option<StatusList>, String> {
+        let tree = self.vault.open_tree("revocations").map_err(|e| e.to_string())?;
+        match tree.get(Self::status_list_key(list_id)).map_err(|e| e.to_string())? {
+            Some(bits) => StatusList::from_bytes(bits.to_vec()).map(Some).map_err(|e| e.to_string()),
+            None => Ok(None),
+        }
+    }
+
+    /// Assigns the next free index of a list to a new credential
+    pub fn allocate_status_index(&self, list_id: &str) -> Result<u64, String> {
+        let length = self.status_list(list_id)?.ok_or("Unknown status list")?.len();
+        let tree = self.vault.open_tree("revocations").map_err(|e| e.to_string())?;
+        let decode = |v: Option<&[u8]>| v.and_then(|b| b.try_into().ok()).map(u64::from_be_bytes).unwrap_or(0);
+        let previous = tree
+            .fetch_and_update(Self::status_list_next_key(list_id), |old| {
+                let next = decode(old);
+                Some((if next < length { next + 1 } else { next }).to_be_bytes().to_vec())
+            })
+            .map_err(|e| format!("Status index error: {}", e))?;
+        let index = decode(previous.as_deref());
+        if index >= length {
+            return Err("Status list is full".to_string());
+        }
+        Ok(index)
+    }
+
+    /// Sets (revoked) or clears the status bit of one credential
+    pub fn set_credential_status(&self, list_id: &str, index: u64, status: bool) -> Result<(), String> {
+        let tree = self.vault.open_tree("revocations").map_err(|e| e.to_string())?;
+        let mut result = Err("Unknown status list".to_string());
+        // Read-modify-write in one atomic update, so concurrent revocations
+        // of different indices are not lost
+        tree.fetch_and_update(Self::status_list_key(list_id), |old| {
+            let bits = old?.to_vec();
+            match StatusList::from_bytes(bits.clone()) {
+                Ok(mut list) => {
+                    result = list.set(index, status).map_err(|_| "Status index out of range".to_string());
+                    Some(list.into_bytes())
+                }
+                Err(e) => {
+                    result = Err(e.to_string());
+                    Some(bits)
+                }
+            }
+        })
+        .map_err(|e| format!("Failed to update status list: {}", e))?;
+        result
+    }
+
+    /// Signs the current state of a list for publication
+    pub fn publish_status_list(
+        &self,
+        secret_key: &BbsSecretKey,
+        public_key: &BbsPublicKey,
+        list_id: &str,
+        status_purpose: &str,
+    ) -> Result<StatusListCredential, String> {
+        let list = self.status_list(list_id)?.ok_or("Unknown status list")?;
+        StatusListCredential::sign(secret_key, public_key, list_id, status_purpose, &list, Self::current_timestamp())
+            .map_err(|e| e.to_string())
+    }
+
     // ========================================================================
     // SESSION PERSISTENCE (Phase 3.2)
     // ========================================================================
@@ -391,6 +512,170 @@ impl MinerEngine {
         tree.remove(code).map_err(|e| e.to_string())?;
         Ok(())
     }
+
+    // ========================================================================
+    // NULLIFIER STORE (Rate-Limited Proofs)
+    // ========================================================================
+
+    /// Scope prefix of every nullifier seen at `site_id` in `epoch`
+    fn nullifier_scope(site_id: &[u8], epoch: &[u8]) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update((site_id.len() as u64).to_be_bytes());
+        hasher.update(site_id);
+        hasher.update(epoch);
+        hasher.finalize().to_vec()
+    }
+
+    /// Records the nullifier of a verified rate-limited proof. Returns false
+    /// when it was already recorded, i.e. the holder exceeded the limit.
+    pub fn record_nullifier(&self, site_id: &[u8], epoch: &[u8], nullifier: &[u8]) -> Result<bool, String> {
+        let tree = self.vault.open_tree("nullifiers").map_err(|e| e.to_string())?;
+        let mut key = Self::nullifier_scope(site_id, epoch);
+        key.extend_from_slice(nullifier);
+        let seen_at = Self::current_timestamp().to_be_bytes();
+        // Insert-if-absent, so concurrent presentations cannot both pass
+        let swap = tree
+            .compare_and_swap(key, None as Option<&[u8]>, Some(&seen_at[..]))
+            .map_err(|e| format!("Nullifier store error: {}", e))?;
+        Ok(swap.is_ok())
+    }
+
+    /// Drops the nullifiers of an epoch that can no longer be presented.
+    /// The removals are applied as one batch, so a failure leaves the epoch
+    /// either fully recorded or fully forgotten.
+    pub fn forget_nullifiers(&self, site_id: &[u8], epoch: &[u8]) -> Result<usize, String> {
+        let tree = self.vault.open_tree("nullifiers").map_err(|e| e.to_string())?;
+        let mut batch = sled::Batch::default();
+        let mut removed = 0;
+        for entry in tree.scan_prefix(Self::nullifier_scope(site_id, epoch)) {
+            let (key, _) = entry.map_err(|e| e.to_string())?;
+            batch.remove(key);
+            removed += 1;
+        }
+        tree.apply_batch(batch).map_err(|e| format!("Nullifier store error: {}", e))?;
+        Ok(removed)
+    }
+
+    // ========================================================================
+    // ACCUMULATOR REVOCATION (VB accumulator)
+    // ========================================================================
+    // Unlike revoke_tag, verifiers only need the current accumulator value,
+    // so presentations stay unlinkable. "accumulator" holds the value and the
+    // last sequence number, "accumulator_revoked" the revoked elements and
+    // "accumulator_log" every update keyed by sequence number.
+
+    fn accumulator_trees(&self) -> Result<(sled::Tree, sled::Tree, sled::Tree), String> {
+        let open = |name: &str| self.vault.open_tree(name).map_err(|e| e.to_string());
+        Ok((open("accumulator")?, open("accumulator_revoked")?, open("accumulator_log")?))
+    }
+
+    /// Current accumulator value (48 bytes), created on first use. Only the
+    /// first caller draws entropy; a racing initializer loses the swap and
+    /// returns the stored value.
+    pub fn accumulator_value(&self) -> Result<Vec<u8>, String> {
+        let (state, _, _) = self.accumulator_trees()?;
+        if let Some(value) = state.get("value").map_err(|e| format!("Accumulator error: {}", e))? {
+            return Ok(value.to_vec());
+        }
+        let initial = accumulator::initial_accumulator(&mut PeriwinkleRng).map_err(|e| e.to_string())?.to_compressed();
+        let value = state
+            .compare_and_swap("value", None as Option<&[u8]>, Some(&initial[..]))
+            .map_err(|e| format!("Accumulator error: {}", e))?
+            .map(|_| initial.to_vec())
+            .or_else(|current| current.current.map(|v| v.to_vec()).ok_or("Accumulator missing"))?;
+        Ok(value)
+    }
+
+    /// Revokes the credential whose revocation id is `revocation_message`
+    pub fn revoke_credential(&self, secret_key: &[u8], revocation_message: &[u8]) -> Result<AccumulatorUpdate, String> {
+        self.update_accumulator(secret_key, revocation_message, true)
+    }
+
+    /// Undoes `revoke_credential`
+    pub fn reinstate_credential(&self, secret_key: &[u8], revocation_message: &[u8]) -> Result<AccumulatorUpdate, String> {
+        self.update_accumulator(secret_key, revocation_message, false)
+    }
+
+    fn update_accumulator(
+        &self,
+        secret_key: &[u8],
+        revocation_message: &[u8],
+        revoke: bool,
+    ) -> Result<AccumulatorUpdate, String> {
+        self.accumulator_value()?;
+        let (state, revoked, log) = self.accumulator_trees()?;
+        let element = Self::hash_identity(revocation_message);
+        (&state, &revoked, &log)
+            .transaction(|(state, revoked, log)| {
+                let abort = |e: String| ConflictableTransactionError::Abort(e);
+                let is_revoked = revoked.get(element.as_bytes())?.is_some();
+                if is_revoked == revoke {
+                    return Err(abort(format!("Credential already {}", if revoke { "revoked" } else { "valid" })));
+                }
+                let previous = state.get("value")?.ok_or_else(|| abort("Accumulator missing".into()))?;
+                let previous = accumulator::parse_accumulator(&previous).map_err(|e| abort(e.to_string()))?;
+                let value = accumulator::accumulate(&previous, secret_key, revocation_message, revoke)
+                    .map_err(|e| abort(e.to_string()))?;
+                let sequence = state
+                    .get("sequence")?
+                    .and_then(|s| s.as_ref().try_into().ok())
+                    .map_or(0, u64::from_be_bytes)
+                    + 1;
+                let update = AccumulatorUpdate::new(sequence, revoke, revocation_message, &previous, &value);
+
+                if revoke {
+                    revoked.insert(element.as_bytes(), &Self::current_timestamp().to_be_bytes()[..])?;
+                } else {
+                    revoked.remove(element.as_bytes())?;
+                }
+                state.insert("value", &value.to_compressed()[..])?;
+                state.insert("sequence", &sequence.to_be_bytes()[..])?;
+                log.insert(&sequence.to_be_bytes()[..], update.to_bytes())?;
+                Ok(update)
+            })
+            .map_err(|e: TransactionError<String>| format!("Accumulator update failed: {}", e))
+    }
+
+    /// Non-membership witness for a credential that is not revoked, valid
+    /// for the accumulator after update number `sequence`
+    pub fn issue_non_revocation_witness(
+        &self,
+        secret_key: &[u8],
+        revocation_message: &[u8],
+    ) -> Result<(Vec<u8>, u64), String> {
+        self.accumulator_value()?;
+        let (state, revoked, _) = self.accumulator_trees()?;
+        let element = Self::hash_identity(revocation_message);
+        (&state, &revoked)
+            .transaction(|(state, revoked)| {
+                let abort = |e: String| ConflictableTransactionError::Abort(e);
+                if revoked.get(element.as_bytes())?.is_some() {
+                    return Err(abort("Credential revoked".into()));
+                }
+                let value = state.get("value")?.ok_or_else(|| abort("Accumulator missing".into()))?;
+                let value = accumulator::parse_accumulator(&value).map_err(|e| abort(e.to_string()))?;
+                let witness = accumulator::issue_witness(&value, secret_key, revocation_message)
+                    .map_err(|e| abort(e.to_string()))?;
+                let sequence = state
+                    .get("sequence")?
+                    .and_then(|s| s.as_ref().try_into().ok())
+                    .map_or(0, u64::from_be_bytes);
+                Ok((witness, sequence))
+            })
+            .map_err(|e: TransactionError<String>| format!("Witness issuance failed: {}", e))
+    }
+
+    /// Updates after `sequence`, in order, for holders to refresh witnesses
+    pub fn accumulator_updates_since(&self, sequence: u64) -> Result<Vec<AccumulatorUpdate>, String> {
+        let (_, _, log) = self.accumulator_trees()?;
+        let mut updates = Vec::new();
+        for entry in log.range((sequence + 1).to_be_bytes()..) {
+            let (key, value) = entry.map_err(|e| e.to_string())?;
+            let seq = u64::from_be_bytes(key.as_ref().try_into().map_err(|_| "Corrupt accumulator log")?);
+            updates.push(AccumulatorUpdate::from_bytes(seq, &value).ok_or("Corrupt accumulator log")?);
+        }
+        Ok(updates)
+    }
 }
 
 // ============================================================================
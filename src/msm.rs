@@ -0,0 +1,70 @@
+// Multi-Scalar Multiplication
+// ===========================
+// Pippenger's bucket method for sum(Pi * ki) over G1. Scalars are cut into
+// c-bit windows; in each window every point is added once to the bucket of
+// its digit and the buckets are folded with a running sum, so n points cost
+// about (255 / c) * (n + 2^c) additions instead of n full scalar
+// multiplications.
+//
+// Running time depends on the scalar digits: only use it with public scalars
+// (verifier side). Signing and proving keep constant-time multiplication.
+
+use bls12_381::{G1Affine, G1Projective, Scalar};
+
+/// Below this many terms the bucket setup costs more than it saves
+const MSM_THRESHOLD: usize = 4;
+
+fn window_bits(n: usize) -> usize {
+    if n < 32 {
+        3
+    } else {
+        (n as f64).ln().ceil() as usize + 2
+    }
+}
+
+/// Value of the `c`-bit window starting at bit `start` of a little-endian scalar
+fn window_digit(bytes: &[u8; 32], start: usize, c: usize) -> usize {
+    let mut digit = 0;
+    for i in 0..c {
+        let bit = start + i;
+        if bit >= 256 {
+            break;
+        }
+        digit |= (((bytes[bit / 8] >> (bit % 8)) & 1) as usize) << i;
+    }
+    digit
+}
+
+/// sum(points[i] * scalars[i]), variable time
+pub(crate) fn msm(points: &[G1Affine], scalars: &[Scalar]) -> G1Projective {
+    debug_assert_eq!(points.len(), scalars.len());
+    if points.len() < MSM_THRESHOLD {
+        return points.iter().zip(scalars).fold(G1Projective::identity(), |acc, (p, k)| acc + p * k);
+    }
+
+    let c = window_bits(points.len());
+    let bytes: Vec<[u8; 32]> = scalars.iter().map(|k| k.to_bytes()).collect();
+    let windows = 255usize.div_ceil(c);
+
+    let mut result = G1Projective::identity();
+    let mut buckets = vec![G1Projective::identity(); (1 << c) - 1];
+    for w in (0..windows).rev() {
+        for _ in 0..c {
+            result = result.double();
+        }
+        buckets.fill(G1Projective::identity());
+        for (p, k) in points.iter().zip(&bytes) {
+            let digit = window_digit(k, w * c, c);
+            if digit != 0 {
+                buckets[digit - 1] += p;
+            }
+        }
+        // sum(d * bucket[d]) as a running sum from the top bucket down
+        let mut running = G1Projective::identity();
+        for bucket in buckets.iter().rev() {
+            running += bucket;
+            result += running;
+        }
+    }
+    result
+}
@@ -0,0 +1,281 @@
+// Multi-Credential Presentations
+// ==============================
+// One proof over several (public key, signature, messages) tuples sharing a
+// single Fiat-Shamir challenge. Hidden messages constrained to be equal get
+// the same blinding m~, so their responses m^ = m~ + c*m coincide exactly
+// when the messages do; the verifier only compares responses.
+
+use crate::bbs::{
+    append_proof_context, message_to_scalar, pseudonym_base, BbsProof, BbsPublicKey, BbsSignature, NymBinding, NymCheck,
+    PROOF_VERSION,
+};
+use crate::periwinkle::PeriwinkleRng;
+use crate::{hash_to_scalar, random_scalar, VerifyError};
+use bls12_381::Scalar;
+use rand::{CryptoRng, RngCore};
+use std::collections::HashMap;
+
+const MULTI_CREDENTIAL_TAG: &[u8] = b"MULTIPASS_MULTI_CREDENTIAL";
+
+/// Prover input for one credential
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct CredentialInput {
+    pub public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub messages: Vec<Vec<u8>>,
+    pub revealed_indices: Vec<u32>,
+}
+
+/// Verifier input for one credential
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct CredentialDisclosure {
+    pub public_key: Vec<u8>,
+    pub total_message_count: u64,
+    pub revealed_indices: Vec<u32>,
+    pub revealed_messages: Vec<Vec<u8>>,
+}
+
+/// Hidden message `first_index` of credential `first_credential` equals
+/// hidden message `second_index` of credential `second_credential`
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct EqualityConstraint {
+    pub first_credential: u32,
+    pub first_index: u32,
+    pub second_credential: u32,
+    pub second_index: u32,
+}
+
+type MessageRef = (usize, usize);
+
+impl EqualityConstraint {
+    fn refs(&self) -> (MessageRef, MessageRef) {
+        (
+            (self.first_credential as usize, self.first_index as usize),
+            (self.second_credential as usize, self.second_index as usize),
+        )
+    }
+}
+
+fn find(parent: &mut HashMap<MessageRef, MessageRef>, r: MessageRef) -> MessageRef {
+    let p = *parent.entry(r).or_insert(r);
+    if p == r {
+        return r;
+    }
+    let root = find(parent, p);
+    parent.insert(r, root);
+    root
+}
+
+fn append_equalities(out: &mut Vec<u8>, equalities: &[EqualityConstraint]) {
+    out.extend_from_slice(&(equalities.len() as u64).to_be_bytes());
+    for eq in equalities {
+        for v in [eq.first_credential, eq.first_index, eq.second_credential, eq.second_index] {
+            out.extend_from_slice(&v.to_be_bytes());
+        }
+    }
+}
+
+fn challenge_prefix(credential_count: usize) -> Vec<u8> {
+    let mut data = vec![PROOF_VERSION];
+    data.extend_from_slice(MULTI_CREDENTIAL_TAG);
+    data.extend_from_slice(&(credential_count as u32).to_be_bytes());
+    data
+}
+
+/// version || u32 LE n || (u32 LE length || proof) per credential
+fn encode(proofs: &[BbsProof]) -> Vec<u8> {
+    let mut out = vec![PROOF_VERSION];
+    out.extend_from_slice(&(proofs.len() as u32).to_le_bytes());
+    for proof in proofs {
+        let bytes = proof.to_bytes();
+        out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        out.extend_from_slice(&bytes);
+    }
+    out
+}
+
+fn decode(bytes: &[u8]) -> Result<Vec<BbsProof>, VerifyError> {
+    match bytes.first() {
+        Some(&PROOF_VERSION) => {}
+        Some(_) => return Err(VerifyError::UnsupportedProofVersion),
+        None => return Err(VerifyError::InvalidSignature),
+    }
+    let read_u32 = |rest: &[u8]| -> Result<usize, VerifyError> {
+        let arr: [u8; 4] = rest.get(..4).and_then(|b| b.try_into().ok()).ok_or(VerifyError::InvalidSignature)?;
+        Ok(u32::from_le_bytes(arr) as usize)
+    };
+    let count = read_u32(&bytes[1..])?;
+    let mut rest = &bytes[5..];
+    let mut proofs = Vec::new();
+    for _ in 0..count {
+        let len = read_u32(rest)?;
+        let body = rest.get(4..4 + len).ok_or(VerifyError::InvalidSignature)?;
+        proofs.push(BbsProof::parse(body)?);
+        rest = &rest[4 + len..];
+    }
+    if !rest.is_empty() {
+        return Err(VerifyError::InvalidSignature);
+    }
+    Ok(proofs)
+}
+
+/// Proves possession of every credential in one proof and that each
+/// equality constraint holds between hidden messages
+#[uniffi::export]
+#[allow(clippy::too_many_arguments)]
+pub fn create_multi_credential_proof(
+    credentials: Vec<CredentialInput>,
+    equalities: Vec<EqualityConstraint>,
+    nonce: Vec<u8>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+) -> Result<Vec<u8>, VerifyError> {
+    create_multi_credential_proof_with_rng(
+        credentials,
+        equalities,
+        nonce,
+        site_id,
+        epoch,
+        alias_index,
+        freshness_claim,
+        &mut PeriwinkleRng,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_multi_credential_proof_with_rng<R: RngCore + CryptoRng>(
+    credentials: Vec<CredentialInput>,
+    equalities: Vec<EqualityConstraint>,
+    nonce: Vec<u8>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+    rng: &mut R,
+) -> Result<Vec<u8>, VerifyError> {
+    if credentials.is_empty() {
+        return Err(VerifyError::InvalidSignature);
+    }
+
+    // Equal messages share one blinding per equivalence class
+    let mut parent = HashMap::new();
+    for eq in &equalities {
+        let (a, b) = eq.refs();
+        for (cred, idx) in [a, b] {
+            let input = credentials.get(cred).ok_or(VerifyError::InvalidSignature)?;
+            if idx >= input.messages.len() || input.revealed_indices.contains(&(idx as u32)) {
+                return Err(VerifyError::InvalidSignature);
+            }
+        }
+        if message_to_scalar(&credentials[a.0].messages[a.1]) != message_to_scalar(&credentials[b.0].messages[b.1]) {
+            return Err(VerifyError::InvalidSignature);
+        }
+        let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+        parent.insert(ra, rb);
+    }
+    let mut class_m_tilde: HashMap<MessageRef, Scalar> = HashMap::new();
+    let mut shared: Vec<HashMap<usize, Scalar>> = vec![HashMap::new(); credentials.len()];
+    let members: Vec<MessageRef> = parent.keys().copied().collect();
+    for r in members {
+        let root = find(&mut parent, r);
+        let m_tilde = match class_m_tilde.get(&root) {
+            Some(m_tilde) => *m_tilde,
+            None => {
+                let m_tilde = random_scalar(rng)?;
+                class_m_tilde.insert(root, m_tilde);
+                m_tilde
+            }
+        };
+        shared[r.0].insert(r.1, m_tilde);
+    }
+
+    let op = pseudonym_base(&site_id, epoch.as_deref());
+    let mut challenge_data = challenge_prefix(credentials.len());
+    let mut witnesses = Vec::with_capacity(credentials.len());
+    for (input, shared_m_tilde) in credentials.iter().zip(&shared) {
+        let pk = BbsPublicKey::parse(&input.public_key)?;
+        let sig = BbsSignature::parse(&input.signature)?;
+        witnesses.push(sig.commit_proof(
+            &pk,
+            &input.messages,
+            &input.revealed_indices,
+            &[],
+            &NymBinding::Pseudonym(op),
+            None,
+            shared_m_tilde,
+            &mut challenge_data,
+            rng,
+        )?);
+    }
+    append_equalities(&mut challenge_data, &equalities);
+    append_proof_context(&mut challenge_data, &nonce, alias_index, freshness_claim.as_deref());
+    let challenge = hash_to_scalar(&challenge_data);
+
+    let proofs: Vec<BbsProof> = witnesses.iter().map(|w| w.respond(challenge)).collect();
+    Ok(encode(&proofs))
+}
+
+/// Verifies a proof from `create_multi_credential_proof` against the same
+/// credentials (in order), equality constraints and presentation context
+#[uniffi::export]
+#[allow(clippy::too_many_arguments)]
+pub fn verify_multi_credential_proof(
+    credentials: Vec<CredentialDisclosure>,
+    proof: Vec<u8>,
+    equalities: Vec<EqualityConstraint>,
+    nonce: Vec<u8>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+) -> Result<bool, VerifyError> {
+    let proofs = decode(&proof)?;
+    if proofs.is_empty() || proofs.len() != credentials.len() {
+        return Err(VerifyError::InvalidSignature);
+    }
+    let challenge = proofs[0].challenge();
+    if proofs.iter().any(|p| p.challenge() != challenge) {
+        return Ok(false);
+    }
+
+    let op = pseudonym_base(&site_id, epoch.as_deref());
+    let mut keys = Vec::with_capacity(credentials.len());
+    let mut hidden = Vec::with_capacity(credentials.len());
+    let mut challenge_data = challenge_prefix(credentials.len());
+    for (disclosure, p) in credentials.iter().zip(&proofs) {
+        let pk = BbsPublicKey::parse(&disclosure.public_key)?;
+        hidden.push(pk.append_proof_transcript(
+            p,
+            disclosure.total_message_count as usize,
+            &disclosure.revealed_indices,
+            &disclosure.revealed_messages,
+            &[],
+            &NymCheck::Pseudonym(op),
+            None,
+            &mut challenge_data,
+        )?);
+        keys.push(pk);
+    }
+    append_equalities(&mut challenge_data, &equalities);
+    append_proof_context(&mut challenge_data, &nonce, alias_index, freshness_claim.as_deref());
+    if hash_to_scalar(&challenge_data) != challenge {
+        return Ok(false);
+    }
+
+    for eq in &equalities {
+        let ((ca, ia), (cb, ib)) = eq.refs();
+        let response = |c: usize, i: usize| {
+            proofs
+                .get(c)
+                .and_then(|p| p.hidden_response(&hidden[c], i))
+                .ok_or(VerifyError::InvalidSignature)
+        };
+        if response(ca, ia)? != response(cb, ib)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(keys.iter().zip(&proofs).all(|(pk, p)| pk.check_proof_pairing(p)))
+}
@@ -0,0 +1,244 @@
+// Rate-Limiting Nullifiers
+// ========================
+// k-times anonymous authentication. Instead of a pseudonym, a rate-limited
+// proof reveals the nullifier N = G_epoch * 1/(nym + counter + 1), with
+// G_epoch = hash_to_g1(site_id, epoch), and range-proves 0 <= counter < limit.
+// One nym secret has exactly `limit` nullifiers per site and epoch, so a
+// verifier recording them (`MinerEngine::record_nullifier`) detects the
+// (limit + 1)-th use without linking uses to each other or to the holder.
+// `limit` is typically the `max_passages` of the holder's DelegationToken.
+//
+// N is tied to the signed nym secret through the BBS+ blinding of the last
+// message: G_epoch - N = N*nym + N*counter, so the prover commits to
+// U = N*(nym~ + counter~) and the verifier recomputes
+// U = N*(nym^ + counter^) - c*(G_epoch - N).
+
+use crate::backend::{Backend, CurveBackend};
+use crate::bbs::{append_proof_context, BbsProof, BbsPublicKey, BbsSignature, NymBinding, NymCheck, PROOF_VERSION};
+use crate::periwinkle::PeriwinkleRng;
+use crate::predicate::{RangePredicate, RangeProof, RangeWitness};
+use crate::{hash_to_curve, hash_to_scalar, random_scalar, VerifyError};
+use bls12_381::{G1Affine, Scalar};
+use ff::Field;
+use group::Curve;
+use rand::{CryptoRng, RngCore};
+use std::collections::HashMap;
+
+const NULLIFIER_DST: &[u8] = b"MULTIPASS_BBS_NULLIFIER_H2G_";
+
+/// Predicate index of the counter, which is not a signed message
+const COUNTER_INDEX: u32 = u32::MAX;
+
+/// Public statement of a rate-limited proof: G_epoch and the use limit
+pub(crate) struct RateLimit {
+    base: G1Affine,
+    limit: u64,
+}
+
+impl RateLimit {
+    pub(crate) fn new(site_id: &[u8], epoch: &[u8], limit: u64) -> Result<Self, VerifyError> {
+        if limit == 0 {
+            return Err(VerifyError::InvalidSignature);
+        }
+        let mut scope = (site_id.len() as u64).to_be_bytes().to_vec();
+        scope.extend_from_slice(site_id);
+        scope.extend_from_slice(epoch);
+        let base = hash_to_curve::hash_to_g1(&scope, NULLIFIER_DST).to_affine();
+        Ok(Self { base, limit })
+    }
+
+    /// 0 <= counter <= limit - 1
+    fn counter_predicate(&self) -> RangePredicate {
+        RangePredicate { index: COUNTER_INDEX, lower: Some(0), upper: Some(self.limit - 1) }
+    }
+
+    /// N || G_epoch || U || limit
+    fn append_statement(&self, out: &mut Vec<u8>, nullifier: &G1Affine, u: &G1Affine) {
+        for p in [nullifier, &self.base, u] {
+            out.extend_from_slice(&p.to_compressed());
+        }
+        out.extend_from_slice(&self.limit.to_be_bytes());
+    }
+
+    /// Computes the nullifier for `counter` and appends its commitments.
+    /// `nym_tilde` must be the BBS+ blinding of the nym secret.
+    pub(crate) fn commit<R: RngCore + CryptoRng>(
+        &self,
+        nym: Scalar,
+        nym_tilde: Scalar,
+        counter: u64,
+        out: &mut Vec<u8>,
+        rng: &mut R,
+    ) -> Result<NullifierWitness, VerifyError> {
+        let counter_scalar = Scalar::from(counter);
+        let inverse = (nym + counter_scalar + Scalar::ONE).invert().into_option().ok_or(VerifyError::CryptoError)?;
+        let nullifier = Backend::lincomb(&[self.base], &[inverse]).to_affine();
+        let counter_tilde = random_scalar(rng)?;
+        let u = Backend::lincomb(&[nullifier], &[nym_tilde + counter_tilde]).to_affine();
+        self.append_statement(out, &nullifier, &u);
+        let range = RangeWitness::commit(&self.counter_predicate(), counter, counter_tilde, out, rng)?;
+        Ok(NullifierWitness { nullifier, counter: counter_scalar, counter_tilde, range })
+    }
+
+    /// Rebuilds U and the counter's range commitments from the responses
+    pub(crate) fn recompute(
+        &self,
+        nullifier: &G1Affine,
+        proof: &NullifierProof,
+        nym_hat: Scalar,
+        challenge: Scalar,
+        out: &mut Vec<u8>,
+    ) -> Result<(), VerifyError> {
+        let u = Backend::msm_vartime(&[*nullifier, self.base], &[nym_hat + proof.counter_hat + challenge, -challenge]);
+        self.append_statement(out, nullifier, &u.to_affine());
+        proof.range.recompute(&self.counter_predicate(), challenge, proof.counter_hat, out)
+    }
+}
+
+/// Prover state of the nullifier between commitment and response
+pub(crate) struct NullifierWitness {
+    nullifier: G1Affine,
+    counter: Scalar,
+    counter_tilde: Scalar,
+    range: RangeWitness,
+}
+
+impl NullifierWitness {
+    pub(crate) fn nullifier(&self) -> G1Affine {
+        self.nullifier
+    }
+
+    pub(crate) fn respond(&self, challenge: Scalar) -> NullifierProof {
+        NullifierProof {
+            counter_hat: self.counter_tilde + challenge * self.counter,
+            range: self.range.respond(challenge),
+        }
+    }
+}
+
+/// counter^ || range proof of the counter
+pub(crate) struct NullifierProof {
+    counter_hat: Scalar,
+    range: RangeProof,
+}
+
+impl NullifierProof {
+    pub(crate) fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.counter_hat.to_bytes());
+        self.range.write(out);
+    }
+
+    /// Reads one nullifier proof and returns it with the number of bytes consumed
+    pub(crate) fn read(bytes: &[u8]) -> Result<(Self, usize), VerifyError> {
+        let counter_bytes: [u8; 32] =
+            bytes.get(..32).and_then(|b| b.try_into().ok()).ok_or(VerifyError::InvalidSignature)?;
+        let counter_hat = Scalar::from_bytes(&counter_bytes).into_option().ok_or(VerifyError::InvalidSignature)?;
+        let (range, used) = RangeProof::read(&bytes[32..])?;
+        Ok((Self { counter_hat, range }, 32 + used))
+    }
+}
+
+// ============================================================================
+// Exported API
+// ============================================================================
+
+/// `create_proof` revealing the nullifier for use number `counter`
+/// (0-based, below `limit`) at `site_id` in `epoch` instead of a pseudonym
+#[uniffi::export]
+#[allow(clippy::too_many_arguments)]
+pub fn create_rate_limited_proof(
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+    revealed_indices: Vec<u32>,
+    nonce: Vec<u8>,
+    site_id: Vec<u8>,
+    epoch: Vec<u8>,
+    limit: u64,
+    counter: u64,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+) -> Result<Vec<u8>, VerifyError> {
+    create_rate_limited_proof_with_rng(
+        public_key,
+        signature,
+        messages,
+        revealed_indices,
+        nonce,
+        site_id,
+        epoch,
+        limit,
+        counter,
+        alias_index,
+        freshness_claim,
+        &mut PeriwinkleRng,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_rate_limited_proof_with_rng<R: RngCore + CryptoRng>(
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+    revealed_indices: Vec<u32>,
+    nonce: Vec<u8>,
+    site_id: Vec<u8>,
+    epoch: Vec<u8>,
+    limit: u64,
+    counter: u64,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+    rng: &mut R,
+) -> Result<Vec<u8>, VerifyError> {
+    let pk = BbsPublicKey::parse(&public_key)?;
+    let sig = BbsSignature::parse(&signature)?;
+    let rate_limit = RateLimit::new(&site_id, &epoch, limit)?;
+    let mut challenge_data = vec![PROOF_VERSION];
+    let witness = sig.commit_proof(
+        &pk,
+        &messages,
+        &revealed_indices,
+        &[],
+        &NymBinding::Nullifier { rate_limit: &rate_limit, counter },
+        None,
+        &HashMap::new(),
+        &mut challenge_data,
+        rng,
+    )?;
+    append_proof_context(&mut challenge_data, &nonce, alias_index, freshness_claim.as_deref());
+    Ok(witness.respond(hash_to_scalar(&challenge_data)).to_bytes())
+}
+
+/// Verifies a proof from `create_rate_limited_proof`. The caller must still
+/// record `BbsProof::nullifier` and reject nullifiers it has already seen.
+#[uniffi::export]
+#[allow(clippy::too_many_arguments)]
+pub fn verify_rate_limited_proof(
+    public_key: Vec<u8>,
+    proof: Vec<u8>,
+    total_message_count: u64,
+    revealed_indices: Vec<u32>,
+    revealed_messages_content: Vec<Vec<u8>>,
+    nonce: Vec<u8>,
+    site_id: Vec<u8>,
+    epoch: Vec<u8>,
+    limit: u64,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+) -> Result<bool, VerifyError> {
+    let pk = BbsPublicKey::parse(&public_key)?;
+    let proof = BbsProof::parse(&proof)?;
+    let rate_limit = RateLimit::new(&site_id, &epoch, limit)?;
+    pk.verify_bound_proof(
+        &proof,
+        total_message_count as usize,
+        &revealed_indices,
+        &revealed_messages_content,
+        &[],
+        &nonce,
+        &NymCheck::Nullifier(&rate_limit),
+        None,
+        alias_index,
+        freshness_claim.as_deref(),
+    )
+}
@@ -1,155 +1,511 @@
-use rand::{RngCore, SeedableRng};
+// Entropy Sources
+// ===============
+// The harvester draws from one `EntropySource`, chosen once per process with
+// `init_entropy` (or `init_default_entropy`) before the first key, signature
+// or proof. Without an explicit choice the first use selects
+// `default_source`: /dev/hwrng mixed with the OS generator when the device
+// exists, the OS generator alone otherwise. That choice is final, so a later
+// `init_entropy` fails with AlreadyInitialized; apps that need another
+// source call `init_entropy` at startup. A failing source is a hard error;
+// the predictable clock-seeded source is only used when asked for.
+// Raw hardware output passes SP 800-90B health tests (see health.rs) before
+// it is mixed. Output comes from an HMAC_DRBG (drbg.rs) seeded and reseeded
+// from the source: every HARVESTER_RESEED_INTERVAL requests, and before any
+// request asking for prediction resistance.
+
+use crate::drbg::{HmacDrbg, SECURITY_STRENGTH};
+use crate::health::{HealthReport, HealthTested};
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore, SeedableRng};
 use rand_chacha::ChaCha20Rng;
 use sha2::{Digest, Sha256};
 use hkdf::Hkdf;
-use std::sync::{Arc, Mutex};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Read};
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Default read timeout for /dev/hwrng
+pub const HWRNG_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Generate requests between reseeds of the harvester DRBG
+pub const HARVESTER_RESEED_INTERVAL: u64 = 1 << 12;
+
+const HWRNG_PATH: &str = "/dev/hwrng";
+const HARVESTER_PERSONALIZATION: &[u8] = b"PERIWINKLE_HARVESTER_HMAC_DRBG_V1";
+const MIXER_SALT: &[u8] = b"PERIWINKLE_ENTROPY_MIXER_V1";
+
+static HARVESTER: OnceLock<Mutex<EntropyHarvester>> = OnceLock::new();
 
-// Lazy static singleton for the harvester
-lazy_static::lazy_static! {
-    static ref HARVESTER: Arc<Mutex<EntropyHarvester>> = Arc::new(Mutex::new(EntropyHarvester::new()));
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Error)]
+pub enum EntropyError {
+    /// The source could not be opened or read
+    Unavailable { source_name: String, reason: String },
+    /// The source produced no data before its timeout
+    Timeout { source_name: String },
+    /// A continuous or startup health test failed; the source stays failed
+    HealthTestFailed { source_name: String, test: String },
+    /// The DRBG has served its reseed interval and must be reseeded
+    ReseedRequired,
+    /// A single DRBG request asked for more than `max_bytes`
+    RequestTooLarge { max_bytes: u64 },
+    /// The sealed root file is missing, malformed or fails to unseal
+    SealedRoot { reason: String },
+    /// No sealed root with this version (never created, or retired)
+    UnknownRootVersion { version: u32 },
+    /// An entropy source was already chosen for this process
+    AlreadyInitialized,
+    /// A thread panicked while holding the harvester or sealed root
+    Poisoned,
 }
 
-/// Simulated Entropy Harvester & PUF
-/// Audited to AAL3 Standards (1856 bits of entropy)
-pub struct EntropyHarvester {
-    rng: ChaCha20Rng,
-    // AAL3 Requirement: 1856 bits = 232 bytes
-    avalanche_noise_pool: [u8; 232], 
-    // Simulated Physically Unclonable Function (PUF) Root
-    puf_root: [u8; 32],
+impl std::fmt::Display for EntropyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
 }
 
-impl EntropyHarvester {
-    fn new() -> Self {
-        use std::fs::File;
-        use std::io::Read;
+impl std::error::Error for EntropyError {}
 
-        // Try to open hardware RNG, fallback to software RNG for dev environments
-        let mut puf = [0u8; 32];
-        let mut seed = [0u8; 32];
-        let mut pool = [0u8; 232];
+/// Where the harvester gets its randomness
+pub trait EntropySource: Send {
+    /// Fills `dest` completely or fails; never returns predictable bytes
+    fn fill(&mut self, dest: &mut [u8]) -> Result<(), EntropyError>;
+
+    /// Short name for logs and errors
+    fn name(&self) -> String;
+
+    /// Health test reports of this source and any it wraps
+    fn health(&self) -> Vec<HealthReport> {
+        Vec::new()
+    }
+}
+
+/// The operating system generator (getrandom)
+#[derive(Debug, Default)]
+pub struct OsSource;
+
+impl EntropySource for OsSource {
+    fn fill(&mut self, dest: &mut [u8]) -> Result<(), EntropyError> {
+        OsRng
+            .try_fill_bytes(dest)
+            .map_err(|e| EntropyError::Unavailable { source_name: self.name(), reason: e.to_string() })
+    }
 
-        match File::open("/dev/hwrng") {
-            Ok(mut rng_file) => {
-                // PRODUCTION MODE: Use hardware RNG
-                rng_file.read_exact(&mut puf).expect("Failed to read PUF root from hardware");
-                rng_file.read_exact(&mut seed).expect("Failed to read seed from hardware");
-                rng_file.read_exact(&mut pool).expect("Failed to fill entropy pool from hardware");
-                eprintln!("[PERIWINKLE] ✅ Hardware RNG initialized from /dev/hwrng");
+    fn name(&self) -> String {
+        "os".to_string()
+    }
+}
+
+/// A file or character device. With a timeout the device is read
+/// non-blocking and gives up once the timeout has passed.
+#[derive(Debug)]
+pub struct DeviceSource {
+    path: PathBuf,
+    timeout: Option<Duration>,
+    file: Option<File>,
+}
+
+impl DeviceSource {
+    /// Blocking reads from `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), timeout: None, file: None }
+    }
+
+    /// Non-blocking reads from `path`, failing after `timeout`
+    pub fn with_timeout(path: impl Into<PathBuf>, timeout: Duration) -> Self {
+        Self { path: path.into(), timeout: Some(timeout), file: None }
+    }
+
+    /// /dev/hwrng with a non-blocking timeout
+    pub fn hwrng(timeout: Duration) -> Self {
+        Self::with_timeout(HWRNG_PATH, timeout)
+    }
+
+    fn unavailable(&self, reason: impl ToString) -> EntropyError {
+        EntropyError::Unavailable { source_name: self.name(), reason: reason.to_string() }
+    }
+
+    fn open(&mut self) -> Result<&mut File, EntropyError> {
+        if self.file.is_none() {
+            let mut options = OpenOptions::new();
+            options.read(true);
+            #[cfg(unix)]
+            if self.timeout.is_some() {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.custom_flags(libc::O_NONBLOCK);
             }
-            Err(_) => {
-                // DEVELOPMENT MODE: Use software fallback with warning
-                eprintln!("[PERIWINKLE] ⚠️  WARNING: /dev/hwrng not available - using software fallback (DEV MODE ONLY)");
-                
-                // Seed from system time and process info
-                let timestamp = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_nanos();
-                
-                let mut hasher = Sha256::new();
-                hasher.update(timestamp.to_le_bytes());
-                hasher.update(b"SPOOKY_DEV_FALLBACK_ENTROPY_NOT_FOR_PRODUCTION");
-                hasher.update(std::process::id().to_le_bytes());
-                puf.copy_from_slice(&hasher.finalize());
-
-                let mut hasher2 = Sha256::new();
-                hasher2.update(&puf);
-                hasher2.update(b"SEED_DERIVATION");
-                seed.copy_from_slice(&hasher2.finalize());
-
-                // Fill pool with ChaCha20
-                let mut rng = ChaCha20Rng::from_seed(seed);
-                rng.fill_bytes(&mut pool);
+            self.file = Some(options.open(&self.path).map_err(|e| self.unavailable(e))?);
+        }
+        Ok(self.file.as_mut().expect("opened above"))
+    }
+}
+
+impl EntropySource for DeviceSource {
+    fn fill(&mut self, dest: &mut [u8]) -> Result<(), EntropyError> {
+        let deadline = self.timeout.map(|t| Instant::now() + t);
+        let name = self.name();
+        let file = self.open()?;
+        let mut filled = 0;
+        while filled < dest.len() {
+            match file.read(&mut dest[filled..]) {
+                Ok(0) => {
+                    return Err(EntropyError::Unavailable { source_name: name, reason: "end of file".to_string() })
+                }
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) if e.kind() == ErrorKind::WouldBlock => match deadline {
+                    Some(deadline) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(1)),
+                    _ => return Err(EntropyError::Timeout { source_name: name }),
+                },
+                Err(e) => return Err(EntropyError::Unavailable { source_name: name, reason: e.to_string() }),
             }
         }
-        
-        let rng = ChaCha20Rng::from_seed(seed);
+        Ok(())
+    }
+
+    fn name(&self) -> String {
+        self.path.display().to_string()
+    }
+}
+
+/// ChaCha20 keyed with a fixed seed: the same bytes on every run. Tests only.
+#[derive(Debug)]
+pub struct TestSource {
+    rng: ChaCha20Rng,
+}
+
+impl TestSource {
+    pub fn new(seed: [u8; 32]) -> Self {
+        Self { rng: ChaCha20Rng::from_seed(seed) }
+    }
+}
+
+impl EntropySource for TestSource {
+    fn fill(&mut self, dest: &mut [u8]) -> Result<(), EntropyError> {
+        self.rng.fill_bytes(dest);
+        Ok(())
+    }
+
+    fn name(&self) -> String {
+        "test".to_string()
+    }
+}
 
-        Self {
-            rng,
+/// ChaCha20 seeded from the clock and process id. Predictable: only for
+/// development machines without any real source, and only when opted into.
+#[derive(Debug)]
+pub struct InsecureClockSource {
+    rng: ChaCha20Rng,
+}
+
+impl InsecureClockSource {
+    pub fn new() -> Self {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or_default();
+        let mut hasher = Sha256::new();
+        hasher.update(timestamp.to_le_bytes());
+        hasher.update(b"SPOOKY_DEV_FALLBACK_ENTROPY_NOT_FOR_PRODUCTION");
+        hasher.update(std::process::id().to_le_bytes());
+        Self { rng: ChaCha20Rng::from_seed(hasher.finalize().into()) }
+    }
+}
+
+impl Default for InsecureClockSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EntropySource for InsecureClockSource {
+    fn fill(&mut self, dest: &mut [u8]) -> Result<(), EntropyError> {
+        self.rng.fill_bytes(dest);
+        Ok(())
+    }
+
+    fn name(&self) -> String {
+        "insecure-clock".to_string()
+    }
+}
+
+/// Combines several sources with HKDF-SHA256: each output block is extracted
+/// from a fresh block of every source, so the result is as strong as the
+/// best of them. Fails if any source fails.
+pub struct MixerSource {
+    sources: Vec<Box<dyn EntropySource>>,
+}
+
+impl MixerSource {
+    pub fn new(sources: Vec<Box<dyn EntropySource>>) -> Self {
+        Self { sources }
+    }
+}
+
+impl EntropySource for MixerSource {
+    fn fill(&mut self, dest: &mut [u8]) -> Result<(), EntropyError> {
+        if self.sources.is_empty() {
+            return Err(EntropyError::Unavailable { source_name: self.name(), reason: "no sources".to_string() });
+        }
+        for chunk in dest.chunks_mut(64) {
+            let mut ikm = Vec::with_capacity(64 * self.sources.len());
+            for source in &mut self.sources {
+                let mut block = [0u8; 64];
+                source.fill(&mut block)?;
+                ikm.extend_from_slice(&block);
+            }
+            Hkdf::<Sha256>::new(Some(MIXER_SALT), &ikm).expand(b"", chunk).expect("chunk fits one HKDF output");
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> String {
+        let names: Vec<String> = self.sources.iter().map(|s| s.name()).collect();
+        format!("mix({})", names.join(","))
+    }
+
+    fn health(&self) -> Vec<HealthReport> {
+        self.sources.iter().flat_map(|s| s.health()).collect()
+    }
+}
+
+/// Health-tested /dev/hwrng mixed with the OS generator if the device
+/// exists, else the OS generator alone
+pub fn default_source() -> Box<dyn EntropySource> {
+    if std::path::Path::new(HWRNG_PATH).exists() {
+        let hwrng = HealthTested::new(DeviceSource::hwrng(HWRNG_TIMEOUT));
+        Box::new(MixerSource::new(vec![Box::new(hwrng), Box::new(OsSource)]))
+    } else {
+        Box::new(OsSource)
+    }
+}
+
+/// Chooses the process-wide entropy source. Fails if the source cannot
+/// produce the initial state or a source was already chosen.
+pub fn init_entropy(source: Box<dyn EntropySource>) -> Result<(), EntropyError> {
+    let harvester = EntropyHarvester::new(source)?;
+    HARVESTER.set(Mutex::new(harvester)).map_err(|_| EntropyError::AlreadyInitialized)
+}
+
+/// Initializes with `default_source`. If it fails and
+/// `allow_insecure_fallback` is set, falls back to `InsecureClockSource`;
+/// `entropy_health` then reports the source as "insecure-clock".
+#[uniffi::export]
+pub fn init_default_entropy(allow_insecure_fallback: bool) -> Result<(), EntropyError> {
+    match init_entropy(default_source()) {
+        Err(EntropyError::AlreadyInitialized) => Err(EntropyError::AlreadyInitialized),
+        Err(_) if allow_insecure_fallback => init_entropy(Box::new(InsecureClockSource::new())),
+        result => result,
+    }
+}
+
+/// The harvester. Without `init_entropy`, the first use selects
+/// `default_source` for the rest of the process.
+fn harvester() -> Result<MutexGuard<'static, EntropyHarvester>, EntropyError> {
+    if HARVESTER.get().is_none() {
+        // A concurrent first use may win the race; either harvester is fine
+        let _ = HARVESTER.set(Mutex::new(EntropyHarvester::new(default_source())?));
+    }
+    HARVESTER.get().expect("set above").lock().map_err(|_| EntropyError::Poisoned)
+}
+
+/// Health of the process-wide entropy source
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct EntropyHealth {
+    pub source_name: String,
+    /// False once any read or health test has failed
+    pub healthy: bool,
+    /// One report per health-tested source
+    pub sources: Vec<HealthReport>,
+    /// Generate requests since the last DRBG reseed, plus one
+    pub drbg_reseed_counter: u64,
+    pub drbg_reseed_interval: u64,
+    pub drbg_reseeds: u64,
+}
+
+/// Health status and test counters of the entropy source
+#[uniffi::export]
+pub fn entropy_health() -> Result<EntropyHealth, EntropyError> {
+    Ok(harvester()?.health())
+}
+
+/// Entropy Harvester. Hardware-bound secrets come from the sealed root
+/// (sealed_root.rs), not from harvester state.
+/// Output is HMAC_DRBG at a 256-bit security strength. Raw hardware bytes
+/// only reach the DRBG after passing the SP 800-90B startup and continuous
+/// health tests at the claimed min-entropy (health.rs); the tests catch a
+/// stuck or badly biased source, they do not measure its entropy.
+pub struct EntropyHarvester {
+    source: Box<dyn EntropySource>,
+    // First source error; the harvester refuses to produce output after it
+    failure: Option<EntropyError>,
+    drbg: HmacDrbg,
+    // State behind level 4 freshness claims, refreshed from the DRBG
+    avalanche_noise_pool: [u8; 232],
+}
+
+impl EntropyHarvester {
+    pub fn new(mut source: Box<dyn EntropySource>) -> Result<Self, EntropyError> {
+        let mut entropy = [0u8; SECURITY_STRENGTH];
+        let mut nonce = [0u8; SECURITY_STRENGTH / 2];
+        source.fill(&mut entropy)?;
+        source.fill(&mut nonce)?;
+        let mut drbg =
+            HmacDrbg::with_reseed_interval(&entropy, &nonce, HARVESTER_PERSONALIZATION, HARVESTER_RESEED_INTERVAL)?;
+        let mut pool = [0u8; 232];
+        drbg.generate(&mut pool, &[])?;
+
+        Ok(Self {
+            source,
+            failure: None,
+            drbg,
             avalanche_noise_pool: pool,
-            puf_root: puf,
+        })
+    }
+
+    /// Reseeds the DRBG from the source. A source failure is permanent.
+    fn reseed(&mut self, additional: &[u8]) -> Result<(), EntropyError> {
+        if let Some(e) = &self.failure {
+            return Err(e.clone());
+        }
+        let mut entropy = [0u8; SECURITY_STRENGTH];
+        if let Err(e) = self.source.fill(&mut entropy) {
+            self.failure = Some(e.clone());
+            return Err(e);
+        }
+        self.drbg.reseed(&entropy, additional)
+    }
+
+    /// DRBG output, reseeding first when prediction resistance is requested
+    /// or the reseed interval is used up (SP 800-90A, 9.3.1)
+    fn generate(&mut self, out: &mut [u8], prediction_resistance: bool, additional: &[u8]) -> Result<(), EntropyError> {
+        if let Some(e) = &self.failure {
+            return Err(e.clone());
+        }
+        if prediction_resistance {
+            self.reseed(additional)?;
+            return self.drbg.generate(out, &[]);
+        }
+        match self.drbg.generate(out, additional) {
+            Err(EntropyError::ReseedRequired) => {
+                self.reseed(additional)?;
+                self.drbg.generate(out, &[])
+            }
+            result => result,
         }
     }
 
-    /// Harvests entropy from "hardware" sources
-    fn harvest(&mut self) {
-        // Simulate avalanche noise by mixing in timestamp jitter
-        let jitter = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        
+    /// Mixes fresh DRBG output into the pool
+    fn harvest(&mut self, prediction_resistance: bool) -> Result<(), EntropyError> {
+        let mut fresh = [0u8; 32];
+        self.generate(&mut fresh, prediction_resistance, &[])?;
+
         let mut hasher = Sha256::new();
         // Fold the large pool into the hash
-        hasher.update(&self.avalanche_noise_pool);
-        hasher.update(jitter.to_le_bytes());
+        hasher.update(self.avalanche_noise_pool);
+        hasher.update(fresh);
         let new_entropy = hasher.finalize();
-        
+
         // Mix new entropy back into the pool (Avalanche)
-        // Simple XOR mixing for simulation
         for i in 0..32 {
             self.avalanche_noise_pool[i] ^= new_entropy[i];
             // Rotating mix for the rest
             self.avalanche_noise_pool[32 + i] ^= new_entropy[i].rotate_left(1);
         }
-        
-        // Reseed RNG periodically (simulated)
-        if jitter % 7 == 0 {
-             let mut seed = [0u8; 32];
-             // Extract seed from first 32 bytes of high-entropy pool
-             seed.copy_from_slice(&self.avalanche_noise_pool[0..32]);
-             self.rng = ChaCha20Rng::from_seed(seed);
+        Ok(())
+    }
+
+    pub fn health(&self) -> EntropyHealth {
+        let sources = self.source.health();
+        EntropyHealth {
+            source_name: self.source.name(),
+            healthy: self.failure.is_none() && sources.iter().all(|r| r.healthy),
+            sources,
+            drbg_reseed_counter: self.drbg.reseed_counter(),
+            drbg_reseed_interval: self.drbg.reseed_interval(),
+            drbg_reseeds: self.drbg.reseeds(),
         }
     }
 
-    pub fn get_entropy(&mut self) -> [u8; 64] {
-        self.harvest();
-        let mut buf = [0u8; 64];
-        self.rng.fill_bytes(&mut buf);
-        buf
+    pub fn get_entropy(&mut self) -> Result<[u8; 64], EntropyError> {
+        self.get_entropy_with(false, &[])
     }
 
-    /// Derives a hardware-bound secret using HKDF over the PUF root and Entropy Pool
-    pub fn derive_secret(&mut self, info: &[u8]) -> [u8; 32] {
-        self.harvest(); // Ensure fresh state
-        
-        // HKDF-SHA256
-        // Salt: Current Entropy Pool (Dynamic binding) or Fixed?
-        // NIST: Salt should be random but known? Or if PUF is secret, salt can be public.
-        // We use the avalanche pool as salt to ensure freshness and device state binding.
-        let hk = Hkdf::<Sha256>::new(Some(&self.avalanche_noise_pool[0..32]), &self.puf_root);
-        let mut okm = [0u8; 32];
-        hk.expand(info, &mut okm).expect("HKDF expand failed");
-        okm
+    /// `get_entropy` with optional prediction resistance and additional input
+    pub fn get_entropy_with(&mut self, prediction_resistance: bool, additional: &[u8]) -> Result<[u8; 64], EntropyError> {
+        let mut buf = [0u8; 64];
+        self.generate(&mut buf, prediction_resistance, additional)?;
+        Ok(buf)
     }
 }
 
-/// Public API to get hardware-bound entropy
-pub fn get_entropy() -> [u8; 64] {
-    HARVESTER.lock().unwrap().get_entropy()
+/// Public API to get hardware-bound entropy. Fails once the source has
+/// failed a read or health test.
+pub fn get_entropy() -> Result<[u8; 64], EntropyError> {
+    harvester()?.get_entropy()
+}
+
+/// `get_entropy`, reseeding from the source first when
+/// `prediction_resistance` is set
+pub fn get_entropy_with(prediction_resistance: bool, additional_input: &[u8]) -> Result<[u8; 64], EntropyError> {
+    harvester()?.get_entropy_with(prediction_resistance, additional_input)
+}
+
+/// `RngCore` view of the harvester: the default randomness of every signing,
+/// proving and key generation path. `try_fill_bytes` reports a failed
+/// source; the infallible methods panic rather than return untested bytes.
+/// The library itself only calls `try_fill_bytes`, so exported functions
+/// report a failed source as an error.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PeriwinkleRng;
+
+impl RngCore for PeriwinkleRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).unwrap_or_else(|e| panic!("[PERIWINKLE] {e}"));
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        for chunk in dest.chunks_mut(64) {
+            chunk.copy_from_slice(&get_entropy().map_err(rand::Error::new)?[..chunk.len()]);
+        }
+        Ok(())
+    }
 }
 
-/// Public API to get a deterministic hardware secret for a given context
-pub fn get_hardware_secret(context: &[u8]) -> [u8; 32] {
-    HARVESTER.lock().unwrap().derive_secret(context)
+impl CryptoRng for PeriwinkleRng {}
+
+/// Public API to get a deterministic hardware secret for a given context:
+/// the same value on every call and across restarts, until the sealed root
+/// is rotated. Requires `init_sealed_root`.
+pub fn get_hardware_secret(context: &[u8]) -> Result<[u8; 32], EntropyError> {
+    Ok(crate::sealed_root::sealed_root()?.derive(context))
 }
 
 /// Level 4 High-Assurance Trigger (Chain 3)
 /// Returns a Freshness Claim (Hash of state) and the 64-byte entropy sample.
-pub fn get_level4_entropy() -> ([u8; 32], [u8; 64]) {
-    let mut h = HARVESTER.lock().unwrap();
-    h.harvest();
-    let entropy = h.get_entropy();
+/// The sample is drawn with prediction resistance: the DRBG reseeds first.
+pub fn get_level4_entropy() -> Result<([u8; 32], [u8; 64]), EntropyError> {
+    let mut h = harvester()?;
+    let entropy = h.get_entropy_with(true, b"LEVEL_4_ENTROPY")?;
+    h.harvest(false)?;
     
     let mut hasher = Sha256::new();
     hasher.update(b"LEVEL_4_FRESHNESS_BINDING");
     hasher.update(&h.avalanche_noise_pool);
     let mut claim = [0u8; 32];
     claim.copy_from_slice(&hasher.finalize());
-    (claim, entropy)
+    Ok((claim, entropy))
 }
 
 use crate::verify_signature_safe;
@@ -0,0 +1,298 @@
+// Range Predicates over Hidden Attributes
+// =======================================
+// Integer attributes are signed as Scalar::from(value) instead of a hash, so
+// they can be compared without being revealed. Each bound of a predicate
+// proves that (value - lower) or (upper - value) fits in RANGE_BITS bits:
+// every bit gets a Pedersen commitment Ci = g*bi + h*ri with an OR-proof
+// that bi is 0 or 1, and the recombined commitment D = g*value + h*rho is
+// opened with the same m~ the BBS+ proof uses for that hidden message. All
+// commitments go into the BBS+ proof's Fiat-Shamir challenge.
+
+use crate::backend::{Backend, CurveBackend};
+use crate::{hash_to_g1, random_scalar, VerifyError};
+use bls12_381::{G1Affine, G1Projective, Scalar};
+use ff::Field;
+use group::Curve;
+use lazy_static::lazy_static;
+use rand::{CryptoRng, RngCore};
+
+/// Width of each bound; any u64 difference fits
+pub const RANGE_BITS: usize = 64;
+
+const INTEGER_MESSAGE_TAG: &[u8] = b"\x00MULTIPASS_U64";
+
+lazy_static! {
+    static ref PEDERSEN_G: G1Affine = hash_to_g1(b"MULTIPASS_RANGE_PROOF_G").to_affine();
+    static ref PEDERSEN_H: G1Affine = hash_to_g1(b"MULTIPASS_RANGE_PROOF_H").to_affine();
+}
+
+/// Statement about the hidden integer message at `index`: lower <= value <= upper
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct RangePredicate {
+    pub index: u32,
+    pub lower: Option<u64>,
+    pub upper: Option<u64>,
+}
+
+/// Encodes an integer attribute so it is signed as Scalar::from(value)
+/// rather than hashed. Use it for any message a predicate may refer to.
+#[uniffi::export]
+pub fn encode_integer_message(value: u64) -> Vec<u8> {
+    let mut message = INTEGER_MESSAGE_TAG.to_vec();
+    message.extend_from_slice(&value.to_be_bytes());
+    message
+}
+
+pub(crate) fn decode_integer_message(message: &[u8]) -> Option<u64> {
+    let value = message.strip_prefix(INTEGER_MESSAGE_TAG)?;
+    Some(u64::from_be_bytes(value.try_into().ok()?))
+}
+
+fn scalar_from_slice(bytes: &[u8]) -> Result<Scalar, VerifyError> {
+    let arr: [u8; 32] = bytes.try_into().map_err(|_| VerifyError::InvalidSignature)?;
+    Scalar::from_bytes(&arr).into_option().ok_or(VerifyError::InvalidSignature)
+}
+
+/// Bounds of a predicate as (sign, constant): D = sign*sum(2^i Ci) + g*constant
+fn bounds(predicate: &RangePredicate) -> Vec<(bool, u64)> {
+    let mut bounds = Vec::with_capacity(2);
+    if let Some(lower) = predicate.lower {
+        bounds.push((true, lower));
+    }
+    if let Some(upper) = predicate.upper {
+        bounds.push((false, upper));
+    }
+    bounds
+}
+
+fn powers_of_two() -> Vec<Scalar> {
+    let mut powers = Vec::with_capacity(RANGE_BITS);
+    let mut p = Scalar::ONE;
+    for _ in 0..RANGE_BITS {
+        powers.push(p);
+        p = p.double();
+    }
+    powers
+}
+
+fn append_statement(transcript: &mut Vec<u8>, predicate: &RangePredicate) {
+    transcript.extend_from_slice(&predicate.index.to_be_bytes());
+    for bound in [predicate.lower, predicate.upper] {
+        match bound {
+            Some(v) => {
+                transcript.push(1);
+                transcript.extend_from_slice(&v.to_be_bytes());
+            }
+            None => transcript.push(0),
+        }
+    }
+}
+
+// ============================================================================
+// Proof Objects
+// ============================================================================
+
+/// Ci || c0 || z0 || z1 (c1 = c - c0)
+struct BitProof {
+    commitment: G1Affine,
+    c0: Scalar,
+    z0: Scalar,
+    z1: Scalar,
+}
+
+struct BoundProof {
+    bits: Vec<BitProof>,
+    rho_hat: Scalar,
+}
+
+/// One bound proof per present bound, lower first
+pub(crate) struct RangeProof {
+    bounds: Vec<BoundProof>,
+}
+
+const BIT_PROOF_LEN: usize = 48 + 3 * 32;
+const BOUND_PROOF_LEN: usize = RANGE_BITS * BIT_PROOF_LEN + 32;
+
+impl RangeProof {
+    pub(crate) fn write(&self, out: &mut Vec<u8>) {
+        out.push(self.bounds.len() as u8);
+        for bound in &self.bounds {
+            for bit in &bound.bits {
+                out.extend_from_slice(&bit.commitment.to_compressed());
+                out.extend_from_slice(&bit.c0.to_bytes());
+                out.extend_from_slice(&bit.z0.to_bytes());
+                out.extend_from_slice(&bit.z1.to_bytes());
+            }
+            out.extend_from_slice(&bound.rho_hat.to_bytes());
+        }
+    }
+
+    /// Reads one range proof and returns it with the number of bytes consumed
+    pub(crate) fn read(bytes: &[u8]) -> Result<(Self, usize), VerifyError> {
+        let bound_count = *bytes.first().ok_or(VerifyError::InvalidSignature)? as usize;
+        if bound_count > 2 || bytes.len() < 1 + bound_count * BOUND_PROOF_LEN {
+            return Err(VerifyError::InvalidSignature);
+        }
+        let mut offset = 1;
+        let mut bounds = Vec::with_capacity(bound_count);
+        for _ in 0..bound_count {
+            let mut bits = Vec::with_capacity(RANGE_BITS);
+            for _ in 0..RANGE_BITS {
+                let c_bytes: [u8; 48] = bytes[offset..offset + 48].try_into().map_err(|_| VerifyError::InvalidSignature)?;
+                let commitment = G1Affine::from_compressed(&c_bytes).into_option().ok_or(VerifyError::InvalidSignature)?;
+                bits.push(BitProof {
+                    commitment,
+                    c0: scalar_from_slice(&bytes[offset + 48..offset + 80])?,
+                    z0: scalar_from_slice(&bytes[offset + 80..offset + 112])?,
+                    z1: scalar_from_slice(&bytes[offset + 112..offset + 144])?,
+                });
+                offset += BIT_PROOF_LEN;
+            }
+            let rho_hat = scalar_from_slice(&bytes[offset..offset + 32])?;
+            offset += 32;
+            bounds.push(BoundProof { bits, rho_hat });
+        }
+        Ok((Self { bounds }, offset))
+    }
+
+    /// Rebuilds the prover's commitments from the responses and appends them
+    /// to `transcript`. `m_hat` is the BBS+ response of the hidden message.
+    pub(crate) fn recompute(
+        &self,
+        predicate: &RangePredicate,
+        challenge: Scalar,
+        m_hat: Scalar,
+        transcript: &mut Vec<u8>,
+    ) -> Result<(), VerifyError> {
+        let statement = bounds(predicate);
+        if statement.len() != self.bounds.len() {
+            return Err(VerifyError::InvalidSignature);
+        }
+        let g = *PEDERSEN_G;
+        let h = *PEDERSEN_H;
+        append_statement(transcript, predicate);
+        for ((positive, constant), bound) in statement.into_iter().zip(&self.bounds) {
+            for bit in &bound.bits {
+                let c1 = challenge - bit.c0;
+                let t0 = Backend::msm_vartime(&[h, bit.commitment], &[bit.z0, -bit.c0]);
+                let t1 = Backend::msm_vartime(&[h, bit.commitment, g], &[bit.z1, -c1, c1]);
+                append_bit(transcript, &bit.commitment, &t0, &t1);
+            }
+            // D = sign*sum(2^i Ci) + g*constant, T = g*m^ + h*rho^ - D*c
+            let sign = if positive { challenge } else { -challenge };
+            let mut points = vec![g, h];
+            let mut scalars = vec![m_hat - challenge * Scalar::from(constant), bound.rho_hat];
+            for (bit, p) in bound.bits.iter().zip(powers_of_two()) {
+                points.push(bit.commitment);
+                scalars.push(-(sign * p));
+            }
+            let t_link = Backend::msm_vartime(&points, &scalars);
+            transcript.extend_from_slice(&t_link.to_affine().to_compressed());
+        }
+        Ok(())
+    }
+}
+
+fn append_bit(transcript: &mut Vec<u8>, commitment: &G1Affine, t0: &G1Projective, t1: &G1Projective) {
+    transcript.extend_from_slice(&commitment.to_compressed());
+    transcript.extend_from_slice(&t0.to_affine().to_compressed());
+    transcript.extend_from_slice(&t1.to_affine().to_compressed());
+}
+
+// ============================================================================
+// Prover
+// ============================================================================
+
+struct BitWitness {
+    bit: bool,
+    r: Scalar,
+    k: Scalar,
+    // Simulated branch
+    c_sim: Scalar,
+    z_sim: Scalar,
+    commitment: G1Affine,
+}
+
+struct BoundWitness {
+    bits: Vec<BitWitness>,
+    rho: Scalar,
+    rho_tilde: Scalar,
+}
+
+/// Prover state between the commitment and response phases
+pub(crate) struct RangeWitness {
+    bounds: Vec<BoundWitness>,
+}
+
+impl RangeWitness {
+    /// Commits to the bit decomposition of each bound of `predicate` for
+    /// `value` and appends the commitments to `transcript`. `m_tilde` must be
+    /// the BBS+ proof's blinding for the same hidden message.
+    pub(crate) fn commit<R: RngCore + CryptoRng>(
+        predicate: &RangePredicate,
+        value: u64,
+        m_tilde: Scalar,
+        transcript: &mut Vec<u8>,
+        rng: &mut R,
+    ) -> Result<Self, VerifyError> {
+        let g = *PEDERSEN_G;
+        let h = *PEDERSEN_H;
+        append_statement(transcript, predicate);
+        let mut bounds_out = Vec::with_capacity(2);
+        for (positive, constant) in bounds(predicate) {
+            let delta = if positive { value.checked_sub(constant) } else { constant.checked_sub(value) };
+            let delta = delta.ok_or(VerifyError::PredicateUnsatisfied { index: predicate.index })?;
+
+            let mut bits = Vec::with_capacity(RANGE_BITS);
+            let mut sum_r = Scalar::ZERO;
+            for (i, p) in powers_of_two().into_iter().enumerate() {
+                let bit = (delta >> i) & 1 == 1;
+                let r = random_scalar(rng)?;
+                let commitment = Backend::lincomb(&[h, g], &[r, Scalar::from(bit as u64)]).to_affine();
+                let k = random_scalar(rng)?;
+                let c_sim = random_scalar(rng)?;
+                let z_sim = random_scalar(rng)?;
+                // Real branch t = h*k, other branch simulated
+                let t_real = Backend::lincomb(&[h], &[k]);
+                let sim_offset = if bit { Scalar::ZERO } else { c_sim };
+                let t_sim = Backend::lincomb(&[h, commitment, g], &[z_sim, -c_sim, sim_offset]);
+                let (t0, t1) = if bit { (t_sim, t_real) } else { (t_real, t_sim) };
+                append_bit(transcript, &commitment, &t0, &t1);
+                sum_r += r * p;
+                bits.push(BitWitness { bit, r, k, c_sim, z_sim, commitment });
+            }
+
+            let rho = if positive { sum_r } else { -sum_r };
+            let rho_tilde = random_scalar(rng)?;
+            let t_link = Backend::lincomb(&[g, h], &[m_tilde, rho_tilde]);
+            transcript.extend_from_slice(&t_link.to_affine().to_compressed());
+            bounds_out.push(BoundWitness { bits, rho, rho_tilde });
+        }
+        Ok(Self { bounds: bounds_out })
+    }
+
+    pub(crate) fn respond(&self, challenge: Scalar) -> RangeProof {
+        let bounds = self
+            .bounds
+            .iter()
+            .map(|bound| {
+                let bits = bound
+                    .bits
+                    .iter()
+                    .map(|w| {
+                        let c_real = challenge - w.c_sim;
+                        let z_real = w.k + c_real * w.r;
+                        let (c0, z0, z1) = if w.bit {
+                            (w.c_sim, w.z_sim, z_real)
+                        } else {
+                            (c_real, z_real, w.z_sim)
+                        };
+                        BitProof { commitment: w.commitment, c0, z0, z1 }
+                    })
+                    .collect();
+                BoundProof { bits, rho_hat: bound.rho_tilde + challenge * bound.rho }
+            })
+            .collect();
+        RangeProof { bounds }
+    }
+}
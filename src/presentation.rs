@@ -0,0 +1,340 @@
+// Presentation Envelope
+// =====================
+// Bundles a proof with everything its verifier needs, so prover and verifier
+// cannot disagree on positional arguments. The nonce is a plain byte string;
+// an empty nonce is the same as `create_proof` with None. Encodes to CBOR
+// (byte strings) and JSON (base64url strings), with snake_case keys.
+//
+// The verifier passes the nonce and site_id it expects rather than trusting
+// the envelope, and the issuer key it looked up by `issuer_key_id`.
+
+use crate::bbs::{BbsProof, BbsPublicKey, BbsSignature};
+use crate::periwinkle::PeriwinkleRng;
+use crate::{checked, Verdict, VerifyError};
+use rand::{CryptoRng, RngCore};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+
+/// Envelope format produced by `create_presentation`
+pub const PRESENTATION_VERSION: u32 = 1;
+
+const KEY_ID_DST: &[u8] = b"MULTIPASS_BBS_KEY_ID_";
+
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct Presentation {
+    pub version: u32,
+    /// `issuer_key_id` of the key the credential was signed with
+    pub issuer_key_id: String,
+    pub proof: Vec<u8>,
+    pub total_message_count: u64,
+    pub revealed_indices: Vec<u32>,
+    pub revealed_messages: Vec<Vec<u8>>,
+    pub nonce: Vec<u8>,
+    pub site_id: Vec<u8>,
+    pub epoch: Option<Vec<u8>>,
+    pub alias_index: u64,
+    pub freshness_claim: Option<Vec<u8>>,
+}
+
+/// Byte string: CBOR bytes, or base64url without padding in JSON
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Bytes(Vec<u8>);
+
+impl Serialize for Bytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&URL_SAFE_NO_PAD.encode(&self.0))
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BytesVisitor;
+
+        impl serde::de::Visitor<'_> for BytesVisitor {
+            type Value = Bytes;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a byte string or base64url string")
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Bytes, E> {
+                Ok(Bytes(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Bytes, E> {
+                Ok(Bytes(v))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Bytes, E> {
+                URL_SAFE_NO_PAD.decode(v).map(Bytes).map_err(E::custom)
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BytesVisitor)
+        } else {
+            deserializer.deserialize_bytes(BytesVisitor)
+        }
+    }
+}
+
+/// Serialized form of `Presentation`
+#[derive(Serialize, Deserialize)]
+struct PresentationWire {
+    version: u32,
+    issuer_key_id: String,
+    proof: Bytes,
+    total_message_count: u64,
+    revealed_indices: Vec<u32>,
+    revealed_messages: Vec<Bytes>,
+    nonce: Bytes,
+    site_id: Bytes,
+    epoch: Option<Bytes>,
+    alias_index: u64,
+    freshness_claim: Option<Bytes>,
+}
+
+impl From<&Presentation> for PresentationWire {
+    fn from(p: &Presentation) -> Self {
+        Self {
+            version: p.version,
+            issuer_key_id: p.issuer_key_id.clone(),
+            proof: Bytes(p.proof.clone()),
+            total_message_count: p.total_message_count,
+            revealed_indices: p.revealed_indices.clone(),
+            revealed_messages: p.revealed_messages.iter().cloned().map(Bytes).collect(),
+            nonce: Bytes(p.nonce.clone()),
+            site_id: Bytes(p.site_id.clone()),
+            epoch: p.epoch.clone().map(Bytes),
+            alias_index: p.alias_index,
+            freshness_claim: p.freshness_claim.clone().map(Bytes),
+        }
+    }
+}
+
+impl From<PresentationWire> for Presentation {
+    fn from(w: PresentationWire) -> Self {
+        Self {
+            version: w.version,
+            issuer_key_id: w.issuer_key_id,
+            proof: w.proof.0,
+            total_message_count: w.total_message_count,
+            revealed_indices: w.revealed_indices,
+            revealed_messages: w.revealed_messages.into_iter().map(|m| m.0).collect(),
+            nonce: w.nonce.0,
+            site_id: w.site_id.0,
+            epoch: w.epoch.map(|e| e.0),
+            alias_index: w.alias_index,
+            freshness_claim: w.freshness_claim.map(|f| f.0),
+        }
+    }
+}
+
+impl Presentation {
+    pub fn to_cbor(&self) -> Result<Vec<u8>, VerifyError> {
+        let mut out = Vec::new();
+        ciborium::ser::into_writer(&PresentationWire::from(self), &mut out).map_err(|_| VerifyError::CryptoError)?;
+        Ok(out)
+    }
+
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, VerifyError> {
+        let wire: PresentationWire = ciborium::de::from_reader(bytes).map_err(|_| VerifyError::proof_structure("presentation"))?;
+        Ok(wire.into())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&PresentationWire::from(self)).expect("presentation serializes")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, VerifyError> {
+        let wire: PresentationWire = serde_json::from_str(json).map_err(|_| VerifyError::proof_structure("presentation"))?;
+        Ok(wire.into())
+    }
+
+    /// Verifies against `public_key` for the verifier's own nonce and site
+    pub fn verify(
+        &self,
+        public_key: &BbsPublicKey,
+        expected_nonce: &[u8],
+        expected_site_id: &[u8],
+    ) -> Result<bool, VerifyError> {
+        checked(self.check(public_key, expected_nonce, expected_site_id))
+    }
+
+    /// `verify`, failing with the check that did not pass instead of false
+    pub fn check(
+        &self,
+        public_key: &BbsPublicKey,
+        expected_nonce: &[u8],
+        expected_site_id: &[u8],
+    ) -> Result<(), VerifyError> {
+        if self.version != PRESENTATION_VERSION {
+            return Err(VerifyError::UnsupportedProofVersion);
+        }
+        if self.issuer_key_id != key_id(public_key) {
+            return Err(VerifyError::InvalidKey);
+        }
+        if self.nonce != expected_nonce {
+            return Err(VerifyError::ContextMismatch { field: "nonce".to_string() });
+        }
+        if self.site_id != expected_site_id {
+            return Err(VerifyError::ContextMismatch { field: "site_id".to_string() });
+        }
+        public_key.check_proof_with_predicates(
+            &BbsProof::parse(&self.proof)?,
+            self.total_message_count as usize,
+            &self.revealed_indices,
+            &self.revealed_messages,
+            &[],
+            &self.nonce,
+            &self.site_id,
+            self.epoch.as_deref(),
+            self.alias_index,
+            self.freshness_claim.as_deref(),
+        )
+    }
+}
+
+/// Hex SHA-256 of the serialized key
+fn key_id(public_key: &BbsPublicKey) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(KEY_ID_DST);
+    hasher.update(public_key.to_bytes());
+    hex::encode(hasher.finalize())
+}
+
+// ============================================================================
+// Exported API
+// ============================================================================
+
+/// Identifier verifiers use to look up the issuer key of a presentation
+#[uniffi::export]
+pub fn issuer_key_id(public_key: Vec<u8>) -> Result<String, VerifyError> {
+    Ok(key_id(&BbsPublicKey::parse(&public_key)?))
+}
+
+/// `create_proof`, wrapped with its verification inputs
+#[uniffi::export]
+#[allow(clippy::too_many_arguments)]
+pub fn create_presentation(
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+    revealed_indices: Vec<u32>,
+    nonce: Vec<u8>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+) -> Result<Presentation, VerifyError> {
+    create_presentation_with_rng(
+        public_key,
+        signature,
+        messages,
+        revealed_indices,
+        nonce,
+        site_id,
+        epoch,
+        alias_index,
+        freshness_claim,
+        &mut PeriwinkleRng,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_presentation_with_rng<R: RngCore + CryptoRng>(
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+    revealed_indices: Vec<u32>,
+    nonce: Vec<u8>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+    rng: &mut R,
+) -> Result<Presentation, VerifyError> {
+    let pk = BbsPublicKey::parse(&public_key)?;
+    let sig = BbsSignature::parse(&signature)?;
+    let mut revealed_indices = revealed_indices;
+    revealed_indices.sort_unstable();
+    revealed_indices.dedup();
+    let proof = sig.prove_with_rng(
+        &pk,
+        &messages,
+        &revealed_indices,
+        Some(&nonce),
+        &site_id,
+        epoch.as_deref(),
+        alias_index,
+        freshness_claim.as_deref(),
+        rng,
+    )?;
+    let revealed_messages = revealed_indices
+        .iter()
+        .map(|&i| messages.get(i as usize).cloned().ok_or(VerifyError::InvalidSignature))
+        .collect::<Result<_, _>>()?;
+    Ok(Presentation {
+        version: PRESENTATION_VERSION,
+        issuer_key_id: key_id(&pk),
+        proof: proof.to_bytes(),
+        total_message_count: messages.len() as u64,
+        revealed_indices,
+        revealed_messages,
+        nonce,
+        site_id,
+        epoch,
+        alias_index,
+        freshness_claim,
+    })
+}
+
+/// Verifies a presentation for the nonce and site_id this verifier issued.
+/// Fails with InvalidKey if it names another issuer key.
+#[uniffi::export]
+pub fn verify_presentation(
+    public_key: Vec<u8>,
+    presentation: Presentation,
+    expected_nonce: Vec<u8>,
+    expected_site_id: Vec<u8>,
+) -> Result<bool, VerifyError> {
+    presentation.verify(&BbsPublicKey::parse(&public_key)?, &expected_nonce, &expected_site_id)
+}
+
+/// `verify_presentation` reporting which check failed
+#[uniffi::export]
+pub fn verify_presentation_verdict(
+    public_key: Vec<u8>,
+    presentation: Presentation,
+    expected_nonce: Vec<u8>,
+    expected_site_id: Vec<u8>,
+) -> Verdict {
+    let check = || presentation.check(&BbsPublicKey::parse(&public_key)?, &expected_nonce, &expected_site_id);
+    check().into()
+}
+
+#[uniffi::export]
+pub fn presentation_to_cbor(presentation: Presentation) -> Result<Vec<u8>, VerifyError> {
+    presentation.to_cbor()
+}
+
+#[uniffi::export]
+pub fn presentation_from_cbor(bytes: Vec<u8>) -> Result<Presentation, VerifyError> {
+    Presentation::from_cbor(&bytes)
+}
+
+#[uniffi::export]
+pub fn presentation_to_json(presentation: Presentation) -> String {
+    presentation.to_json()
+}
+
+#[uniffi::export]
+pub fn presentation_from_json(json: String) -> Result<Presentation, VerifyError> {
+    Presentation::from_json(&json)
+}
@@ -0,0 +1,413 @@
+// Credential Schemas
+// ==================
+// Names and types the signed messages of a credential. The message layout is
+//   0        schema hash (always revealed, so the verifier knows the layout)
+//   1..=n    attributes, in schema order
+//   n + 1    nym secret (always hidden)
+// so a key for an n-attribute schema needs n + 2 messages.
+//
+// Integer, date and boolean attributes are integer-encoded
+// (`encode_integer_message`) and can carry range predicates; dates become
+// YYYYMMDD so their numeric order is their calendar order. String and bytes
+// attributes are hashed to scalars.
+//
+// The schema is shared as JSON:
+//   {"id": "...", "attributes": [{"name": "birthdate", "type": "date"}, ...]}
+
+use crate::bbs::{BbsProof, BbsPublicKey, BbsSecretKey, BbsSignature};
+use crate::predicate::{decode_integer_message, encode_integer_message};
+use crate::periwinkle::PeriwinkleRng;
+use crate::VerifyError;
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+
+const SCHEMA_HASH_DST: &[u8] = b"MULTIPASS_CREDENTIAL_SCHEMA_V1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, uniffi::Enum)]
+#[serde(rename_all = "lowercase")]
+pub enum AttributeType {
+    String,
+    /// Unsigned 64-bit integer
+    Integer,
+    /// ISO 8601 calendar date, YYYY-MM-DD
+    Date,
+    Boolean,
+    Bytes,
+}
+
+impl AttributeType {
+    fn tag(&self) -> u8 {
+        match self {
+            AttributeType::String => 0,
+            AttributeType::Integer => 1,
+            AttributeType::Date => 2,
+            AttributeType::Boolean => 3,
+            AttributeType::Bytes => 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Enum)]
+pub enum AttributeValue {
+    String { value: String },
+    Integer { value: u64 },
+    Date { value: String },
+    Boolean { value: bool },
+    Bytes { value: Vec<u8> },
+}
+
+impl AttributeValue {
+    pub fn attribute_type(&self) -> AttributeType {
+        match self {
+            AttributeValue::String { .. } => AttributeType::String,
+            AttributeValue::Integer { .. } => AttributeType::Integer,
+            AttributeValue::Date { .. } => AttributeType::Date,
+            AttributeValue::Boolean { .. } => AttributeType::Boolean,
+            AttributeValue::Bytes { .. } => AttributeType::Bytes,
+        }
+    }
+
+    /// Signed message for this value
+    pub fn to_message(&self) -> Result<Vec<u8>, VerifyError> {
+        Ok(match self {
+            AttributeValue::String { value } => value.as_bytes().to_vec(),
+            AttributeValue::Integer { value } => encode_integer_message(*value),
+            AttributeValue::Date { value } => encode_integer_message(parse_date(value)?),
+            AttributeValue::Boolean { value } => encode_integer_message(*value as u64),
+            AttributeValue::Bytes { value } => value.clone(),
+        })
+    }
+
+    /// Inverse of `to_message` for a revealed message of type `attribute_type`
+    pub fn from_message(attribute_type: AttributeType, message: &[u8]) -> Result<Self, VerifyError> {
+        let integer = || decode_integer_message(message).ok_or(VerifyError::SchemaMismatch);
+        Ok(match attribute_type {
+            AttributeType::String => AttributeValue::String {
+                value: String::from_utf8(message.to_vec()).map_err(|_| VerifyError::SchemaMismatch)?,
+            },
+            AttributeType::Integer => AttributeValue::Integer { value: integer()? },
+            AttributeType::Date => AttributeValue::Date { value: format_date(integer()?)? },
+            AttributeType::Boolean => match integer()? {
+                0 => AttributeValue::Boolean { value: false },
+                1 => AttributeValue::Boolean { value: true },
+                _ => return Err(VerifyError::SchemaMismatch),
+            },
+            AttributeType::Bytes => AttributeValue::Bytes { value: message.to_vec() },
+        })
+    }
+}
+
+fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+fn check_date(year: u64, month: u64, day: u64) -> Result<u64, VerifyError> {
+    if !(1..=9999).contains(&year) || !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return Err(VerifyError::SchemaMismatch);
+    }
+    Ok(year * 10_000 + month * 100 + day)
+}
+
+/// "YYYY-MM-DD" -> YYYYMMDD
+fn parse_date(date: &str) -> Result<u64, VerifyError> {
+    let bytes = date.as_bytes();
+    if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return Err(VerifyError::SchemaMismatch);
+    }
+    let field = |range: std::ops::Range<usize>| {
+        let digits = &date[range];
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(VerifyError::SchemaMismatch);
+        }
+        digits.parse::<u64>().map_err(|_| VerifyError::SchemaMismatch)
+    };
+    check_date(field(0..4)?, field(5..7)?, field(8..10)?)
+}
+
+fn format_date(encoded: u64) -> Result<String, VerifyError> {
+    let (year, month, day) = (encoded / 10_000, encoded / 100 % 100, encoded % 100);
+    check_date(year, month, day)?;
+    Ok(format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, uniffi::Record)]
+pub struct AttributeDefinition {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub attribute_type: AttributeType,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, uniffi::Record)]
+pub struct CredentialSchema {
+    /// Identifier of the credential type, e.g. a URL
+    pub id: String,
+    pub attributes: Vec<AttributeDefinition>,
+}
+
+impl CredentialSchema {
+    /// Rejects empty or duplicate attribute names
+    pub fn validate(&self) -> Result<(), VerifyError> {
+        let mut names = HashSet::new();
+        for attribute in &self.attributes {
+            if attribute.name.is_empty() || !names.insert(attribute.name.as_str()) {
+                return Err(VerifyError::SchemaMismatch);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, VerifyError> {
+        let schema: Self = serde_json::from_str(json).map_err(|_| VerifyError::SchemaMismatch)?;
+        schema.validate()?;
+        Ok(schema)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("schema serializes")
+    }
+
+    /// Messages a key for this schema must support
+    pub fn message_count(&self) -> usize {
+        self.attributes.len() + 2
+    }
+
+    /// SHA-256 over the id and the (name, type) list, each length-prefixed
+    pub fn hash(&self) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(SCHEMA_HASH_DST);
+        hasher.update((self.id.len() as u64).to_be_bytes());
+        hasher.update(self.id.as_bytes());
+        hasher.update((self.attributes.len() as u32).to_be_bytes());
+        for attribute in &self.attributes {
+            hasher.update((attribute.name.len() as u64).to_be_bytes());
+            hasher.update(attribute.name.as_bytes());
+            hasher.update([attribute.attribute_type.tag()]);
+        }
+        hasher.finalize().to_vec()
+    }
+
+    /// Message index of the attribute called `name`
+    pub fn index_of(&self, name: &str) -> Result<u32, VerifyError> {
+        self.attributes
+            .iter()
+            .position(|a| a.name == name)
+            .map(|i| i as u32 + 1)
+            .ok_or(VerifyError::SchemaMismatch)
+    }
+
+    /// Full message list: schema hash, every attribute, nym secret
+    pub fn messages(
+        &self,
+        attributes: &HashMap<String, AttributeValue>,
+        nym_secret: &[u8],
+    ) -> Result<Vec<Vec<u8>>, VerifyError> {
+        self.validate()?;
+        if attributes.len() != self.attributes.len() {
+            return Err(VerifyError::SchemaMismatch);
+        }
+        let mut messages = vec![self.hash()];
+        for definition in &self.attributes {
+            let value = attributes.get(&definition.name).ok_or(VerifyError::SchemaMismatch)?;
+            if value.attribute_type() != definition.attribute_type {
+                return Err(VerifyError::SchemaMismatch);
+            }
+            messages.push(value.to_message()?);
+        }
+        messages.push(nym_secret.to_vec());
+        Ok(messages)
+    }
+
+    /// Revealed indices, schema hash first, for the named attributes
+    pub fn revealed_indices(&self, names: &[String]) -> Result<Vec<u32>, VerifyError> {
+        let mut indices = vec![0];
+        for name in names {
+            indices.push(self.index_of(name)?);
+        }
+        indices.sort_unstable();
+        indices.dedup();
+        Ok(indices)
+    }
+
+    /// Revealed indices and messages for the verifier, schema hash included
+    pub fn revealed_messages(
+        &self,
+        revealed: &HashMap<String, AttributeValue>,
+    ) -> Result<(Vec<u32>, Vec<Vec<u8>>), VerifyError> {
+        self.validate()?;
+        let mut disclosed = vec![(0, self.hash())];
+        for (name, value) in revealed {
+            let index = self.index_of(name)?;
+            if value.attribute_type() != self.attributes[index as usize - 1].attribute_type {
+                return Err(VerifyError::SchemaMismatch);
+            }
+            disclosed.push((index, value.to_message()?));
+        }
+        disclosed.sort_by_key(|(i, _)| *i);
+        Ok(disclosed.into_iter().unzip())
+    }
+}
+
+// ============================================================================
+// Exported API
+// ============================================================================
+
+#[uniffi::export]
+pub fn schema_to_json(schema: CredentialSchema) -> String {
+    schema.to_json()
+}
+
+#[uniffi::export]
+pub fn schema_from_json(json: String) -> Result<CredentialSchema, VerifyError> {
+    CredentialSchema::from_json(&json)
+}
+
+/// Message 0 of every credential of this schema
+#[uniffi::export]
+pub fn schema_hash(schema: CredentialSchema) -> Vec<u8> {
+    schema.hash()
+}
+
+/// Message index of an attribute, e.g. for a `RangePredicate`
+#[uniffi::export]
+pub fn schema_attribute_index(schema: CredentialSchema, name: String) -> Result<u32, VerifyError> {
+    schema.index_of(&name)
+}
+
+/// Decodes a revealed message of the named attribute
+#[uniffi::export]
+pub fn schema_attribute_value(
+    schema: CredentialSchema,
+    name: String,
+    message: Vec<u8>,
+) -> Result<AttributeValue, VerifyError> {
+    let index = schema.index_of(&name)? as usize;
+    AttributeValue::from_message(schema.attributes[index - 1].attribute_type, &message)
+}
+
+/// Signs a credential given by attribute name; `nym_secret` comes from
+/// `generate_nym_secret`
+#[uniffi::export]
+pub fn sign_with_schema(
+    secret_key: Vec<u8>,
+    public_key: Vec<u8>,
+    schema: CredentialSchema,
+    attributes: HashMap<String, AttributeValue>,
+    nym_secret: Vec<u8>,
+) -> Result<Vec<u8>, VerifyError> {
+    sign_with_schema_with_rng(secret_key, public_key, schema, attributes, nym_secret, &mut PeriwinkleRng)
+}
+
+pub fn sign_with_schema_with_rng<R: RngCore + CryptoRng>(
+    secret_key: Vec<u8>,
+    public_key: Vec<u8>,
+    schema: CredentialSchema,
+    attributes: HashMap<String, AttributeValue>,
+    nym_secret: Vec<u8>,
+    rng: &mut R,
+) -> Result<Vec<u8>, VerifyError> {
+    let sk = BbsSecretKey::from_bytes(secret_key)?;
+    let pk = BbsPublicKey::parse(&public_key)?;
+    Ok(sk.sign_messages_with_rng(&pk, &schema.messages(&attributes, &nym_secret)?, rng)?.to_bytes())
+}
+
+/// `create_proof` revealing the schema hash and the named attributes
+#[uniffi::export]
+#[allow(clippy::too_many_arguments)]
+pub fn create_proof_with_schema(
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    schema: CredentialSchema,
+    attributes: HashMap<String, AttributeValue>,
+    nym_secret: Vec<u8>,
+    revealed_attributes: Vec<String>,
+    nonce: Option<Vec<u8>>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+) -> Result<Vec<u8>, VerifyError> {
+    create_proof_with_schema_with_rng(
+        public_key,
+        signature,
+        schema,
+        attributes,
+        nym_secret,
+        revealed_attributes,
+        nonce,
+        site_id,
+        epoch,
+        alias_index,
+        freshness_claim,
+        &mut PeriwinkleRng,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_proof_with_schema_with_rng<R: RngCore + CryptoRng>(
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    schema: CredentialSchema,
+    attributes: HashMap<String, AttributeValue>,
+    nym_secret: Vec<u8>,
+    revealed_attributes: Vec<String>,
+    nonce: Option<Vec<u8>>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+    rng: &mut R,
+) -> Result<Vec<u8>, VerifyError> {
+    let pk = BbsPublicKey::parse(&public_key)?;
+    let sig = BbsSignature::parse(&signature)?;
+    let messages = schema.messages(&attributes, &nym_secret)?;
+    let proof = sig.prove_with_rng(
+        &pk,
+        &messages,
+        &schema.revealed_indices(&revealed_attributes)?,
+        nonce.as_deref(),
+        &site_id,
+        epoch.as_deref(),
+        alias_index,
+        freshness_claim.as_deref(),
+        rng,
+    )?;
+    Ok(proof.to_bytes())
+}
+
+/// `verify_proof_safe` for a proof from `create_proof_with_schema`, given
+/// the attributes it revealed
+#[uniffi::export]
+#[allow(clippy::too_many_arguments)]
+pub fn verify_proof_with_schema(
+    public_key: Vec<u8>,
+    proof: Vec<u8>,
+    schema: CredentialSchema,
+    revealed_attributes: HashMap<String, AttributeValue>,
+    nonce: Vec<u8>,
+    site_id: Vec<u8>,
+    epoch: Option<Vec<u8>>,
+    alias_index: u64,
+    freshness_claim: Option<Vec<u8>>,
+) -> Result<bool, VerifyError> {
+    let pk = BbsPublicKey::parse(&public_key)?;
+    let proof = BbsProof::parse(&proof)?;
+    let (indices, messages) = schema.revealed_messages(&revealed_attributes)?;
+    pk.verify_proof_of(
+        &proof,
+        schema.message_count(),
+        &indices,
+        &messages,
+        &nonce,
+        &site_id,
+        epoch.as_deref(),
+        alias_index,
+        freshness_claim.as_deref(),
+    )
+}
@@ -0,0 +1,297 @@
+// Sealed Root Secret
+// ==================
+// Persistent root of every "hardware secret" derivation. The roots live in
+// one file, sealed with ChaCha20-Poly1305 under a 32-byte key-encryption key
+// the platform keeps in its keystore (Android Keystore, Secure Enclave), so
+// derivations are the same across calls and process restarts.
+//
+// Each root has a version. Rotation adds a fresh root and makes it current;
+// older versions stay available until retired, so values derived under them
+// (linkage tags, for example) can still be recomputed.
+//
+// Derivation is an HKDF-SHA256 hierarchy over context labels:
+//   k_0 = HKDF-Extract(DST || version, root)
+//   k_i = HKDF-Expand(k_{i-1}, label_i, 32)
+//
+// File: MAGIC || format || nonce || AEAD(current || count || (version || root)*)
+// with MAGIC || format as associated data.
+
+use crate::periwinkle::{EntropyError, PeriwinkleRng};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hkdf::Hkdf;
+use rand::{CryptoRng, RngCore};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use zeroize::{Zeroize, Zeroizing};
+
+const MAGIC: &[u8] = b"PWROOT";
+const FORMAT_VERSION: u8 = 1;
+const NONCE_LEN: usize = 12;
+const ROOT_LEN: usize = 32;
+const ENTRY_LEN: usize = 4 + ROOT_LEN;
+const DERIVE_DST: &[u8] = b"PERIWINKLE_SEALED_ROOT_V1_";
+
+fn sealed_error(reason: impl ToString) -> EntropyError {
+    EntropyError::SealedRoot { reason: reason.to_string() }
+}
+
+fn cipher(kek: &[u8; 32]) -> ChaCha20Poly1305 {
+    ChaCha20Poly1305::new(&Key::from(*kek))
+}
+
+/// Versioned root secrets, backed by a sealed file
+pub struct SealedRoot {
+    path: PathBuf,
+    kek: Zeroizing<[u8; 32]>,
+    current: u32,
+    roots: BTreeMap<u32, [u8; ROOT_LEN]>,
+}
+
+impl Drop for SealedRoot {
+    fn drop(&mut self) {
+        for root in self.roots.values_mut() {
+            root.zeroize();
+        }
+    }
+}
+
+impl std::fmt::Debug for SealedRoot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SealedRoot")
+            .field("path", &self.path)
+            .field("current", &self.current)
+            .field("versions", &self.versions())
+            .finish_non_exhaustive()
+    }
+}
+
+impl SealedRoot {
+    /// Unseals an existing root file. Fails if `kek` is wrong or the file
+    /// was modified.
+    pub fn open(path: impl Into<PathBuf>, kek: [u8; 32]) -> Result<Self, EntropyError> {
+        let path = path.into();
+        let sealed = std::fs::read(&path).map_err(sealed_error)?;
+        let kek = Zeroizing::new(kek);
+        let header_len = MAGIC.len() + 1;
+        if sealed.len() < header_len + NONCE_LEN || &sealed[..MAGIC.len()] != MAGIC {
+            return Err(sealed_error("not a sealed root file"));
+        }
+        if sealed[MAGIC.len()] != FORMAT_VERSION {
+            return Err(sealed_error(format!("unsupported format {}", sealed[MAGIC.len()])));
+        }
+        let (header, rest) = sealed.split_at(header_len);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        let nonce: [u8; NONCE_LEN] = nonce.try_into().expect("split at NONCE_LEN");
+        let plaintext = Zeroizing::new(
+            cipher(&kek)
+                .decrypt(&Nonce::from(nonce), Payload { msg: ciphertext, aad: header })
+                .map_err(|_| sealed_error("wrong key-encryption key or tampered file"))?,
+        );
+        let (current, roots) = decode_roots(&plaintext)?;
+        Ok(Self { path, kek, current, roots })
+    }
+
+    /// Creates a root file holding a fresh version 1 root. Fails if `path`
+    /// already exists.
+    pub fn create(path: impl Into<PathBuf>, kek: [u8; 32]) -> Result<Self, EntropyError> {
+        Self::create_with_rng(path, kek, &mut PeriwinkleRng)
+    }
+
+    pub fn create_with_rng<R: RngCore + CryptoRng>(
+        path: impl Into<PathBuf>,
+        kek: [u8; 32],
+        rng: &mut R,
+    ) -> Result<Self, EntropyError> {
+        let path = path.into();
+        if path.exists() {
+            return Err(sealed_error(format!("{} already exists", path.display())));
+        }
+        let mut root = Self { path, kek: Zeroizing::new(kek), current: 0, roots: BTreeMap::new() };
+        root.rotate_with_rng(rng)?;
+        Ok(root)
+    }
+
+    /// `open` if `path` exists, else `create`
+    pub fn open_or_create(path: impl Into<PathBuf>, kek: [u8; 32]) -> Result<Self, EntropyError> {
+        let path = path.into();
+        if path.exists() {
+            Self::open(path, kek)
+        } else {
+            Self::create(path, kek)
+        }
+    }
+
+    pub fn current_version(&self) -> u32 {
+        self.current
+    }
+
+    /// Versions still available, oldest first
+    pub fn versions(&self) -> Vec<u32> {
+        self.roots.keys().copied().collect()
+    }
+
+    /// Adds a fresh root as the new current version and persists it
+    pub fn rotate(&mut self) -> Result<u32, EntropyError> {
+        self.rotate_with_rng(&mut PeriwinkleRng)
+    }
+
+    pub fn rotate_with_rng<R: RngCore + CryptoRng>(&mut self, rng: &mut R) -> Result<u32, EntropyError> {
+        let version = self.current.checked_add(1).ok_or_else(|| sealed_error("version overflow"))?;
+        let mut root = [0u8; ROOT_LEN];
+        rng.try_fill_bytes(&mut root).map_err(sealed_error)?;
+        self.roots.insert(version, root);
+        root.zeroize();
+        let previous = std::mem::replace(&mut self.current, version);
+        if let Err(e) = self.persist(rng) {
+            self.current = previous;
+            if let Some(mut root) = self.roots.remove(&version) {
+                root.zeroize();
+            }
+            return Err(e);
+        }
+        Ok(version)
+    }
+
+    /// Removes an old version; values derived under it can no longer be
+    /// recomputed. The current version cannot be retired.
+    pub fn retire(&mut self, version: u32) -> Result<(), EntropyError> {
+        if version == self.current {
+            return Err(sealed_error("cannot retire the current version"));
+        }
+        let mut root = self.roots.remove(&version).ok_or(EntropyError::UnknownRootVersion { version })?;
+        if let Err(e) = self.persist(&mut PeriwinkleRng) {
+            self.roots.insert(version, root);
+            return Err(e);
+        }
+        root.zeroize();
+        Ok(())
+    }
+
+    /// Key for `label` under the current version
+    pub fn derive(&self, label: &[u8]) -> [u8; 32] {
+        self.derive_path(self.current, &[label]).expect("current version exists")
+    }
+
+    /// Key at the end of the `labels` path under `version`
+    pub fn derive_path(&self, version: u32, labels: &[&[u8]]) -> Result<[u8; 32], EntropyError> {
+        let root = self.roots.get(&version).ok_or(EntropyError::UnknownRootVersion { version })?;
+        let salt = [DERIVE_DST, &version.to_be_bytes()].concat();
+        let (prk, _) = Hkdf::<Sha256>::extract(Some(&salt), root);
+        let mut key: [u8; 32] = prk.into();
+        for label in labels {
+            let hk = Hkdf::<Sha256>::from_prk(&key).expect("32-byte PRK");
+            hk.expand(label, &mut key).expect("32-byte output");
+        }
+        Ok(key)
+    }
+
+    /// Seals the roots with a fresh nonce and atomically replaces the file
+    fn persist<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Result<(), EntropyError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.try_fill_bytes(&mut nonce).map_err(sealed_error)?;
+        let header = [MAGIC, &[FORMAT_VERSION]].concat();
+        let plaintext = Zeroizing::new(encode_roots(self.current, &self.roots));
+        let ciphertext = cipher(&self.kek)
+            .encrypt(&Nonce::from(nonce), Payload { msg: &plaintext, aad: &header })
+            .map_err(|_| sealed_error("encryption failed"))?;
+        write_atomic(&self.path, &[header.as_slice(), &nonce, &ciphertext].concat())
+    }
+}
+
+fn encode_roots(current: u32, roots: &BTreeMap<u32, [u8; ROOT_LEN]>) -> Vec<u8> {
+    let mut out = Vec::with_capacity(8 + roots.len() * ENTRY_LEN);
+    out.extend_from_slice(&current.to_be_bytes());
+    out.extend_from_slice(&(roots.len() as u32).to_be_bytes());
+    for (version, root) in roots {
+        out.extend_from_slice(&version.to_be_bytes());
+        out.extend_from_slice(root);
+    }
+    out
+}
+
+fn decode_roots(bytes: &[u8]) -> Result<(u32, BTreeMap<u32, [u8; ROOT_LEN]>), EntropyError> {
+    let malformed = || sealed_error("malformed root list");
+    let u32_at = |i: usize| bytes.get(i..i + 4).map(|b| u32::from_be_bytes(b.try_into().unwrap()));
+    let current = u32_at(0).ok_or_else(malformed)?;
+    let count = u32_at(4).ok_or_else(malformed)? as usize;
+    if bytes.len() != 8 + count * ENTRY_LEN {
+        return Err(malformed());
+    }
+    let roots: BTreeMap<u32, [u8; ROOT_LEN]> = bytes[8..]
+        .chunks_exact(ENTRY_LEN)
+        .map(|entry| (u32::from_be_bytes(entry[..4].try_into().unwrap()), entry[4..].try_into().unwrap()))
+        .collect();
+    if roots.len() != count || !roots.contains_key(&current) {
+        return Err(malformed());
+    }
+    Ok((current, roots))
+}
+
+/// Writes to a sibling temporary file, then renames it over `path`
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), EntropyError> {
+    use std::io::Write;
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&tmp).map_err(sealed_error)?;
+    file.write_all(bytes).and_then(|_| file.sync_all()).map_err(sealed_error)?;
+    std::fs::rename(&tmp, path).map_err(sealed_error)
+}
+
+// ============================================================================
+// Process-wide root
+// ============================================================================
+
+static SEALED_ROOT: OnceLock<Mutex<SealedRoot>> = OnceLock::new();
+
+/// The root chosen with `init_sealed_root`
+pub(crate) fn sealed_root() -> Result<MutexGuard<'static, SealedRoot>, EntropyError> {
+    let root = SEALED_ROOT.get().ok_or_else(|| sealed_error("not initialized; call init_sealed_root"))?;
+    root.lock().map_err(|_| EntropyError::Poisoned)
+}
+
+/// Opens (or creates) the sealed root file backing `get_hardware_secret`.
+/// Returns the current version.
+#[uniffi::export]
+pub fn init_sealed_root(path: String, key_encryption_key: Vec<u8>) -> Result<u32, EntropyError> {
+    let kek = Zeroizing::new(key_encryption_key);
+    let kek: [u8; 32] = kek.as_slice().try_into().map_err(|_| sealed_error("key-encryption key must be 32 bytes"))?;
+    let root = SealedRoot::open_or_create(path, kek)?;
+    let version = root.current_version();
+    SEALED_ROOT.set(Mutex::new(root)).map_err(|_| EntropyError::AlreadyInitialized)?;
+    Ok(version)
+}
+
+/// Rotates the sealed root; later derivations use the returned version
+#[uniffi::export]
+pub fn rotate_hardware_secret() -> Result<u32, EntropyError> {
+    sealed_root()?.rotate()
+}
+
+#[uniffi::export]
+pub fn retire_hardware_secret(version: u32) -> Result<(), EntropyError> {
+    sealed_root()?.retire(version)
+}
+
+#[uniffi::export]
+pub fn hardware_secret_version() -> Result<u32, EntropyError> {
+    Ok(sealed_root()?.current_version())
+}
+
+/// Secret for `context` under a given root version, to recompute values
+/// derived before a rotation
+#[uniffi::export]
+pub fn derive_hardware_secret(context: Vec<u8>, version: u32) -> Result<Vec<u8>, EntropyError> {
+    Ok(sealed_root()?.derive_path(version, &[&context])?.to_vec())
+}
@@ -0,0 +1,248 @@
+// Bitstring Status Lists
+// ======================
+// W3C Bitstring Status List revocation. The issuer keeps one bit per issued
+// credential and publishes the whole list, GZIP-compressed and multibase
+// base64url encoded, as a credential signed with its BBS key. Each holder
+// credential signs its position in the list as an integer message
+// (`encode_status_index`), which the holder reveals so the verifier can look
+// the bit up. Index 0 is the most significant bit of the first byte.
+//
+// The revealed index is the same in every presentation, so verifiers can link
+// them; use accumulator revocation where presentations must stay unlinkable.
+
+use crate::bbs::{BbsPublicKey, BbsSecretKey, BbsSignature};
+use crate::predicate::{decode_integer_message, encode_integer_message};
+use crate::periwinkle::PeriwinkleRng;
+use crate::VerifyError;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rand::{CryptoRng, RngCore};
+use std::io::{Read, Write};
+
+/// Minimum list length (16 KiB), so a status index hides among many holders
+pub const MIN_STATUS_LIST_BITS: u64 = 131_072;
+
+/// Largest decompressed list a verifier accepts
+pub const MAX_STATUS_LIST_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Multibase prefix of base64url without padding
+const MULTIBASE_BASE64URL: char = 'u';
+
+/// Uncompressed bit array of a status list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusList {
+    bits: Vec<u8>,
+}
+
+impl StatusList {
+    /// All-clear list of at least `length` bits
+    pub fn new(length: u64) -> Result<Self, VerifyError> {
+        let length = length.max(MIN_STATUS_LIST_BITS);
+        if length > MAX_STATUS_LIST_BYTES * 8 {
+            return Err(VerifyError::StatusListLength { length });
+        }
+        Ok(Self { bits: vec![0; length.div_ceil(8) as usize] })
+    }
+
+    pub fn from_bytes(bits: Vec<u8>) -> Result<Self, VerifyError> {
+        let length = bits.len() as u64 * 8;
+        if !(MIN_STATUS_LIST_BITS..=MAX_STATUS_LIST_BYTES * 8).contains(&length) {
+            return Err(VerifyError::StatusListLength { length });
+        }
+        Ok(Self { bits })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bits
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bits
+    }
+
+    /// Number of status entries
+    pub fn len(&self) -> u64 {
+        self.bits.len() as u64 * 8
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Status bit at `index`, or None past the end of the list
+    pub fn get(&self, index: u64) -> Option<bool> {
+        let byte = self.bits.get(usize::try_from(index / 8).ok()?)?;
+        Some(byte & (0x80 >> (index % 8)) != 0)
+    }
+
+    pub fn set(&mut self, index: u64, status: bool) -> Result<(), VerifyError> {
+        let length = self.len();
+        let byte = usize::try_from(index / 8)
+            .ok()
+            .and_then(|i| self.bits.get_mut(i))
+            .ok_or(VerifyError::StatusIndexOutOfRange { index, length })?;
+        let mask = 0x80 >> (index % 8);
+        if status {
+            *byte |= mask;
+        } else {
+            *byte &= !mask;
+        }
+        Ok(())
+    }
+
+    /// `encodedList`: multibase base64url (no padding) of the GZIP-compressed bits
+    pub fn encode(&self) -> Result<String, VerifyError> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(&self.bits).map_err(|_| VerifyError::CryptoError)?;
+        let compressed = encoder.finish().map_err(|_| VerifyError::CryptoError)?;
+        let mut encoded = String::from(MULTIBASE_BASE64URL);
+        encoded.push_str(&URL_SAFE_NO_PAD.encode(compressed));
+        Ok(encoded)
+    }
+
+    pub fn decode(encoded: &str) -> Result<Self, VerifyError> {
+        let body = encoded.strip_prefix(MULTIBASE_BASE64URL).ok_or(VerifyError::InvalidSignature)?;
+        let compressed = URL_SAFE_NO_PAD.decode(body).map_err(|_| VerifyError::InvalidSignature)?;
+        // Bounded read, so a small payload cannot inflate without limit
+        let mut bits = Vec::new();
+        GzDecoder::new(compressed.as_slice())
+            .take(MAX_STATUS_LIST_BYTES + 1)
+            .read_to_end(&mut bits)
+            .map_err(|_| VerifyError::InvalidSignature)?;
+        Self::from_bytes(bits)
+    }
+}
+
+/// Signed status list, as published by the issuer
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct StatusListCredential {
+    /// URL or identifier of the list, matching the holder's credentialStatus
+    pub id: String,
+    /// "revocation" or "suspension"
+    pub status_purpose: String,
+    pub encoded_list: String,
+    /// Unix time the list was signed at
+    pub valid_from: u64,
+    /// BBS signature over id, status_purpose, encoded_list and valid_from
+    pub signature: Vec<u8>,
+}
+
+impl StatusListCredential {
+    fn messages(id: &str, status_purpose: &str, encoded_list: &str, valid_from: u64) -> Vec<Vec<u8>> {
+        vec![
+            id.as_bytes().to_vec(),
+            status_purpose.as_bytes().to_vec(),
+            encoded_list.as_bytes().to_vec(),
+            encode_integer_message(valid_from),
+        ]
+    }
+
+    /// Signs `list` with the first four generators of the issuer key
+    pub fn sign(
+        secret_key: &BbsSecretKey,
+        public_key: &BbsPublicKey,
+        id: &str,
+        status_purpose: &str,
+        list: &StatusList,
+        valid_from: u64,
+    ) -> Result<Self, VerifyError> {
+        Self::sign_with_rng(secret_key, public_key, id, status_purpose, list, valid_from, &mut PeriwinkleRng)
+    }
+
+    pub fn sign_with_rng<R: RngCore + CryptoRng>(
+        secret_key: &BbsSecretKey,
+        public_key: &BbsPublicKey,
+        id: &str,
+        status_purpose: &str,
+        list: &StatusList,
+        valid_from: u64,
+        rng: &mut R,
+    ) -> Result<Self, VerifyError> {
+        let encoded_list = list.encode()?;
+        let messages = Self::messages(id, status_purpose, &encoded_list, valid_from);
+        let signature = secret_key.sign_messages_with_rng(public_key, &messages, rng)?.to_bytes();
+        Ok(Self { id: id.to_string(), status_purpose: status_purpose.to_string(), encoded_list, valid_from, signature })
+    }
+
+    pub fn verify(&self, public_key: &BbsPublicKey) -> Result<bool, VerifyError> {
+        let signature = BbsSignature::parse(&self.signature)?;
+        let messages = Self::messages(&self.id, &self.status_purpose, &self.encoded_list, self.valid_from);
+        public_key.verify_signature(&signature, &messages)
+    }
+
+    /// Verifies the signature and decodes the list
+    pub fn verified_list(&self, public_key: &BbsPublicKey) -> Result<StatusList, VerifyError> {
+        if !self.verify(public_key)? {
+            return Err(VerifyError::InvalidSignature);
+        }
+        StatusList::decode(&self.encoded_list)
+    }
+}
+
+// ============================================================================
+// Exported API
+// ============================================================================
+
+/// Message binding a credential to its status list entry; sign it alongside
+/// the other attributes and reveal it in presentations
+#[uniffi::export]
+pub fn encode_status_index(index: u64) -> Vec<u8> {
+    encode_integer_message(index)
+}
+
+/// Signs the raw bits of a status list with the issuer key
+#[uniffi::export]
+pub fn sign_status_list(
+    secret_key: Vec<u8>,
+    public_key: Vec<u8>,
+    id: String,
+    status_purpose: String,
+    bitstring: Vec<u8>,
+    valid_from: u64,
+) -> Result<StatusListCredential, VerifyError> {
+    sign_status_list_with_rng(secret_key, public_key, id, status_purpose, bitstring, valid_from, &mut PeriwinkleRng)
+}
+
+pub fn sign_status_list_with_rng<R: RngCore + CryptoRng>(
+    secret_key: Vec<u8>,
+    public_key: Vec<u8>,
+    id: String,
+    status_purpose: String,
+    bitstring: Vec<u8>,
+    valid_from: u64,
+    rng: &mut R,
+) -> Result<StatusListCredential, VerifyError> {
+    let sk = BbsSecretKey::from_bytes(secret_key)?;
+    let pk = BbsPublicKey::parse(&public_key)?;
+    StatusListCredential::sign_with_rng(
+        &sk,
+        &pk,
+        &id,
+        &status_purpose,
+        &StatusList::from_bytes(bitstring)?,
+        valid_from,
+        rng,
+    )
+}
+
+#[uniffi::export]
+pub fn verify_status_list(public_key: Vec<u8>, credential: StatusListCredential) -> Result<bool, VerifyError> {
+    credential.verify(&BbsPublicKey::parse(&public_key)?)
+}
+
+/// Status bit of the entry named by a revealed `encode_status_index`
+/// message; true means revoked (or suspended, per the list's purpose).
+/// Fails if the list signature does not verify or the index is out of range.
+#[uniffi::export]
+pub fn check_credential_status(
+    public_key: Vec<u8>,
+    credential: StatusListCredential,
+    status_index_message: Vec<u8>,
+) -> Result<bool, VerifyError> {
+    let index = decode_integer_message(&status_index_message).ok_or(VerifyError::InvalidSignature)?;
+    let list = credential.verified_list(&BbsPublicKey::parse(&public_key)?)?;
+    list.get(index).ok_or(VerifyError::StatusIndexOutOfRange { index, length: list.len() })
+}
@@ -0,0 +1,283 @@
+// VB accumulator revocation: registry in MinerEngine, holder witness
+// updates and the zero-knowledge non-revocation proof
+// `cargo test --test accumulator`
+
+mod common;
+
+use common::*;
+use multipass::accumulator::{
+    accumulator_public_key, create_proof_with_revocation_with_rng, generate_accumulator_secret_key_with_rng,
+    update_non_revocation_witness, verify_non_revocation_witness, verify_proof_with_revocation, NonRevocationInput,
+    RevocationCheck,
+};
+use multipass::bbs::PROOF_VERSION;
+use multipass::deterministic::seeded_rng;
+use multipass::hash_to_curve::{hash_to_g1, hash_to_scalar};
+use multipass::{BbsPublicKey, G1Affine, G1Projective, G2Affine, Scalar};
+use bls12_381::pairing;
+use ff::Field;
+use group::Curve;
+
+/// Accumulator keys over a fresh Store
+struct Registry {
+    store: Store,
+    secret_key: Vec<u8>,
+    public_key: Vec<u8>,
+}
+
+impl Registry {
+    fn open(name: &str) -> Self {
+        let store = Store::open(&format!("accumulator-{}", name));
+        let secret_key = generate_accumulator_secret_key_with_rng(&mut seeded_rng(name.as_bytes())).unwrap();
+        let public_key = accumulator_public_key(secret_key.clone()).unwrap();
+        Self { store, secret_key, public_key }
+    }
+
+    fn issue_witness(&self, id: &[u8]) -> Result<(Vec<u8>, u64), String> {
+        self.store.engine.issue_non_revocation_witness(&self.secret_key, id)
+    }
+
+    fn revoke(&self, id: &[u8]) {
+        self.store.engine.revoke_credential(&self.secret_key, id).unwrap();
+    }
+
+    fn reinstate(&self, id: &[u8]) {
+        self.store.engine.reinstate_credential(&self.secret_key, id).unwrap();
+    }
+
+    fn value(&self) -> Vec<u8> {
+        self.store.engine.accumulator_value().unwrap()
+    }
+
+    /// Brings a witness issued at `sequence` up to date with the log
+    fn refresh(&self, witness: Vec<u8>, id: &[u8], sequence: u64) -> Result<Vec<u8>, multipass::VerifyError> {
+        let updates = self.store.engine.accumulator_updates_since(sequence).unwrap();
+        update_non_revocation_witness(witness, id.to_vec(), updates)
+    }
+
+    fn witness_holds(&self, witness: &[u8], id: &[u8]) -> bool {
+        verify_non_revocation_witness(self.public_key.clone(), self.value(), witness.to_vec(), id.to_vec()).unwrap()
+    }
+}
+
+#[test]
+fn accumulator_value_is_created_once() {
+    let registry = Registry::open("created-once");
+    let value = registry.value();
+    assert_eq!(value.len(), 48);
+    assert_eq!(registry.value(), value);
+    registry.revoke(b"bob");
+    let revoked = registry.value();
+    assert_ne!(revoked, value);
+    assert_eq!(registry.value(), revoked);
+}
+
+#[test]
+fn witness_survives_other_revocations_and_reinstatements() {
+    let registry = Registry::open("others");
+    let (witness, sequence) = registry.issue_witness(b"alice").unwrap();
+    assert!(registry.witness_holds(&witness, b"alice"));
+
+    registry.revoke(b"bob");
+    registry.revoke(b"carol");
+    registry.reinstate(b"bob");
+    // Not refreshed: no longer matches the accumulator
+    assert!(!registry.witness_holds(&witness, b"alice"));
+
+    let witness = registry.refresh(witness, b"alice", sequence).unwrap();
+    assert!(registry.witness_holds(&witness, b"alice"));
+    // A witness is specific to its element
+    assert!(!registry.witness_holds(&witness, b"bob"));
+}
+
+#[test]
+fn witness_fails_once_its_own_element_is_revoked() {
+    let registry = Registry::open("own");
+    let (witness, sequence) = registry.issue_witness(b"alice").unwrap();
+    registry.revoke(b"bob");
+    registry.revoke(b"alice");
+    assert!(registry.refresh(witness, b"alice", sequence).is_err());
+    assert!(registry.issue_witness(b"alice").is_err());
+
+    // Reinstated credentials get a fresh witness
+    registry.reinstate(b"alice");
+    let (witness, _) = registry.issue_witness(b"alice").unwrap();
+    assert!(registry.witness_holds(&witness, b"alice"));
+}
+
+/// Messages: name (revealed), revocation id (hidden, index 1), nym secret
+fn credential(revocation_id: &[u8]) -> Credential {
+    Issuer::new("accumulator", 3).issue(b"alice", vec![b"alice".to_vec(), revocation_id.to_vec()])
+}
+
+impl Credential {
+    fn prove(&self, accumulator: Vec<u8>, witness: Vec<u8>) -> Vec<u8> {
+        create_proof_with_revocation_with_rng(
+            self.public_key.clone(),
+            self.signature.clone(),
+            self.messages.clone(),
+            vec![0],
+            NONCE.to_vec(),
+            SITE.to_vec(),
+            None,
+            0,
+            None,
+            NonRevocationInput { accumulator, witness, message_index: 1 },
+            &mut seeded_rng(b"prove"),
+        )
+        .unwrap()
+    }
+
+    fn verify(&self, proof: Vec<u8>, registry: &Registry) -> bool {
+        verify_proof_with_revocation(
+            self.public_key.clone(),
+            proof,
+            3,
+            vec![0],
+            vec![self.messages[0].clone()],
+            NONCE.to_vec(),
+            SITE.to_vec(),
+            None,
+            0,
+            None,
+            RevocationCheck { public_key: registry.public_key.clone(), accumulator: registry.value(), message_index: 1 },
+        )
+        .unwrap()
+    }
+}
+
+#[test]
+fn non_revocation_proof_round_trip() {
+    let registry = Registry::open("proof");
+    let credential = credential(b"alice-id");
+    registry.revoke(b"bob-id");
+    let (witness, _) = registry.issue_witness(b"alice-id").unwrap();
+    let proof = credential.prove(registry.value(), witness);
+    assert!(credential.verify(proof, &registry));
+}
+
+#[test]
+fn proof_against_a_stale_accumulator_is_rejected() {
+    let registry = Registry::open("stale");
+    let credential = credential(b"alice-id");
+    let (witness, sequence) = registry.issue_witness(b"alice-id").unwrap();
+    let stale_value = registry.value();
+    registry.revoke(b"bob-id");
+
+    // Proven against the old value, checked against the current one
+    let proof = credential.prove(stale_value, witness.clone());
+    assert!(!credential.verify(proof, &registry));
+
+    // Refreshing the witness fixes it
+    let witness = registry.refresh(witness, b"alice-id", sequence).unwrap();
+    let proof = credential.prove(registry.value(), witness);
+    assert!(credential.verify(proof, &registry));
+}
+
+const H2S_DST: &[u8] = b"MULTIPASS_BBS_BLS12381G1_XMD:SHA-256_H2S_";
+const PSEUDONYM_DST: &[u8] = b"MULTIPASS_BBS_PSEUDONYM_H2G_";
+
+/// Offset of C' in a proof with two hidden messages and no nullifier
+const C_PRIME_OFFSET: usize = 1 + 48 * 3 + 32 * 5 + 48 + 1 + 4 + 32 * 2;
+
+fn g1(bytes: &[u8]) -> G1Affine {
+    G1Affine::from_compressed(bytes.try_into().unwrap()).unwrap()
+}
+
+fn scalar(bytes: &[u8]) -> Scalar {
+    Scalar::from_bytes(bytes.try_into().unwrap()).unwrap()
+}
+
+/// A holder's own prover that derives D from the witness's accumulator
+/// rather than as P*delta: Cbar = (V_w - P*d - C*y)*r, D = V*r - C'*y - Cbar.
+/// With V_w the current value this is the honest proof, with delta = d*r.
+fn forge(credential: &Credential, witness: &[u8], witness_value: &[u8], current: &[u8], seed: &[u8]) -> Vec<u8> {
+    let mut rng = seeded_rng(seed);
+    let mut random = || Scalar::random(&mut rng);
+    let pk = BbsPublicKey::parse(&credential.public_key).unwrap();
+    let h = pk.generators();
+    let m: Vec<Scalar> = credential.messages.iter().map(|m| hash_to_scalar(m, H2S_DST)).collect();
+    let (a, e, s) = (g1(&credential.signature[..48]), scalar(&credential.signature[48..80]), scalar(&credential.signature[80..]));
+
+    // BBS+ proof hiding messages 1 and 2
+    let b = G1Projective::generator() + h[0] * s + h[1] * m[0] + h[2] * m[1] + h[3] * m[2];
+    let (r1, r2) = (random(), random());
+    let r3 = r1.invert().unwrap();
+    let a_prime = a * r1;
+    let abar = b * r1 - a_prime * e;
+    let d = b * r1 - h[0] * r2;
+    let (e_tilde, r2_tilde, r3_tilde, s_tilde, y_tilde, nym_tilde) = (random(), random(), random(), random(), random(), random());
+    let t1 = a_prime * -e_tilde + h[0] * r2_tilde;
+    let t2 = d * r3_tilde - h[0] * s_tilde - h[2] * y_tilde - h[3] * nym_tilde;
+    let base = hash_to_g1(SITE, PSEUDONYM_DST);
+    let (tag, ut) = (base * m[2], base * nym_tilde);
+
+    // Non-revocation part, proving the delta of an honest witness
+    let (value, c, witness_d, y) = (g1(current), g1(&witness[..48]), scalar(&witness[48..]), m[1]);
+    let r = random();
+    let c_prime = c * r;
+    let c_bar = (g1(witness_value) - G1Affine::generator() * witness_d - c * y) * r;
+    let big_d = value * r - c_prime * y - c_bar;
+    let (r_tilde, delta_tilde) = (random(), random());
+    let t = value * r_tilde - c_prime * y_tilde;
+    let t_delta = G1Affine::generator() * delta_tilde;
+
+    let mut transcript = vec![PROOF_VERSION];
+    transcript.extend_from_slice(&pk.to_bytes());
+    for p in [a_prime, abar, d, t1, t2] {
+        transcript.extend_from_slice(&p.to_affine().to_compressed());
+    }
+    for x in [3u64, 1, 0] {
+        transcript.extend_from_slice(&x.to_be_bytes());
+    }
+    transcript.extend_from_slice(&m[0].to_bytes());
+    transcript.extend_from_slice(&0u64.to_be_bytes());
+    for p in [tag, base, ut, value.into(), c_prime, c_bar, big_d, t, t_delta] {
+        transcript.extend_from_slice(&p.to_affine().to_compressed());
+    }
+    transcript.extend_from_slice(&(NONCE.len() as u64).to_be_bytes());
+    transcript.extend_from_slice(NONCE);
+    transcript.extend_from_slice(&0u64.to_le_bytes());
+    let ch = hash_to_scalar(&transcript, H2S_DST);
+
+    let mut proof = vec![PROOF_VERSION];
+    for p in [a_prime, abar, d] {
+        proof.extend_from_slice(&p.to_affine().to_compressed());
+    }
+    let s_prime = s - r2 * r3;
+    for x in [e_tilde + ch * e, r2_tilde + ch * r2, r3_tilde + ch * r3, s_tilde + ch * s_prime, ch] {
+        proof.extend_from_slice(&x.to_bytes());
+    }
+    proof.extend_from_slice(&tag.to_affine().to_compressed());
+    proof.push(0x02);
+    proof.extend_from_slice(&2u32.to_le_bytes());
+    for x in [y_tilde + ch * y, nym_tilde + ch * m[2]] {
+        proof.extend_from_slice(&x.to_bytes());
+    }
+    for p in [c_prime, c_bar, big_d] {
+        proof.extend_from_slice(&p.to_affine().to_compressed());
+    }
+    for x in [r_tilde + ch * r, delta_tilde + ch * witness_d * r] {
+        proof.extend_from_slice(&x.to_bytes());
+    }
+    proof
+}
+
+#[test]
+fn revoked_holder_cannot_forge_a_proof_from_an_old_witness() {
+    let registry = Registry::open("forged");
+    let credential = credential(b"alice-id");
+    let (witness, _) = registry.issue_witness(b"alice-id").unwrap();
+    let old_value = registry.value();
+    // The same prover on a current witness is accepted
+    assert!(credential.verify(forge(&credential, &witness, &old_value, &old_value, b"honest"), &registry));
+
+    registry.revoke(b"alice-id");
+    let forged = forge(&credential, &witness, &old_value, &registry.value(), b"forged");
+    // C' and Cbar satisfy e(C', Q) == e(Cbar, G2), and Cbar + D = V*r - C'*y
+    // holds by construction, so only the proof of knowledge of delta fails
+    let q = G2Affine::from_compressed(registry.public_key.as_slice().try_into().unwrap()).unwrap();
+    let (c_prime, c_bar) = (g1(&forged[C_PRIME_OFFSET..][..48]), g1(&forged[C_PRIME_OFFSET + 48..][..48]));
+    assert_eq!(pairing(&c_prime, &q), pairing(&c_bar, &G2Affine::generator()));
+    assert!(!credential.verify(forged, &registry));
+}
@@ -0,0 +1,133 @@
+// bls12_381 and blst backends must agree bit for bit
+// `cargo test --features blst --test backend_parity`
+
+#![cfg(feature = "blst")]
+
+use ff::Field;
+use group::{Curve, Group};
+use multipass::backend::{Bls12381Backend, BlstBackend, CurveBackend};
+use multipass::{G1Affine, G1Projective, G2Affine, G2Projective, Scalar};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+const SIZES: [usize; 7] = [0, 1, 2, 3, 10, 64, 130];
+
+fn random_points(rng: &mut StdRng, n: usize) -> Vec<G1Affine> {
+    let points: Vec<G1Projective> = (0..n).map(|_| G1Projective::random(&mut *rng)).collect();
+    let mut affine = vec![G1Affine::identity(); n];
+    G1Projective::batch_normalize(&points, &mut affine);
+    affine
+}
+
+fn random_scalars(rng: &mut StdRng, n: usize) -> Vec<Scalar> {
+    (0..n).map(|_| Scalar::random(&mut *rng)).collect()
+}
+
+#[test]
+fn hash_to_g1_matches() {
+    let oversize_dst = vec![b'D'; 300];
+    let dsts: [&[u8]; 3] = [
+        b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_",
+        b"MULTIPASS_H2G_",
+        &oversize_dst,
+    ];
+    for dst in dsts {
+        for msg in [&b""[..], b"abc", &[0xa5; 1000]] {
+            let zkcrypto = Bls12381Backend::hash_to_g1(msg, dst).to_affine();
+            let blst = BlstBackend::hash_to_g1(msg, dst).to_affine();
+            assert_eq!(zkcrypto.to_compressed(), blst.to_compressed());
+        }
+    }
+}
+
+#[test]
+fn linear_combinations_match() {
+    let mut rng = StdRng::seed_from_u64(12);
+    for n in SIZES {
+        let points = random_points(&mut rng, n);
+        let mut scalars = random_scalars(&mut rng, n);
+        if n > 2 {
+            // Edge scalars: 0, 1 and -1
+            scalars[0] = Scalar::ZERO;
+            scalars[1] = Scalar::ONE;
+            scalars[2] = -Scalar::ONE;
+        }
+        let expected = Bls12381Backend::lincomb(&points, &scalars).to_affine();
+        for result in [
+            Bls12381Backend::msm_vartime(&points, &scalars),
+            BlstBackend::lincomb(&points, &scalars),
+            BlstBackend::msm_vartime(&points, &scalars),
+        ] {
+            assert_eq!(result.to_affine(), expected, "n = {n}");
+        }
+    }
+}
+
+#[test]
+fn identity_points_match() {
+    let mut rng = StdRng::seed_from_u64(13);
+    let mut points = random_points(&mut rng, 6);
+    points[2] = G1Affine::identity();
+    let scalars = random_scalars(&mut rng, 6);
+    let expected = Bls12381Backend::lincomb(&points, &scalars).to_affine();
+    assert_eq!(BlstBackend::lincomb(&points, &scalars).to_affine(), expected);
+    assert_eq!(BlstBackend::msm_vartime(&points, &scalars).to_affine(), expected);
+    assert_eq!(BlstBackend::lincomb(&points[2..3], &scalars[..1]).to_affine(), G1Affine::identity());
+}
+
+#[test]
+fn pairing_products_match() {
+    let mut rng = StdRng::seed_from_u64(14);
+    let k = Scalar::random(&mut rng);
+    let p = G1Projective::random(&mut rng).to_affine();
+    let q = G2Projective::random(&mut rng).to_affine();
+    let pk = (p * k).to_affine();
+    let neg_p = -p;
+    let qk = (q * k).to_affine();
+    let wrong = (q * (k + Scalar::ONE)).to_affine();
+    let identity = G1Affine::identity();
+
+    // e(P*k, Q) * e(-P, Q*k) == 1, off by one in the exponent != 1
+    let cases: [(&[(&G1Affine, &G2Affine)], bool); 4] = [
+        (&[(&pk, &q), (&neg_p, &qk)], true),
+        (&[(&pk, &q), (&neg_p, &wrong)], false),
+        (&[(&identity, &q), (&p, &G2Affine::identity())], true),
+        (&[], true),
+    ];
+    for (terms, expected) in cases {
+        let zk_prepared: Vec<_> = terms.iter().map(|(_, q)| Bls12381Backend::prepare_g2(q)).collect();
+        let zk_terms: Vec<_> = terms.iter().map(|(p, _)| *p).zip(&zk_prepared).collect();
+        let blst_prepared: Vec<_> = terms.iter().map(|(_, q)| BlstBackend::prepare_g2(q)).collect();
+        let blst_terms: Vec<_> = terms.iter().map(|(p, _)| *p).zip(&blst_prepared).collect();
+        assert_eq!(Bls12381Backend::pairing_product_is_identity(&zk_terms), expected);
+        assert_eq!(BlstBackend::pairing_product_is_identity(&blst_terms), expected);
+    }
+}
+
+#[test]
+fn legacy_credentials_round_trip() {
+    let sk = multipass::generate_secret_key().unwrap();
+    let pk = multipass::get_public_key(sk.clone(), 3).unwrap();
+    let messages = vec![b"alice".to_vec(), multipass::predicate::encode_integer_message(30), multipass::generate_nym_secret().unwrap()];
+    let signature = multipass::sign(sk, pk.clone(), messages.clone()).unwrap();
+    assert!(multipass::verify_signature_safe(pk.clone(), signature.clone(), messages.clone()).unwrap());
+
+    let proof = multipass::create_proof(
+        pk.clone(),
+        signature,
+        messages.clone(),
+        vec![0],
+        Some(b"nonce".to_vec()),
+        b"site".to_vec(),
+        None,
+        0,
+        None,
+    )
+    .unwrap();
+    let verify = |revealed: &[u8]| {
+        multipass::verify_proof_safe(pk.clone(), proof.clone(), 3, vec![0], vec![revealed.to_vec()], b"nonce".to_vec(), b"site".to_vec(), None, 0, None)
+            .unwrap()
+    };
+    assert!(verify(&messages[0]));
+    assert!(!verify(&messages[2]));
+}
@@ -0,0 +1,147 @@
+// Batch verification of signatures and proofs, with bisection of failures
+// `cargo test --test batch`
+
+mod common;
+
+use common::*;
+use multipass::batch::{batch_verify_proofs_with_rng, batch_verify_signatures_with_rng};
+use multipass::deterministic::{create_proof_seeded, seeded_rng};
+use multipass::*;
+
+fn issuer(name: &str) -> Issuer {
+    Issuer::new(name, 3)
+}
+
+impl Issuer {
+    fn sign(&self, holder: u32) -> SignatureBatchItem {
+        let Credential { public_key, signature, messages } =
+            self.issue(&holder.to_be_bytes(), vec![format!("holder-{}", holder).into_bytes(), b"NL".to_vec()]);
+        SignatureBatchItem { public_key, signature, messages }
+    }
+
+    fn present(&self, holder: u32) -> ProofBatchItem {
+        Self::present_signature(&self.sign(holder))
+    }
+
+    /// Proof over `signed`, whether or not its signature is valid
+    fn present_signature(signed: &SignatureBatchItem) -> ProofBatchItem {
+        let proof = create_proof_seeded(
+            signed.signature.clone(),
+            signed.public_key.clone(),
+            signed.signature.clone(),
+            signed.messages.clone(),
+            vec![1],
+            Some(NONCE.to_vec()),
+            SITE.to_vec(),
+            None,
+            0,
+            None,
+        )
+        .unwrap();
+        ProofBatchItem {
+            public_key: signed.public_key.clone(),
+            proof,
+            total_message_count: 3,
+            revealed_indices: vec![1],
+            revealed_messages: vec![signed.messages[1].clone()],
+            nonce: NONCE.to_vec(),
+            site_id: SITE.to_vec(),
+            epoch: None,
+            alias_index: 0,
+            freshness_claim: None,
+        }
+    }
+}
+
+fn verify_signatures(items: Vec<SignatureBatchItem>) -> BatchVerification {
+    batch_verify_signatures_with_rng(items, &mut seeded_rng(b"batch")).unwrap()
+}
+
+fn verify_proofs(items: Vec<ProofBatchItem>) -> BatchVerification {
+    batch_verify_proofs_with_rng(items, &mut seeded_rng(b"batch")).unwrap()
+}
+
+/// A well-formed signature whose pairing equation does not hold
+fn forged(mut item: SignatureBatchItem) -> SignatureBatchItem {
+    item.signature[80..112].copy_from_slice(&[7; 32]);
+    item
+}
+
+#[test]
+fn valid_batches_pass() {
+    let issuer = issuer("issuer");
+    let result = verify_signatures((0..8).map(|h| issuer.sign(h)).collect());
+    assert!(result.all_valid);
+    assert!(result.invalid_indices.is_empty());
+
+    let result = verify_proofs((0..4).map(|h| issuer.present(h)).collect());
+    assert!(result.all_valid);
+    assert!(result.invalid_indices.is_empty());
+}
+
+#[test]
+fn empty_batch_is_valid() {
+    let result = verify_signatures(Vec::new());
+    assert!(result.all_valid);
+    assert!(result.invalid_indices.is_empty());
+    assert!(verify_proofs(Vec::new()).all_valid);
+}
+
+#[test]
+fn single_bad_item_is_located() {
+    let issuer = issuer("issuer");
+    for bad in [0, 5, 7] {
+        let mut items: Vec<_> = (0..8).map(|h| issuer.sign(h)).collect();
+        items[bad] = forged(items[bad].clone());
+        let SignatureBatchItem { public_key, signature, messages } = items[bad].clone();
+        assert!(!verify_signature_safe(public_key, signature, messages).unwrap());
+        let result = verify_signatures(items);
+        assert!(!result.all_valid);
+        assert_eq!(result.invalid_indices, vec![bad as u32]);
+    }
+
+    // Passes the challenge check, fails only in the shared pairing check
+    let mut items: Vec<_> = (0..4).map(|h| issuer.present(h)).collect();
+    items[2] = Issuer::present_signature(&forged(issuer.sign(2)));
+    let result = verify_proofs(items);
+    assert_eq!(result.invalid_indices, vec![2]);
+}
+
+#[test]
+fn several_bad_and_malformed_items_are_all_reported() {
+    let issuer = issuer("issuer");
+    let mut items: Vec<_> = (0..8).map(|h| issuer.sign(h)).collect();
+    items[1] = forged(items[1].clone());
+    items[4].messages[1] = b"DE".to_vec();
+    items[6].signature.truncate(100);
+    assert_eq!(verify_signatures(items).invalid_indices, vec![1, 4, 6]);
+
+    let mut items: Vec<_> = (0..4).map(|h| issuer.present(h)).collect();
+    items[3].nonce = b"other nonce".to_vec();
+    assert_eq!(verify_proofs(items).invalid_indices, vec![3]);
+}
+
+#[test]
+fn batch_mixing_issuer_keys() {
+    let (first, second, third) = (issuer("first"), issuer("second"), issuer("third"));
+    let signatures: Vec<_> = (0..9)
+        .map(|h| match h % 3 {
+            0 => first.sign(h),
+            1 => second.sign(h),
+            _ => third.sign(h),
+        })
+        .collect();
+    assert!(verify_signatures(signatures.clone()).all_valid);
+
+    let proofs = vec![first.present(0), second.present(1), first.present(2), third.present(3)];
+    assert!(verify_proofs(proofs.clone()).all_valid);
+
+    // A signature presented under another issuer's key
+    let mut wrong_key = signatures;
+    wrong_key[4].public_key = first.public_key.clone();
+    assert_eq!(verify_signatures(wrong_key).invalid_indices, vec![4]);
+
+    let mut wrong_key = proofs;
+    wrong_key[1].public_key = third.public_key.clone();
+    assert_eq!(verify_proofs(wrong_key).invalid_indices, vec![1]);
+}
@@ -0,0 +1,95 @@
+// BbsSignature::prove / BbsPublicKey::verify_proof_of and the PROOF_VERSION 0x05 wire format
+// `cargo test --test bbs_proof`
+
+mod common;
+
+use common::*;
+use multipass::bbs::PROOF_VERSION;
+use multipass::deterministic::seeded_rng;
+use multipass::*;
+
+const REVEALED: [u32; 2] = [0, 2];
+
+fn credential() -> Credential {
+    let attributes = vec![b"alice".to_vec(), b"1990-01-01".to_vec(), b"NL".to_vec()];
+    Issuer::new("bbs-proof", 4).issue(b"alice", attributes)
+}
+
+impl Credential {
+    fn prove(&self) -> BbsProof {
+        let (public_key, signature) = self.typed();
+        signature
+            .prove_with_rng(&public_key, &self.messages, &REVEALED, Some(NONCE), SITE, None, 0, None, &mut seeded_rng(b"prove"))
+            .unwrap()
+    }
+
+    fn check(&self, proof: &BbsProof, revealed: &[Vec<u8>], nonce: &[u8]) -> Result<(), VerifyError> {
+        let (public_key, _) = self.typed();
+        public_key.check_proof_with_predicates(proof, 4, &REVEALED, revealed, &[], nonce, SITE, None, 0, None)
+    }
+
+    fn verify(&self, proof: &BbsProof, revealed: &[Vec<u8>], nonce: &[u8]) -> Result<bool, VerifyError> {
+        let (public_key, _) = self.typed();
+        public_key.verify_proof_of(proof, 4, &REVEALED, revealed, nonce, SITE, None, 0, None)
+    }
+}
+
+#[test]
+fn proof_round_trips_through_bytes_and_verifies() {
+    let c = credential();
+    let proof = c.prove();
+    let bytes = proof.to_bytes();
+    assert_eq!(bytes[0], PROOF_VERSION);
+    let parsed = BbsProof::parse(&bytes).unwrap();
+    assert_eq!(parsed.to_bytes(), bytes);
+    assert!(c.verify(&parsed, &c.revealed(&REVEALED), NONCE).unwrap());
+    assert_eq!(c.check(&parsed, &c.revealed(&REVEALED), NONCE), Ok(()));
+}
+
+#[test]
+fn flipped_challenge_byte_is_rejected() {
+    let c = credential();
+    let mut bytes = c.prove().to_bytes();
+    bytes[CHALLENGE_OFFSET] ^= 0x01;
+    let proof = BbsProof::parse(&bytes).unwrap();
+    assert_eq!(c.check(&proof, &c.revealed(&REVEALED), NONCE), Err(VerifyError::ChallengeMismatch));
+    assert!(!c.verify(&proof, &c.revealed(&REVEALED), NONCE).unwrap());
+}
+
+#[test]
+fn wrong_revealed_message_is_rejected() {
+    let c = credential();
+    let proof = c.prove();
+    let revealed = vec![b"mallory".to_vec(), c.messages[2].clone()];
+    assert_eq!(c.check(&proof, &revealed, NONCE), Err(VerifyError::ChallengeMismatch));
+    assert!(!c.verify(&proof, &revealed, NONCE).unwrap());
+}
+
+#[test]
+fn wrong_nonce_is_rejected() {
+    let c = credential();
+    let proof = c.prove();
+    assert_eq!(c.check(&proof, &c.revealed(&REVEALED), b"replayed nonce"), Err(VerifyError::ChallengeMismatch));
+    assert!(!c.verify(&proof, &c.revealed(&REVEALED), b"replayed nonce").unwrap());
+}
+
+#[test]
+fn previous_proof_version_is_rejected() {
+    let mut bytes = credential().prove().to_bytes();
+    for version in [PROOF_VERSION - 1, 0x03, 0x00] {
+        bytes[0] = version;
+        assert_eq!(BbsProof::parse(&bytes).err(), Some(VerifyError::UnsupportedProofVersion));
+    }
+}
+
+#[test]
+fn truncated_proof_is_rejected() {
+    let bytes = credential().prove().to_bytes();
+    assert_eq!(BbsProof::parse(&[]).err().map(|e| e.code()), Some("proof_structure"));
+    for len in [1, 100, bytes.len() - 32, bytes.len() - 1] {
+        match BbsProof::parse(&bytes[..len]) {
+            Err(VerifyError::ProofStructure { .. }) => {}
+            other => panic!("{} bytes: expected ProofStructure, got {:?}", len, other.err()),
+        }
+    }
+}
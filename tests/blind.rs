@@ -0,0 +1,116 @@
+// Blind issuance: blind_commitment -> blind_sign -> unblind_signature
+// `cargo test --test blind`
+
+use multipass::deterministic::{generate_secret_key_seeded, seeded_rng};
+use multipass::*;
+
+const NONCE: &[u8] = b"issuer nonce";
+
+struct Issuer {
+    secret_key: Vec<u8>,
+    public_key: Vec<u8>,
+}
+
+fn issuer(seed: &[u8]) -> Issuer {
+    let secret_key = generate_secret_key_seeded(seed.to_vec()).unwrap();
+    let public_key = get_public_key(secret_key.clone(), 3).unwrap();
+    Issuer { secret_key, public_key }
+}
+
+fn link_secret() -> Vec<IndexedMessage> {
+    vec![IndexedMessage { index: 2, message: b"link secret".to_vec() }]
+}
+
+fn known() -> Vec<IndexedMessage> {
+    vec![
+        IndexedMessage { index: 0, message: b"alice".to_vec() },
+        IndexedMessage { index: 1, message: b"NL".to_vec() },
+    ]
+}
+
+impl Issuer {
+    fn commit(&self, hidden: Vec<IndexedMessage>) -> Result<BlindCommitment, VerifyError> {
+        blind_commitment_with_rng(self.public_key.clone(), hidden, NONCE.to_vec(), &mut seeded_rng(b"commit"))
+    }
+
+    fn sign(&self, commitment: Vec<u8>, known: Vec<IndexedMessage>) -> Result<Vec<u8>, VerifyError> {
+        blind_sign_with_rng(
+            self.secret_key.clone(),
+            self.public_key.clone(),
+            commitment,
+            NONCE.to_vec(),
+            known,
+            &mut seeded_rng(b"blind-sign"),
+        )
+    }
+}
+
+#[test]
+fn commit_sign_unblind_verify() {
+    let issuer = issuer(b"issuer");
+    let commitment = issuer.commit(link_secret()).unwrap();
+    let blind_signature = issuer.sign(commitment.commitment, known()).unwrap();
+
+    let messages = vec![b"alice".to_vec(), b"NL".to_vec(), b"link secret".to_vec()];
+    let signature = unblind_signature(
+        issuer.public_key.clone(),
+        blind_signature.clone(),
+        commitment.blinding_factor,
+        messages.clone(),
+    )
+    .unwrap();
+    assert_ne!(signature, blind_signature);
+    assert!(verify_signature_safe(issuer.public_key.clone(), signature, messages.clone()).unwrap());
+    assert!(!verify_signature_safe(issuer.public_key, blind_signature, messages).unwrap());
+}
+
+#[test]
+fn tampered_commitment_is_rejected() {
+    let issuer = issuer(b"issuer");
+    let commitment = issuer.commit(link_secret()).unwrap().commitment;
+
+    // Last byte of the only m^ response
+    let mut tampered = commitment.clone();
+    *tampered.last_mut().unwrap() ^= 0x01;
+    assert!(matches!(issuer.sign(tampered, known()), Err(VerifyError::ChallengeMismatch)));
+
+    // Committed index rewritten from 2 to 1
+    let mut tampered = commitment.clone();
+    tampered[116] = 1;
+    assert!(matches!(issuer.sign(tampered, known()), Err(VerifyError::ChallengeMismatch)));
+
+    let mut truncated = commitment.clone();
+    truncated.pop();
+    assert!(matches!(issuer.sign(truncated, known()), Err(VerifyError::ProofStructure { ref field }) if field == "length"));
+}
+
+#[test]
+fn commitment_is_bound_to_the_issuer_key() {
+    let commitment = issuer(b"issuer").commit(link_secret()).unwrap().commitment;
+    let other = issuer(b"other issuer");
+    assert!(matches!(other.sign(commitment, known()), Err(VerifyError::ChallengeMismatch)));
+}
+
+#[test]
+fn out_of_range_and_duplicate_indexes_are_rejected() {
+    let issuer = issuer(b"issuer");
+    let out_of_range = vec![IndexedMessage { index: 3, message: b"link secret".to_vec() }];
+    assert!(matches!(
+        issuer.commit(out_of_range),
+        Err(VerifyError::RevealedIndexOutOfRange { index: 3, message_count: 3 })
+    ));
+
+    let commitment = issuer.commit(link_secret()).unwrap().commitment;
+    let overlapping = vec![
+        IndexedMessage { index: 0, message: b"alice".to_vec() },
+        IndexedMessage { index: 2, message: b"NL".to_vec() },
+    ];
+    assert!(matches!(
+        issuer.sign(commitment.clone(), overlapping),
+        Err(VerifyError::ProofStructure { ref field }) if field == "indexes"
+    ));
+
+    // Index 1 left unsigned
+    let gap = vec![IndexedMessage { index: 0, message: b"alice".to_vec() }];
+    assert!(matches!(issuer.sign(commitment, gap), Err(VerifyError::ProofStructure { ref field }) if field == "indexes"));
+}
@@ -0,0 +1,86 @@
+// Fixtures shared by the integration tests: a seeded issuer, the credentials
+// it signs, a fixed clock and a MinerEngine on a throwaway sled directory
+#![allow(dead_code)]
+
+use multipass::deterministic::{generate_secret_key_seeded, seeded_rng};
+use multipass::miner::MinerEngine;
+use multipass::*;
+use std::path::PathBuf;
+
+pub const NONCE: &[u8] = b"verifier nonce";
+pub const SITE: &[u8] = b"rp.example";
+
+/// Offset of the Fiat-Shamir challenge in the proof encoding
+pub const CHALLENGE_OFFSET: usize = 1 + 48 * 3 + 32 * 4;
+/// Offset of the pseudonym in the proof encoding
+pub const TAG_OFFSET: usize = 1 + 48 * 3 + 32 * 5;
+
+pub struct FixedClock(pub u64);
+
+impl Clock for FixedClock {
+    fn now(&self) -> u64 {
+        self.0
+    }
+}
+
+pub struct Issuer {
+    pub secret_key: Vec<u8>,
+    pub public_key: Vec<u8>,
+}
+
+impl Issuer {
+    /// Key over `message_count` messages, derived from `name`
+    pub fn new(name: &str, message_count: u32) -> Self {
+        let secret_key = generate_secret_key_seeded(name.as_bytes().to_vec()).unwrap();
+        let public_key = get_public_key(secret_key.clone(), message_count).unwrap();
+        Self { secret_key, public_key }
+    }
+
+    /// Signs `attributes` || nym secret, drawing the nym secret and the
+    /// signature randomness from `holder`
+    pub fn issue(&self, holder: &[u8], attributes: Vec<Vec<u8>>) -> Credential {
+        let mut rng = seeded_rng(holder);
+        let mut messages = attributes;
+        messages.push(generate_nym_secret_with_rng(&mut rng).unwrap());
+        let signature =
+            sign_with_rng(self.secret_key.clone(), self.public_key.clone(), messages.clone(), &mut rng).unwrap();
+        Credential { public_key: self.public_key.clone(), signature, messages }
+    }
+}
+
+pub struct Credential {
+    pub public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub messages: Vec<Vec<u8>>,
+}
+
+impl Credential {
+    /// The messages at `indices`, in order
+    pub fn revealed(&self, indices: &[u32]) -> Vec<Vec<u8>> {
+        indices.iter().map(|&i| self.messages[i as usize].clone()).collect()
+    }
+
+    pub fn typed(&self) -> (BbsPublicKey, BbsSignature) {
+        (BbsPublicKey::parse(&self.public_key).unwrap(), BbsSignature::parse(&self.signature).unwrap())
+    }
+}
+
+/// A MinerEngine on a fresh sled directory, removed on drop
+pub struct Store {
+    pub engine: MinerEngine,
+    path: PathBuf,
+}
+
+impl Store {
+    pub fn open(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("multipass-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        Self { engine: MinerEngine::new(path.to_str().unwrap(), 1.0).unwrap(), path }
+    }
+}
+
+impl Drop for Store {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
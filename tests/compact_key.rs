@@ -0,0 +1,42 @@
+// compact_public_key / expand_public_key
+
+use multipass::bbs::MAX_MESSAGE_COUNT;
+use multipass::deterministic::seeded_rng;
+use multipass::*;
+
+fn compact(w: &[u8], message_count: u32, seed: &[u8]) -> Vec<u8> {
+    [w, &message_count.to_be_bytes(), seed].concat()
+}
+
+#[test]
+fn compact_key_expands_to_the_full_key() {
+    let sk = generate_secret_key_with_rng(&mut seeded_rng(b"compact")).unwrap();
+    let pk = get_public_key_with_seed(sk, b"seed".to_vec(), 3).unwrap();
+    let compact = compact_public_key(pk.clone(), b"seed".to_vec()).unwrap();
+    assert_eq!(compact.len(), 100 + 4);
+    assert_eq!(expand_public_key(compact).unwrap(), pk);
+}
+
+#[test]
+fn message_count_above_the_parser_maximum_is_rejected() {
+    let sk = generate_secret_key_with_rng(&mut seeded_rng(b"compact")).unwrap();
+    let pk = get_public_key_with_seed(sk, b"seed".to_vec(), 1).unwrap();
+    let err = expand_public_key(compact(&pk[..96], MAX_MESSAGE_COUNT + 1, b"seed")).unwrap_err();
+    assert_eq!(err.code(), "key_parse");
+    let err = expand_public_key(compact(&pk[..96], u32::MAX, b"seed")).unwrap_err();
+    assert_eq!(err.code(), "key_parse");
+}
+
+#[test]
+fn identity_w_is_rejected() {
+    let identity = G2Affine::identity().to_compressed();
+    match expand_public_key(compact(&identity, 2, b"seed")) {
+        Err(VerifyError::KeyParse { field, .. }) => assert_eq!(field, "w"),
+        other => panic!("expected KeyParse on w, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn truncated_compact_key_is_rejected() {
+    assert_eq!(expand_public_key(vec![0u8; 99]).unwrap_err().code(), "key_parse");
+}
@@ -0,0 +1,184 @@
+// Seeded mode must be reproducible and produce valid outputs
+// `cargo test --test deterministic`
+
+use multipass::deterministic::{
+    create_proof_seeded, generate_nym_secret_seeded, generate_secret_key_seeded, known_answer_vectors, seeded_rng,
+    sign_seeded,
+};
+use multipass::presentation::create_presentation_with_rng;
+use multipass::schema::{create_proof_with_schema_with_rng, sign_with_schema_with_rng};
+use multipass::status_list::sign_status_list_with_rng;
+use multipass::{
+    get_public_key, verify_proof_safe, verify_signature_safe, AttributeDefinition, AttributeType, AttributeValue,
+    CredentialSchema,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// `known_answer_vectors(b"multipass-kat")`, pinned so the bindings and
+/// future releases can be checked against the same bytes
+const KAT_FIXTURE: &str = include_str!("fixtures/known_answer_vectors.json");
+
+fn messages() -> Vec<Vec<u8>> {
+    vec![b"alice".to_vec(), b"1990-01-01".to_vec(), b"US".to_vec()]
+}
+
+#[test]
+fn same_seed_same_outputs() {
+    let sk = generate_secret_key_seeded(b"seed".to_vec()).unwrap();
+    assert_eq!(sk, generate_secret_key_seeded(b"seed".to_vec()).unwrap());
+    assert_ne!(sk, generate_secret_key_seeded(b"other".to_vec()).unwrap());
+
+    let pk = get_public_key(sk.clone(), 3).unwrap();
+    let sig = sign_seeded(b"sign".to_vec(), sk.clone(), pk.clone(), messages()).unwrap();
+    assert_eq!(sig, sign_seeded(b"sign".to_vec(), sk, pk, messages()).unwrap());
+}
+
+#[test]
+fn seeded_outputs_verify() {
+    let sk = generate_secret_key_seeded(b"seed".to_vec()).unwrap();
+    let pk = get_public_key(sk.clone(), 3).unwrap();
+    let sig = sign_seeded(b"sign".to_vec(), sk, pk.clone(), messages()).unwrap();
+    assert!(verify_signature_safe(pk.clone(), sig.clone(), messages()).unwrap());
+
+    let prove = || {
+        create_proof_seeded(
+            b"prove".to_vec(),
+            pk.clone(),
+            sig.clone(),
+            messages(),
+            vec![1],
+            Some(b"nonce".to_vec()),
+            b"site".to_vec(),
+            None,
+            0,
+            None,
+        )
+        .unwrap()
+    };
+    let proof = prove();
+    assert_eq!(proof, prove());
+    assert!(verify_proof_safe(
+        pk,
+        proof,
+        3,
+        vec![1],
+        vec![messages()[1].clone()],
+        b"nonce".to_vec(),
+        b"site".to_vec(),
+        None,
+        0,
+        None,
+    )
+    .unwrap());
+}
+
+#[test]
+fn known_answer_vectors_match_fixture() {
+    let json = known_answer_vectors(b"multipass-kat".to_vec()).unwrap();
+    let expected: Value = serde_json::from_str(KAT_FIXTURE).unwrap();
+    assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), expected);
+}
+
+#[test]
+fn seeded_schema_presentation_and_status_list_are_reproducible() {
+    let sk = generate_secret_key_seeded(b"seed".to_vec()).unwrap();
+    let nym_secret = generate_nym_secret_seeded(b"nym".to_vec()).unwrap();
+
+    let schema = CredentialSchema {
+        id: "https://example.com/member".to_string(),
+        attributes: vec![AttributeDefinition { name: "name".to_string(), attribute_type: AttributeType::String }],
+    };
+    let attributes = HashMap::from([("name".to_string(), AttributeValue::String { value: "alice".to_string() })]);
+    let pk = get_public_key(sk.clone(), schema.message_count() as u32).unwrap();
+    let sign = || {
+        sign_with_schema_with_rng(
+            sk.clone(), pk.clone(), schema.clone(), attributes.clone(), nym_secret.clone(), &mut seeded_rng(b"sign"),
+        )
+        .unwrap()
+    };
+    let signature = sign();
+    assert_eq!(signature, sign());
+    let prove = || {
+        create_proof_with_schema_with_rng(
+            pk.clone(),
+            signature.clone(),
+            schema.clone(),
+            attributes.clone(),
+            nym_secret.clone(),
+            vec!["name".to_string()],
+            Some(b"nonce".to_vec()),
+            b"site".to_vec(),
+            None,
+            0,
+            None,
+            &mut seeded_rng(b"prove"),
+        )
+        .unwrap()
+    };
+    assert_eq!(prove(), prove());
+
+    let messages = vec![b"alice".to_vec(), nym_secret.clone()];
+    let pk2 = get_public_key(sk.clone(), 2).unwrap();
+    let signature = sign_seeded(b"sign".to_vec(), sk.clone(), pk2.clone(), messages.clone()).unwrap();
+    let present = || {
+        create_presentation_with_rng(
+            pk2.clone(),
+            signature.clone(),
+            messages.clone(),
+            vec![0],
+            b"nonce".to_vec(),
+            b"site".to_vec(),
+            None,
+            0,
+            None,
+            &mut seeded_rng(b"present"),
+        )
+        .unwrap()
+    };
+    assert_eq!(present(), present());
+
+    let pk4 = get_public_key(sk.clone(), 4).unwrap();
+    let sign_list = || {
+        sign_status_list_with_rng(
+            sk.clone(),
+            pk4.clone(),
+            "https://example.com/status/1".to_string(),
+            "revocation".to_string(),
+            vec![0u8; 16 * 1024],
+            1_700_000_000,
+            &mut seeded_rng(b"status"),
+        )
+        .unwrap()
+    };
+    assert_eq!(sign_list(), sign_list());
+}
+
+#[test]
+fn known_answer_vectors_are_stable_and_valid() {
+    let json = known_answer_vectors(b"multipass-kat".to_vec()).unwrap();
+    assert_eq!(json, known_answer_vectors(b"multipass-kat".to_vec()).unwrap());
+    assert_ne!(json, known_answer_vectors(b"multipass-kat-2".to_vec()).unwrap());
+
+    let v: Value = serde_json::from_str(&json).unwrap();
+    let bytes = |key: &str| hex::decode(v[key].as_str().unwrap()).unwrap();
+    let messages: Vec<Vec<u8>> =
+        v["messages"].as_array().unwrap().iter().map(|m| hex::decode(m.as_str().unwrap()).unwrap()).collect();
+    let revealed: Vec<u32> = v["revealed_indices"].as_array().unwrap().iter().map(|i| i.as_u64().unwrap() as u32).collect();
+
+    assert_eq!(get_public_key(bytes("secret_key"), messages.len() as u32).unwrap(), bytes("public_key"));
+    assert!(verify_signature_safe(bytes("public_key"), bytes("signature"), messages.clone()).unwrap());
+    assert!(verify_proof_safe(
+        bytes("public_key"),
+        bytes("proof"),
+        messages.len() as u64,
+        revealed.clone(),
+        revealed.iter().map(|&i| messages[i as usize].clone()).collect(),
+        bytes("nonce"),
+        bytes("site_id"),
+        None,
+        0,
+        None,
+    )
+    .unwrap());
+}
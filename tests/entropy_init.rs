@@ -0,0 +1,20 @@
+// Implicit selection of the process-wide entropy source. Its own test binary,
+// since the choice lasts for the whole process.
+// `cargo test --test entropy_init`
+
+use multipass::periwinkle::{default_source, entropy_health, get_entropy, init_default_entropy, init_entropy, EntropyError, TestSource};
+
+#[test]
+fn first_use_selects_the_default_source_for_good() {
+    // No init_entropy: the first draw picks default_source
+    let first = get_entropy().unwrap();
+    assert_ne!(first, get_entropy().unwrap());
+    let health = entropy_health().unwrap();
+    assert_eq!(health.source_name, default_source().name());
+    assert!(health.healthy);
+
+    // The implicit choice cannot be replaced afterwards
+    assert_eq!(init_entropy(Box::new(TestSource::new([1; 32]))), Err(EntropyError::AlreadyInitialized));
+    assert_eq!(init_default_entropy(true), Err(EntropyError::AlreadyInitialized));
+    assert_eq!(entropy_health().unwrap().source_name, default_source().name());
+}
@@ -0,0 +1,199 @@
+// Entropy sources fill completely or fail; nothing falls back silently
+// `cargo test --test entropy_sources`
+
+use multipass::health::{HealthTested, HealthTests};
+use multipass::periwinkle::{DeviceSource, EntropyError, EntropySource, MixerSource, OsSource, TestSource};
+use std::time::Duration;
+
+fn fill(source: &mut dyn EntropySource, len: usize) -> Result<Vec<u8>, EntropyError> {
+    let mut buf = vec![0u8; len];
+    source.fill(&mut buf)?;
+    Ok(buf)
+}
+
+#[test]
+fn test_source_is_deterministic() {
+    let a = fill(&mut TestSource::new([7; 32]), 100).unwrap();
+    assert_eq!(a, fill(&mut TestSource::new([7; 32]), 100).unwrap());
+    assert_ne!(a, fill(&mut TestSource::new([8; 32]), 100).unwrap());
+}
+
+#[test]
+fn mixer_depends_on_every_source() {
+    let mix = |a: u8, b: u8| {
+        let mut mixer = MixerSource::new(vec![Box::new(TestSource::new([a; 32])), Box::new(TestSource::new([b; 32]))]);
+        fill(&mut mixer, 200).unwrap()
+    };
+    assert_eq!(mix(1, 2), mix(1, 2));
+    assert_ne!(mix(1, 2), mix(1, 3));
+    assert_ne!(mix(1, 2), mix(3, 2));
+    assert!(fill(&mut MixerSource::new(vec![]), 32).is_err());
+}
+
+#[test]
+fn mixer_fails_with_any_source() {
+    let mut mixer = MixerSource::new(vec![Box::new(OsSource), Box::new(DeviceSource::new("/nonexistent/rng"))]);
+    assert!(matches!(fill(&mut mixer, 32), Err(EntropyError::Unavailable { .. })));
+}
+
+#[test]
+fn device_source_reads_and_reports_eof() {
+    let path = std::env::temp_dir().join(format!("periwinkle-{}", std::process::id()));
+    std::fs::write(&path, [0xab; 40]).unwrap();
+    let mut source = DeviceSource::with_timeout(&path, Duration::from_millis(50));
+    assert_eq!(fill(&mut source, 32).unwrap(), vec![0xab; 32]);
+    assert!(matches!(fill(&mut source, 32), Err(EntropyError::Unavailable { .. })));
+    std::fs::remove_file(path).unwrap();
+
+    #[cfg(unix)]
+    assert_eq!(fill(&mut DeviceSource::new("/dev/urandom"), 64).unwrap().len(), 64);
+}
+
+#[test]
+fn os_source_fills() {
+    let a = fill(&mut OsSource, 64).unwrap();
+    assert_ne!(a, fill(&mut OsSource, 64).unwrap());
+}
+
+/// Noise source that goes bad after `good` bytes
+struct Degrading {
+    inner: TestSource,
+    good: usize,
+    pattern: fn(usize) -> u8,
+}
+
+impl EntropySource for Degrading {
+    fn fill(&mut self, dest: &mut [u8]) -> Result<(), EntropyError> {
+        self.inner.fill(dest)?;
+        for (i, b) in dest.iter_mut().enumerate() {
+            if i >= self.good {
+                *b = (self.pattern)(i);
+            }
+        }
+        self.good = self.good.saturating_sub(dest.len());
+        Ok(())
+    }
+
+    fn name(&self) -> String {
+        "degrading".to_string()
+    }
+}
+
+fn degrading(good: usize, pattern: fn(usize) -> u8) -> HealthTested<Degrading> {
+    HealthTested::new(Degrading { inner: TestSource::new([9; 32]), good, pattern })
+}
+
+#[test]
+fn cutoffs_match_sp800_90b() {
+    // Table 2 of SP 800-90B, W = 512, alpha = 2^-20
+    for (h, rct, apt) in [(1.0, 21, 311), (2.0, 11, 177), (4.0, 6, 62), (8.0, 4, 13)] {
+        let mut tests = HealthTests::new(h);
+        assert_eq!(tests.check(&vec![0x5a; rct - 1]), Ok(()));
+        assert_eq!(tests.check(&[0x5a]), Err("repetition_count"));
+
+        // apt - 1 copies of the window's first byte, in runs short of the RCT cutoff
+        let mut tests = HealthTests::new(h);
+        let mut samples = Vec::new();
+        let mut zeros = 0;
+        while zeros < apt - 1 {
+            let run = (rct - 1).min(apt - 1 - zeros);
+            samples.resize(samples.len() + run, 0);
+            zeros += run;
+            samples.push(samples.len() as u8 | 1);
+        }
+        assert_eq!(tests.check(&samples), Ok(()));
+        assert_eq!(tests.check(&[0]), Err("adaptive_proportion"));
+    }
+}
+
+#[test]
+fn healthy_source_passes_startup() {
+    let mut source = HealthTested::new(TestSource::new([3; 32]));
+    assert!(fill(&mut source, 4096).is_ok());
+    let report = &source.health()[0];
+    assert!(report.healthy && report.startup_passed);
+    assert_eq!(report.samples_tested, 1024 + 4096);
+}
+
+#[test]
+fn stuck_source_fails_startup() {
+    let mut source = degrading(0, |_| 0x42);
+    assert!(matches!(fill(&mut source, 32), Err(EntropyError::HealthTestFailed { test, .. }) if test == "repetition_count"));
+    assert!(!source.health()[0].startup_passed);
+}
+
+#[test]
+fn biased_source_fails_and_stays_failed() {
+    // Every other byte equal: no long runs, but too frequent for APT
+    let mut source = degrading(2048, |i| if i % 2 == 0 { 0x42 } else { i as u8 });
+    assert!(fill(&mut source, 512).is_ok());
+    let err = fill(&mut source, 1024).unwrap_err();
+    assert!(matches!(&err, EntropyError::HealthTestFailed { test, .. } if test == "adaptive_proportion"));
+    assert_eq!(fill(&mut source, 32).unwrap_err(), err);
+    let report = &source.health()[0];
+    assert!(!report.healthy);
+    assert_eq!(report.failed_test.as_deref(), Some("adaptive_proportion"));
+}
+
+/// RNG whose source has failed: `try_fill_bytes` errors, the rest panic
+struct FailedRng;
+
+impl rand::RngCore for FailedRng {
+    fn next_u32(&mut self) -> u32 {
+        panic!("infallible read from a failed source")
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        panic!("infallible read from a failed source")
+    }
+
+    fn fill_bytes(&mut self, _: &mut [u8]) {
+        panic!("infallible read from a failed source")
+    }
+
+    fn try_fill_bytes(&mut self, _: &mut [u8]) -> Result<(), rand::Error> {
+        Err(rand::Error::new(EntropyError::HealthTestFailed {
+            source_name: "failed".to_string(),
+            test: "repetition_count".to_string(),
+        }))
+    }
+}
+
+impl rand::CryptoRng for FailedRng {}
+
+#[test]
+fn failed_source_is_an_error_not_a_panic() {
+    use multipass::deterministic::seeded_rng;
+    use multipass::VerifyError;
+    let unavailable = |e: VerifyError| {
+        assert_eq!(e.code(), "entropy_unavailable");
+        assert!(matches!(e, VerifyError::EntropyUnavailable { reason } if reason.contains("repetition_count")));
+    };
+
+    unavailable(multipass::generate_secret_key_with_rng(&mut FailedRng).unwrap_err());
+    unavailable(multipass::generate_nym_secret_with_rng(&mut FailedRng).unwrap_err());
+    unavailable(multipass::accumulator::generate_accumulator_secret_key_with_rng(&mut FailedRng).unwrap_err());
+
+    let sk = multipass::generate_secret_key_with_rng(&mut seeded_rng(b"working")).unwrap();
+    let pk = multipass::get_public_key(sk.clone(), 2).unwrap();
+    let messages = vec![b"alice".to_vec(), multipass::generate_nym_secret_with_rng(&mut seeded_rng(b"working")).unwrap()];
+    unavailable(multipass::sign_with_rng(sk.clone(), pk.clone(), messages.clone(), &mut FailedRng).unwrap_err());
+
+    let signature = multipass::sign_with_rng(sk, pk.clone(), messages.clone(), &mut seeded_rng(b"working")).unwrap();
+    let proof = multipass::create_proof_with_rng(
+        pk.clone(),
+        signature.clone(),
+        messages.clone(),
+        vec![0],
+        None,
+        b"site".to_vec(),
+        None,
+        0,
+        None,
+        &mut FailedRng,
+    );
+    unavailable(proof.unwrap_err());
+
+    let item = multipass::SignatureBatchItem { public_key: pk, signature, messages };
+    unavailable(multipass::batch::batch_verify_signatures_with_rng(vec![item], &mut FailedRng).unwrap_err());
+}
@@ -0,0 +1,115 @@
+// EpochSchedule::verify_proof against an injected clock
+// `cargo test --test epoch`
+
+mod common;
+
+use common::*;
+use multipass::deterministic::seeded_rng;
+use multipass::epoch::{current_epoch, verify_epoch_proof};
+use multipass::*;
+use std::num::NonZeroU64;
+
+const NOW: u64 = 1_700_000_000;
+
+fn credential() -> Credential {
+    Issuer::new("epoch", 2).issue(b"alice", vec![b"alice".to_vec()])
+}
+
+impl Credential {
+    fn prove(&self, epoch: &[u8]) -> BbsProof {
+        let (public_key, signature) = self.typed();
+        signature
+            .prove_with_rng(&public_key, &self.messages, &[0], Some(NONCE), SITE, Some(epoch), 0, None, &mut seeded_rng(b"prove"))
+            .unwrap()
+    }
+
+    fn verify(&self, schedule: &EpochSchedule, now: u64, proof: &BbsProof, epoch: &[u8]) -> Result<bool, VerifyError> {
+        schedule.verify_proof(
+            &FixedClock(now),
+            &self.typed().0,
+            proof,
+            2,
+            &[0],
+            &self.messages[..1],
+            NONCE,
+            SITE,
+            epoch,
+            0,
+            None,
+        )
+    }
+}
+
+#[test]
+fn current_epoch_verifies() {
+    let cred = credential();
+    let schedule = EpochSchedule::daily();
+    let epoch = schedule.epoch_at(NOW);
+    let proof = cred.prove(&epoch);
+    assert!(cred.verify(&schedule, NOW, &proof, &epoch).unwrap());
+    // Last second of the same day
+    assert!(cred.verify(&schedule, NOW - NOW % 86_400 + 86_399, &proof, &epoch).unwrap());
+}
+
+#[test]
+fn previous_and_future_epochs_are_rejected() {
+    let cred = credential();
+    let schedule = EpochSchedule::daily();
+    let current = schedule.epoch_at(NOW);
+
+    for presented in [schedule.epoch_at(NOW - 86_400), schedule.epoch_at(NOW + 86_400)] {
+        let proof = cred.prove(&presented);
+        match cred.verify(&schedule, NOW, &proof, &presented) {
+            Err(e @ VerifyError::EpochMismatch { .. }) => {
+                assert_eq!(e.code(), "epoch_mismatch");
+                assert_eq!(e, VerifyError::EpochMismatch { expected: current.clone(), presented: presented.clone() });
+            }
+            other => panic!("expected EpochMismatch, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn proof_for_one_epoch_does_not_verify_under_another() {
+    let cred = credential();
+    let schedule = EpochSchedule::daily();
+    let proof = cred.prove(&schedule.epoch_at(NOW - 86_400));
+    assert!(!cred.verify(&schedule, NOW, &proof, &schedule.epoch_at(NOW)).unwrap());
+}
+
+#[test]
+fn schedules_of_different_periods_do_not_share_epochs() {
+    let cred = credential();
+    let daily = EpochSchedule::daily();
+    let hourly = EpochSchedule::new(NonZeroU64::new(3_600).unwrap());
+    assert_ne!(hourly.epoch_at(0), daily.epoch_at(0));
+
+    let epoch = daily.epoch_at(NOW);
+    let proof = cred.prove(&epoch);
+    assert!(matches!(cred.verify(&hourly, NOW, &proof, &epoch), Err(VerifyError::EpochMismatch { .. })));
+}
+
+#[test]
+fn period_zero_is_rejected() {
+    let err = current_epoch(0).err().unwrap();
+    assert_eq!(err, VerifyError::InvalidEpochPeriod);
+    assert_eq!(err.code(), "invalid_epoch_period");
+
+    let cred = credential();
+    let epoch = EpochSchedule::daily().epoch_at(NOW);
+    let proof = cred.prove(&epoch).to_bytes();
+    let result = verify_epoch_proof(
+        cred.public_key.clone(),
+        proof,
+        2,
+        vec![0],
+        vec![cred.messages[0].clone()],
+        NONCE.to_vec(),
+        SITE.to_vec(),
+        epoch,
+        0,
+        0,
+        None,
+    );
+    assert_eq!(result, Err(VerifyError::InvalidEpochPeriod));
+}
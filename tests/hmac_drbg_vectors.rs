@@ -0,0 +1,124 @@
+// NIST CAVS 14.3 HMAC_DRBG (SHA-256) vectors: no reseed, reseed and
+// prediction resistance. Entropy inputs are consumed in order: 32 bytes of
+// entropy and a 16-byte nonce at instantiation, 32 bytes per reseed.
+
+use multipass::drbg::HmacDrbg;
+use multipass::periwinkle::{EntropyError, EntropyHarvester, TestSource, HARVESTER_RESEED_INTERVAL};
+use serde_json::Value;
+
+const FIXTURES: &str = include_str!("fixtures/hmac_drbg_sha256.json");
+
+struct Case {
+    entropy: Vec<u8>,
+    offset: usize,
+    v: Value,
+}
+
+impl Case {
+    fn new(v: &Value) -> Self {
+        Self { entropy: bytes(v, "entropy"), offset: 0, v: v.clone() }
+    }
+
+    fn take(&mut self, n: usize) -> Vec<u8> {
+        self.offset += n;
+        self.entropy[self.offset - n..self.offset].to_vec()
+    }
+
+    fn field(&self, key: &str) -> Vec<u8> {
+        bytes(&self.v, key)
+    }
+
+    fn instantiate(&mut self) -> HmacDrbg {
+        let entropy = self.take(32);
+        let nonce = self.take(16);
+        HmacDrbg::instantiate(&entropy, &nonce, &self.field("personalization")).unwrap()
+    }
+
+    fn finish(&self, output: &[u8]) {
+        assert_eq!(self.offset, self.entropy.len(), "{}", self.v["name"]);
+        assert_eq!(hex::encode(output), self.v["returned_bytes"].as_str().unwrap(), "{}", self.v["name"]);
+    }
+}
+
+fn bytes(v: &Value, key: &str) -> Vec<u8> {
+    hex::decode(v[key].as_str().unwrap()).unwrap()
+}
+
+fn cases(kind: &str) -> Vec<Value> {
+    let fixtures: Value = serde_json::from_str(FIXTURES).unwrap();
+    let cases = fixtures[kind].as_array().unwrap().clone();
+    assert!(!cases.is_empty());
+    cases
+}
+
+#[test]
+fn no_reseed() {
+    for v in cases("no_reseed") {
+        let mut case = Case::new(&v);
+        let mut drbg = case.instantiate();
+        let mut out = vec![0u8; case.field("returned_bytes").len()];
+        drbg.generate(&mut out, &case.field("additional_input_1")).unwrap();
+        drbg.generate(&mut out, &case.field("additional_input_2")).unwrap();
+        case.finish(&out);
+    }
+}
+
+#[test]
+fn reseed() {
+    for v in cases("reseed") {
+        let mut case = Case::new(&v);
+        let mut drbg = case.instantiate();
+        let entropy = case.take(32);
+        drbg.reseed(&entropy, &case.field("additional_input_reseed")).unwrap();
+        let mut out = vec![0u8; case.field("returned_bytes").len()];
+        drbg.generate(&mut out, &case.field("additional_input_1")).unwrap();
+        drbg.generate(&mut out, &case.field("additional_input_2")).unwrap();
+        case.finish(&out);
+        assert_eq!(drbg.reseeds(), 1);
+    }
+}
+
+#[test]
+fn prediction_resistance() {
+    for v in cases("prediction_resistance") {
+        let mut case = Case::new(&v);
+        let mut drbg = case.instantiate();
+        let mut out = vec![0u8; case.field("returned_bytes").len()];
+        for add in ["additional_input_1", "additional_input_2"] {
+            // Reseed with the request's additional input, then generate without it
+            let entropy = case.take(32);
+            drbg.reseed(&entropy, &case.field(add)).unwrap();
+            drbg.generate(&mut out, &[]).unwrap();
+        }
+        case.finish(&out);
+    }
+}
+
+#[test]
+fn reseed_interval_is_enforced() {
+    let mut drbg = HmacDrbg::with_reseed_interval(&[1; 32], &[2; 16], b"", 2).unwrap();
+    let mut out = [0u8; 32];
+    drbg.generate(&mut out, &[]).unwrap();
+    drbg.generate(&mut out, &[]).unwrap();
+    assert_eq!(drbg.generate(&mut out, &[]), Err(EntropyError::ReseedRequired));
+    drbg.reseed(&[3; 32], &[]).unwrap();
+    assert_eq!(drbg.reseed_counter(), 1);
+    drbg.generate(&mut out, &[]).unwrap();
+
+    assert!(HmacDrbg::instantiate(&[1; 16], &[2; 16], b"").is_err());
+    assert!(matches!(drbg.generate(&mut vec![0; 70_000], &[]), Err(EntropyError::RequestTooLarge { .. })));
+}
+
+#[test]
+fn harvester_reseeds_on_schedule_and_on_request() {
+    let mut harvester = EntropyHarvester::new(Box::new(TestSource::new([5; 32]))).unwrap();
+    for _ in 0..HARVESTER_RESEED_INTERVAL {
+        harvester.get_entropy().unwrap();
+    }
+    assert_eq!(harvester.health().drbg_reseeds, 1);
+    harvester.get_entropy_with(true, b"level 4").unwrap();
+    let health = harvester.health();
+    assert_eq!(health.drbg_reseeds, 2);
+    assert_eq!(health.drbg_reseed_counter, 2);
+    assert!(health.healthy);
+}
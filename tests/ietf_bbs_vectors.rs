@@ -0,0 +1,308 @@
+// Fixtures from draft-irtf-cfrg-bbs-signatures-06, Section 8 / Appendix C
+
+use group::Curve;
+use multipass::hash_to_curve::scalar_from_okm;
+use multipass::ietf_bbs::*;
+use multipass::{G1Affine, Scalar};
+use rand::{CryptoRng, RngCore};
+
+const KEY_MATERIAL: &str = "746869732d49532d6a7573742d616e2d546573742d494b4d2d746f2d67656e65726174652d246528724074232d6b6579";
+const KEY_INFO: &str = "746869732d49532d736f6d652d6b65792d6d657461646174612d746f2d62652d757365642d696e2d746573742d6b65792d67656e";
+const HEADER: &str = "11223344556677889900aabbccddeeff";
+const MESSAGE_1: &str = "9872ad089e452c7b6e283dfac2a80d58e8d0ff71cc4d5e310a1debdda4a45f02";
+const PRESENTATION_HEADER: &str = "bed231d880675ed101ead304512e043ade9958dd0241ea70b4b3957fba941501";
+
+const MESSAGES: [&str; 10] = [
+    MESSAGE_1,
+    "c344136d9ab02da4dd5908bbba913ae6f58c2cc844b802a6f811f5fb075f9b80",
+    "7372e9daa5ed31e6cd5c825eac1b855e84476a1d94932aa348e07b73",
+    "77fe97eb97a1ebe2e81e4e3597a3ee740a66e9ef2412472c",
+    "496694774c5604ab1b2544eababcf0f53278ff50",
+    "515ae153e22aae04ad16f759e07237b4",
+    "d183ddc6e2665aa4e2f088af",
+    "ac55fb33a75909ed",
+    "96012096",
+    "",
+];
+
+/// Multi-message signature over all of MESSAGES, BLS12381-SHA-256
+const SHA256_MULTI_MESSAGE_SIGNATURE: &str = "8339b285a4acd89dec7777c09543a43e3cc60684b0a6f8ab335da4825c96e1463e28f8c5f4fd0641d19cec5920d3a8ff4bedb6c9691454597bbd298288abed3632078557b2ace7d44caed846e1a0a1e8";
+
+/// ProofGen over MESSAGE_1 disclosed, with HEADER, PRESENTATION_HEADER and
+/// the mocked random scalars, BLS12381-SHA-256
+const SHA256_SINGLE_MESSAGE_PROOF: &str = "94916292a7a6bade28456c601d3af33fcf39278d6594b467e128a3f83686a104ef2b2fcf72df0215eeaf69262ffe8194a19fab31a82ddbe06908985abc4c9825788b8a1610942d12b7f5debbea8985296361206dbace7af0cc834c80f33e0aadaeea5597befbb651827b5eed5a66f1a959bb46cfd5ca1a817a14475960f69b32c54db7587b5ee3ab665fbd37b506830a49f21d592f5e634f47cee05a025a2f8f94e73a6c15f02301d1178a92873b6e8634bafe4983c3e15a663d64080678dbf29417519b78af042be2b3e1c4d08b8d520ffab008cbaaca5671a15b22c239b38e940cfeaa5e72104576a9ec4a6fad78c532381aeaa6fb56409cef56ee5c140d455feeb04426193c57086c9b6d397d9418";
+
+/// Seed of mocked_calculate_random_scalars, the ASCII of "3.141592653589793238462643383279"
+const MOCKED_SCALARS_SEED: &str = "332e313431353932363533353839373933323338343632363433333833323739";
+
+/// First mocked random scalars for BLS12381-SHA-256
+const SHA256_MOCKED_SCALARS: [&str; 4] = [
+    "04f8e2518993c4383957ad14eb13a023c4ad0c67d01ec86eeb902e732ed6df3f",
+    "5d87c1ba64c320ad601d227a1b74188a41a100325cecf00223729863966392b1",
+    "0444607600ac70482e9c983b4b063214080b9e808300aa4cc02a91b3a92858fe",
+    "548cd11eae4318e88cda10b4cd31ae29d41c3a0b057196ee9cf3a69d471e4e94",
+];
+
+struct Fixture {
+    suite: BbsCiphersuite,
+    key_dst: &'static str,
+    sk: &'static str,
+    pk: &'static str,
+    p1: &'static str,
+    q1: &'static str,
+    h: &'static [&'static str],
+    signature: &'static str,
+}
+
+const FIXTURES: [Fixture; 2] = [
+    Fixture {
+        suite: BbsCiphersuite::Bls12381Sha256,
+        key_dst: "4242535f424c53313233383147315f584d443a5348412d3235365f535357555f524f5f4832475f484d32535f4b455947454e5f4453545f",
+        sk: "60e55110f76883a13d030b2f6bd11883422d5abde717569fc0731f51237169fc",
+        pk: "a820f230f6ae38503b86c70dc50b61c58a77e45c39ab25c0652bbaa8fa136f2851bd4781c9dcde39fc9d1d52c9e60268061e7d7632171d91aa8d460acee0e96f1e7c4cfb12d3ff9ab5d5dc91c277db75c845d649ef3c4f63aebc364cd55ded0c",
+        p1: "a8ce256102840821a3e94ea9025e4662b205762f9776b3a766c872b948f1fd225e7c59698588e70d11406d161b4e28c9",
+        q1: "a9ec65b70a7fbe40c874c9eb041c2cb0a7af36ccec1bea48fa2ba4c2eb67ef7f9ecb17ed27d38d27cdeddff44c8137be",
+        h: &[
+            "98cd5313283aaf5db1b3ba8611fe6070d19e605de4078c38df36019fbaad0bd28dd090fd24ed27f7f4d22d5ff5dea7d4",
+            "a31fbe20c5c135bcaa8d9fc4e4ac665cc6db0226f35e737507e803044093f37697a9d452490a970eea6f9ad6c3dcaa3a",
+            "b479263445f4d2108965a9086f9d1fdc8cde77d14a91c856769521ad3344754cc5ce90d9bc4c696dffbc9ef1d6ad1b62",
+            "ac0401766d2128d4791d922557c7b4d1ae9a9b508ce266575244a8d6f32110d7b0b7557b77604869633bb49afbe20035",
+            "b95d2898370ebc542857746a316ce32fa5151c31f9b57915e308ee9d1de7db69127d919e984ea0747f5223821b596335",
+            "8f19359ae6ee508157492c06765b7df09e2e5ad591115742f2de9c08572bb2845cbf03fd7e23b7f031ed9c7564e52f39",
+            "abc914abe2926324b2c848e8a411a2b6df18cbe7758db8644145fefb0bf0a2d558a8c9946bd35e00c69d167aadf304c1",
+            "80755b3eb0dd4249cbefd20f177cee88e0761c066b71794825c9997b551f24051c352567ba6c01e57ac75dff763eaa17",
+            "82701eb98070728e1769525e73abff1783cedc364adb20c05c897a62f2ab2927f86f118dcb7819a7b218d8f3fee4bd7f",
+            "a1f229540474f4d6f1134761b92b788128c7ac8dc9b0c52d59493132679673032ac7db3fb3d79b46b13c1c41ee495bca",
+        ],
+        signature: "84773160b824e194073a57493dac1a20b667af70cd2352d8af241c77658da5253aa8458317cca0eae615690d55b1f27164657dcafee1d5c1973947aa70e2cfbb4c892340be5969920d0916067b4565a0",
+    },
+    Fixture {
+        suite: BbsCiphersuite::Bls12381Shake256,
+        key_dst: "4242535f424c53313233383147315f584f463a5348414b452d3235365f535357555f524f5f4832475f484d32535f4b455947454e5f4453545f",
+        sk: "2eee0f60a8a3a8bec0ee942bfd46cbdae9a0738ee68f5a64e7238311cf09a079",
+        pk: "92d37d1d6cd38fea3a873953333eab23a4c0377e3e049974eb62bd45949cdeb18fb0490edcd4429adff56e65cbce42cf188b31bddbd619e419b99c2c41b38179eb001963bc3decaae0d9f702c7a8c004f207f46c734a5eae2e8e82833f3e7ea5",
+        p1: "8929dfbc7e6642c4ed9cba0856e493f8b9d7d5fcb0c31ef8fdcd34d50648a56c795e106e9eada6e0bda386b414150755",
+        q1: "a9d40131066399fd41af51d883f4473b0dcd7d028d3d34ef17f3241d204e28507d7ecae032afa1d5490849b7678ec1f8",
+        h: &[
+            "903c7ca0b7e78a2017d0baf74103bd00ca8ff9bf429f834f071c75ffe6bfdec6d6dca15417e4ac08ca4ae1e78b7adc0e",
+            "84321f5855bfb6b001f0dfcb47ac9b5cc68f1a4edd20f0ec850e0563b27d2accee6edff1a26b357762fb24e8ddbb6fcb",
+            "b3060dff0d12a32819e08da00e61810676cc9185fdd750e5ef82b1a9798c7d76d63de3b6225d6c9a479d6c21a7c8bf93",
+            "8f1093d1e553cdead3c70ce55b6d664e5d1912cc9edfdd37bf1dad11ca396a0a8bb062092d391ebf8790ea5722413f68",
+            "990824e00b48a68c3d9a308e8c52a57b1bc84d1cf5d3c0f8c6fb6b1230e4e5b8eb752fb374da0b1ef687040024868140",
+            "b86d1c6ab8ce22bc53f625d1ce9796657f18060fcb1893ce8931156ef992fe56856199f8fa6c998e5d855a354a26b0dd",
+        ],
+        signature: "b9a622a4b404e6ca4c85c15739d2124a1deb16df750be202e2430e169bc27fb71c44d98e6d40792033e1c452145ada95030832c5dc778334f2f1b528eced21b0b97a12025a283d78b7136bb9825d04ef",
+    },
+];
+
+fn unhex(s: &str) -> Vec<u8> {
+    hex::decode(s).unwrap()
+}
+
+fn messages() -> Vec<Vec<u8>> {
+    MESSAGES.iter().map(|m| unhex(m)).collect()
+}
+
+/// mocked_calculate_random_scalars: expand_message(SEED, api_id ||
+/// "MOCK_RANDOM_SCALARS_DST_", 48 * count), each 48 bytes reduced mod r
+fn mocked_random_scalars(suite: BbsCiphersuite, count: usize) -> Vec<[u8; 32]> {
+    let dst = [suite.api_id(), b"MOCK_RANDOM_SCALARS_DST_".to_vec()].concat();
+    suite
+        .expand_message(&unhex(MOCKED_SCALARS_SEED), &dst, 48 * count)
+        .chunks(48)
+        .map(|okm| scalar_from_okm(okm).to_bytes())
+        .collect()
+}
+
+/// Serves the mocked scalars through the RngCore interface: each 64-byte draw
+/// is one scalar, little-endian and zero-padded, so the library's wide
+/// reduction returns it unchanged and ProofGen uses the draft's r1, r2, e~,
+/// r1~, r3~ and m~_j in that order.
+struct MockedRng(Vec<u8>);
+
+impl MockedRng {
+    fn new(suite: BbsCiphersuite, count: usize) -> Self {
+        Self(mocked_random_scalars(suite, count).iter().flat_map(|s| [s.as_slice(), &[0u8; 32]].concat()).collect())
+    }
+}
+
+impl RngCore for MockedRng {
+    fn next_u32(&mut self) -> u32 {
+        unimplemented!("ProofGen draws whole scalars")
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        unimplemented!("ProofGen draws whole scalars")
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).unwrap()
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        assert_eq!(dest.len(), 64, "one wide scalar per draw");
+        assert!(self.0.len() >= 64, "more random scalars drawn than mocked");
+        dest.copy_from_slice(&self.0[..64]);
+        self.0.drain(..64);
+        Ok(())
+    }
+}
+
+impl CryptoRng for MockedRng {}
+
+#[test]
+fn keygen_matches_fixtures() {
+    for f in &FIXTURES {
+        let sk = ietf_keygen(f.suite, unhex(KEY_MATERIAL), unhex(KEY_INFO), unhex(f.key_dst)).unwrap();
+        assert_eq!(hex::encode(&sk), f.sk);
+        // The default key_dst is the one used by the fixtures
+        let sk_default = ietf_keygen(f.suite, unhex(KEY_MATERIAL), unhex(KEY_INFO), vec![]).unwrap();
+        assert_eq!(sk_default, sk);
+        assert_eq!(hex::encode(ietf_sk_to_pk(sk).unwrap()), f.pk);
+    }
+}
+
+#[test]
+fn generators_match_fixtures() {
+    for f in &FIXTURES {
+        assert_eq!(hex::encode(f.suite.p1().to_affine().to_compressed()), f.p1);
+        let generators = f.suite.create_generators(f.h.len() + 1, &f.suite.api_id());
+        assert_eq!(hex::encode(generators[0].to_affine().to_compressed()), f.q1);
+        for (h, expected) in generators[1..].iter().zip(f.h) {
+            assert_eq!(hex::encode(h.to_affine().to_compressed()), *expected);
+        }
+    }
+}
+
+#[test]
+fn single_message_signature_matches_fixtures() {
+    for f in &FIXTURES {
+        let messages = vec![unhex(MESSAGE_1)];
+        let signature = ietf_sign(f.suite, unhex(f.sk), unhex(f.pk), unhex(HEADER), messages.clone()).unwrap();
+        assert_eq!(hex::encode(&signature), f.signature);
+        assert!(ietf_verify(f.suite, unhex(f.pk), signature.clone(), unhex(HEADER), messages).unwrap());
+        // Modified message
+        assert!(!ietf_verify(f.suite, unhex(f.pk), signature, unhex(HEADER), vec![vec![]]).unwrap());
+    }
+}
+
+#[test]
+fn proof_roundtrip_with_presentation_header() {
+    for f in &FIXTURES {
+        let messages: Vec<Vec<u8>> = (0u8..4).map(|i| vec![i; 16]).collect();
+        let signature = ietf_sign(f.suite, unhex(f.sk), unhex(f.pk), unhex(HEADER), messages.clone()).unwrap();
+        let ph = b"presentation header".to_vec();
+        let proof = ietf_proof_gen(
+            f.suite, unhex(f.pk), signature, unhex(HEADER), ph.clone(), messages.clone(), vec![0, 2],
+        )
+        .unwrap();
+        assert_eq!(proof.len(), 272 + 2 * 32);
+
+        let disclosed = vec![messages[0].clone(), messages[2].clone()];
+        assert!(ietf_proof_verify(
+            f.suite, unhex(f.pk), proof.clone(), unhex(HEADER), ph, disclosed.clone(), vec![0, 2],
+        )
+        .unwrap());
+        assert!(!ietf_proof_verify(
+            f.suite, unhex(f.pk), proof, unhex(HEADER), b"other".to_vec(), disclosed, vec![0, 2],
+        )
+        .unwrap());
+    }
+}
+
+#[test]
+fn multi_message_signature_matches_fixture() {
+    let f = &FIXTURES[0];
+    let signature = ietf_sign(f.suite, unhex(f.sk), unhex(f.pk), unhex(HEADER), messages()).unwrap();
+    assert_eq!(hex::encode(&signature), SHA256_MULTI_MESSAGE_SIGNATURE);
+    assert!(ietf_verify(f.suite, unhex(f.pk), signature.clone(), unhex(HEADER), messages()).unwrap());
+    // Message order is signed
+    let mut swapped = messages();
+    swapped.swap(0, 1);
+    assert!(!ietf_verify(f.suite, unhex(f.pk), signature, unhex(HEADER), swapped).unwrap());
+}
+
+#[test]
+fn mocked_random_scalars_match_fixture() {
+    let scalars = mocked_random_scalars(BbsCiphersuite::Bls12381Sha256, 10);
+    for (s, expected) in scalars.iter().zip(SHA256_MOCKED_SCALARS) {
+        let mut be = *s;
+        be.reverse();
+        assert_eq!(hex::encode(be), expected);
+    }
+}
+
+#[test]
+fn proof_gen_with_mocked_scalars_matches_fixture() {
+    let f = &FIXTURES[0];
+    let proof = ietf_proof_gen_with_rng(
+        f.suite,
+        unhex(f.pk),
+        unhex(f.signature),
+        unhex(HEADER),
+        unhex(PRESENTATION_HEADER),
+        vec![unhex(MESSAGE_1)],
+        vec![0],
+        &mut MockedRng::new(f.suite, 5),
+    )
+    .unwrap();
+    assert_eq!(hex::encode(&proof), SHA256_SINGLE_MESSAGE_PROOF);
+    assert!(ietf_proof_verify(
+        f.suite, unhex(f.pk), proof, unhex(HEADER), unhex(PRESENTATION_HEADER), vec![unhex(MESSAGE_1)], vec![0],
+    )
+    .unwrap());
+}
+
+fn scalar_be(bytes: &[u8]) -> Scalar {
+    let mut le: [u8; 32] = bytes.try_into().unwrap();
+    le.reverse();
+    Scalar::from_bytes(&le).unwrap()
+}
+
+fn g1(bytes: &[u8]) -> G1Affine {
+    G1Affine::from_compressed(bytes.try_into().unwrap()).unwrap()
+}
+
+/// ProofInit and ProofFinalize of the draft applied to the mocked r1, r2,
+/// e~, r1~ and r3~, checked against the proof fields of both ciphersuites
+#[test]
+fn proof_gen_uses_mocked_scalars_as_drafted() {
+    for f in &FIXTURES {
+        let signature = ietf_sign(f.suite, unhex(f.sk), unhex(f.pk), unhex(HEADER), messages()).unwrap();
+        let disclosed_indexes = vec![0, 2, 4, 6];
+        let undisclosed = MESSAGES.len() - disclosed_indexes.len();
+        let proof = ietf_proof_gen_with_rng(
+            f.suite,
+            unhex(f.pk),
+            signature.clone(),
+            unhex(HEADER),
+            unhex(PRESENTATION_HEADER),
+            messages(),
+            disclosed_indexes.clone(),
+            &mut MockedRng::new(f.suite, 5 + undisclosed),
+        )
+        .unwrap();
+        assert_eq!(proof.len(), 272 + undisclosed * 32);
+
+        let mocked: Vec<Scalar> =
+            mocked_random_scalars(f.suite, 5 + undisclosed).iter().map(|s| Scalar::from_bytes(s).unwrap()).collect();
+        let [r1, r2, e_tilde, r1_tilde, r3_tilde, ..] = mocked[..] else { unreachable!() };
+        let (a, e) = (g1(&signature[..48]), scalar_be(&signature[48..]));
+        let (abar, bbar, d) = (g1(&proof[..48]), g1(&proof[48..96]), g1(&proof[96..144]));
+        let challenge = scalar_be(&proof[proof.len() - 32..]);
+
+        // Abar = A * (r1 * r2), Bbar = D * r1 - Abar * e
+        assert_eq!(abar, (a * (r1 * r2)).to_affine());
+        assert_eq!(bbar, (d * r1 - abar * e).to_affine());
+        // e^ = e~ + e * c, r1^ = r1~ - r1 * c, r3^ = r3~ - c / r2
+        assert_eq!(scalar_be(&proof[144..176]), e_tilde + e * challenge);
+        assert_eq!(scalar_be(&proof[176..208]), r1_tilde - r1 * challenge);
+        assert_eq!(scalar_be(&proof[208..240]), r3_tilde - r2.invert().unwrap() * challenge);
+
+        let disclosed = disclosed_indexes.iter().map(|&i| unhex(MESSAGES[i as usize])).collect();
+        assert!(ietf_proof_verify(
+            f.suite, unhex(f.pk), proof, unhex(HEADER), unhex(PRESENTATION_HEADER), disclosed, disclosed_indexes,
+        )
+        .unwrap());
+    }
+}
@@ -0,0 +1,112 @@
+// Multi-credential proofs with equality constraints between hidden messages
+// `cargo test --test multi_credential`
+
+mod common;
+
+use common::*;
+use multipass::deterministic::seeded_rng;
+use multipass::multi_credential::{create_multi_credential_proof_with_rng, verify_multi_credential_proof};
+use multipass::*;
+
+/// holder id || attribute || nym secret, signed by the issuer named `issuer`
+fn credential(issuer: &str, holder_id: &[u8], attribute: &[u8]) -> CredentialInput {
+    let Credential { public_key, signature, messages } =
+        Issuer::new(issuer, 3).issue(holder_id, vec![holder_id.to_vec(), attribute.to_vec()]);
+    CredentialInput { public_key, signature, messages, revealed_indices: vec![1] }
+}
+
+fn disclosure(input: &CredentialInput) -> CredentialDisclosure {
+    CredentialDisclosure {
+        public_key: input.public_key.clone(),
+        total_message_count: input.messages.len() as u64,
+        revealed_indices: input.revealed_indices.clone(),
+        revealed_messages: input.revealed_indices.iter().map(|&i| input.messages[i as usize].clone()).collect(),
+    }
+}
+
+/// Message `index` of credential 0 equals message `index` of credential 1
+fn same(index: u32) -> EqualityConstraint {
+    EqualityConstraint { first_credential: 0, first_index: index, second_credential: 1, second_index: index }
+}
+
+fn prove(credentials: &[CredentialInput], equalities: &[EqualityConstraint]) -> Result<Vec<u8>, VerifyError> {
+    create_multi_credential_proof_with_rng(
+        credentials.to_vec(),
+        equalities.to_vec(),
+        NONCE.to_vec(),
+        SITE.to_vec(),
+        None,
+        0,
+        None,
+        &mut seeded_rng(b"multi-credential"),
+    )
+}
+
+fn verify(
+    disclosures: &[CredentialDisclosure],
+    proof: &[u8],
+    equalities: &[EqualityConstraint],
+) -> Result<bool, VerifyError> {
+    verify_multi_credential_proof(
+        disclosures.to_vec(),
+        proof.to_vec(),
+        equalities.to_vec(),
+        NONCE.to_vec(),
+        SITE.to_vec(),
+        None,
+        0,
+        None,
+    )
+}
+
+#[test]
+fn equality_across_two_credentials_verifies() {
+    let credentials = [credential("passport office", b"holder-42", b"NL"), credential("university", b"holder-42", b"MSc")];
+    let disclosures: Vec<_> = credentials.iter().map(disclosure).collect();
+    let proof = prove(&credentials, &[same(0)]).unwrap();
+    assert!(verify(&disclosures, &proof, &[same(0)]).unwrap());
+
+    // The constraints are part of the statement
+    assert!(!verify(&disclosures, &proof, &[]).unwrap());
+    let unconstrained = prove(&credentials, &[]).unwrap();
+    assert!(verify(&disclosures, &unconstrained, &[]).unwrap());
+    assert!(!verify(&disclosures, &unconstrained, &[same(0)]).unwrap());
+}
+
+#[test]
+fn unequal_hidden_values_are_rejected() {
+    let credentials = [credential("passport office", b"holder-42", b"NL"), credential("university", b"holder-43", b"MSc")];
+    let disclosures: Vec<_> = credentials.iter().map(disclosure).collect();
+    assert!(prove(&credentials, &[same(0)]).is_err());
+
+    let proof = prove(&credentials, &[]).unwrap();
+    assert!(verify(&disclosures, &proof, &[]).unwrap());
+    assert!(!verify(&disclosures, &proof, &[same(0)]).unwrap());
+}
+
+#[test]
+fn constraint_on_revealed_index_is_rejected() {
+    let credentials = [credential("passport office", b"holder-42", b"NL"), credential("university", b"holder-42", b"NL")];
+    let disclosures: Vec<_> = credentials.iter().map(disclosure).collect();
+    // Index 1 is revealed by both
+    assert!(prove(&credentials, &[same(1)]).is_err());
+
+    let proof = prove(&credentials, &[]).unwrap();
+    assert!(!verify(&disclosures, &proof, &[same(1)]).unwrap());
+}
+
+#[test]
+fn mismatched_issuer_keys_are_rejected() {
+    let credentials = [credential("passport office", b"holder-42", b"NL"), credential("university", b"holder-42", b"MSc")];
+    let proof = prove(&credentials, &[same(0)]).unwrap();
+
+    let mut swapped: Vec<_> = credentials.iter().map(disclosure).collect();
+    let first_key = swapped[0].public_key.clone();
+    swapped[0].public_key = swapped[1].public_key.clone();
+    swapped[1].public_key = first_key;
+    assert!(!verify(&swapped, &proof, &[same(0)]).unwrap());
+
+    let mut impostor: Vec<_> = credentials.iter().map(disclosure).collect();
+    impostor[1].public_key = credential("diploma mill", b"holder-42", b"MSc").public_key;
+    assert!(!verify(&impostor, &proof, &[same(0)]).unwrap());
+}
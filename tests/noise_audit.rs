@@ -0,0 +1,27 @@
+// MinerEngine records every ε spend in the vault's audit tree
+// `cargo test --test noise_audit`
+
+use multipass::miner::MinerEngine;
+
+#[test]
+fn released_batches_are_audited() {
+    let path = std::env::temp_dir().join(format!("multipass-noise-audit-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    let engine = MinerEngine::new(path.to_str().unwrap(), 0.5).unwrap();
+    assert!(engine.noise_audit_log().unwrap().is_empty());
+
+    // The 15th distinct identity releases the buffer
+    for identity in 0..14u32 {
+        assert!(engine.submit_macro_data("STEPS".to_string(), 1.0, &identity.to_be_bytes()).unwrap().is_none());
+    }
+    assert!(engine.noise_audit_log().unwrap().is_empty());
+    assert!(engine.submit_macro_data("STEPS".to_string(), 1.0, &14u32.to_be_bytes()).unwrap().is_some());
+
+    let log = engine.noise_audit_log().unwrap();
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].action, "SUBMIT_BATCH");
+    assert_eq!(log[0].epsilon, 0.5);
+
+    drop(engine);
+    let _ = std::fs::remove_dir_all(&path);
+}
@@ -0,0 +1,116 @@
+// Rate-limited proofs and the MinerEngine nullifier store
+// `cargo test --test nullifier`
+
+mod common;
+
+use common::*;
+use multipass::deterministic::seeded_rng;
+use multipass::nullifier::{create_rate_limited_proof_with_rng, verify_rate_limited_proof};
+use multipass::*;
+
+const EPOCH: &[u8] = b"2026-10-17";
+const LIMIT: u64 = 3;
+
+fn holder() -> Credential {
+    Issuer::new("issuer", 2).issue(b"alice", vec![b"alice".to_vec()])
+}
+
+impl Credential {
+    fn prove(&self, counter: u64) -> Result<Vec<u8>, VerifyError> {
+        create_rate_limited_proof_with_rng(
+            self.public_key.clone(),
+            self.signature.clone(),
+            self.messages.clone(),
+            vec![0],
+            NONCE.to_vec(),
+            SITE.to_vec(),
+            EPOCH.to_vec(),
+            LIMIT,
+            counter,
+            0,
+            None,
+            &mut seeded_rng(&counter.to_be_bytes()),
+        )
+    }
+
+    fn verify(&self, proof: &[u8], limit: u64) -> Result<bool, VerifyError> {
+        verify_rate_limited_proof(
+            self.public_key.clone(),
+            proof.to_vec(),
+            2,
+            vec![0],
+            vec![self.messages[0].clone()],
+            NONCE.to_vec(),
+            SITE.to_vec(),
+            EPOCH.to_vec(),
+            limit,
+            0,
+            None,
+        )
+    }
+}
+
+fn nullifier(proof: &[u8]) -> Vec<u8> {
+    BbsProof::parse(proof).unwrap().nullifier().expect("rate-limited proof carries a nullifier")
+}
+
+#[test]
+fn rate_limited_proof_round_trip() {
+    let holder = holder();
+    let proof = holder.prove(0).unwrap();
+    assert!(holder.verify(&proof, LIMIT).unwrap());
+    assert!(BbsProof::parse(&proof).unwrap().nullifier().is_some());
+}
+
+#[test]
+fn double_spend_is_detected_by_the_store() {
+    let holder = holder();
+    let store = Store::open("nullifier-double-spend");
+    let first = holder.prove(0).unwrap();
+    let second = holder.prove(1).unwrap();
+    // Fresh randomness, same counter: same nullifier
+    let replay = create_rate_limited_proof_with_rng(
+        holder.public_key.clone(),
+        holder.signature.clone(),
+        holder.messages.clone(),
+        vec![0],
+        NONCE.to_vec(),
+        SITE.to_vec(),
+        EPOCH.to_vec(),
+        LIMIT,
+        0,
+        0,
+        None,
+        &mut seeded_rng(b"replay"),
+    )
+    .unwrap();
+    assert_ne!(first, replay);
+    assert_eq!(nullifier(&first), nullifier(&replay));
+    assert_ne!(nullifier(&first), nullifier(&second));
+
+    let record = |proof: &[u8]| store.engine.record_nullifier(SITE, EPOCH, &nullifier(proof)).unwrap();
+    assert!(record(&first));
+    assert!(record(&second));
+    assert!(!record(&replay));
+    // Other epochs and sites are separate scopes
+    assert!(store.engine.record_nullifier(SITE, b"2026-10-18", &nullifier(&first)).unwrap());
+    assert!(store.engine.record_nullifier(b"other.example", EPOCH, &nullifier(&first)).unwrap());
+
+    assert_eq!(store.engine.forget_nullifiers(SITE, EPOCH).unwrap(), 2);
+    assert_eq!(store.engine.forget_nullifiers(SITE, EPOCH).unwrap(), 0);
+    assert!(record(&replay));
+    assert!(!store.engine.record_nullifier(SITE, b"2026-10-18", &nullifier(&first)).unwrap());
+}
+
+#[test]
+fn counter_outside_the_limit_is_rejected() {
+    let holder = holder();
+    assert!(holder.prove(LIMIT).is_err());
+    assert!(holder.prove(u64::MAX).is_err());
+
+    // A proof for counter 2 does not verify under a limit of 2
+    let proof = holder.prove(LIMIT - 1).unwrap();
+    assert!(holder.verify(&proof, LIMIT).unwrap());
+    assert!(!holder.verify(&proof, LIMIT - 1).unwrap());
+    assert!(holder.verify(&proof, 0).is_err());
+}
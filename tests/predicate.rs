@@ -0,0 +1,126 @@
+// Range predicates over hidden integer attributes
+// `cargo test --test predicate`
+
+mod common;
+
+use common::*;
+use multipass::deterministic::seeded_rng;
+use multipass::predicate::encode_integer_message;
+use multipass::*;
+
+/// name || encode_integer_message(value) || nym secret
+fn credential(value: u64) -> Credential {
+    Issuer::new("predicate", 3).issue(b"alice", vec![b"alice".to_vec(), encode_integer_message(value)])
+}
+
+fn range(lower: Option<u64>, upper: Option<u64>) -> RangePredicate {
+    RangePredicate { index: 1, lower, upper }
+}
+
+impl Credential {
+    fn prove(&self, revealed: &[u32], predicates: &[RangePredicate]) -> Result<BbsProof, VerifyError> {
+        let (public_key, signature) = self.typed();
+        signature.prove_with_predicates_and_rng(
+            &public_key,
+            &self.messages,
+            revealed,
+            predicates,
+            Some(NONCE),
+            SITE,
+            None,
+            0,
+            None,
+            &mut seeded_rng(b"prove"),
+        )
+    }
+
+    fn check(&self, proof: &BbsProof, revealed: &[u32], predicates: &[RangePredicate]) -> Result<(), VerifyError> {
+        self.typed().0.check_proof_with_predicates(
+            proof,
+            self.messages.len(),
+            revealed,
+            &self.revealed(revealed),
+            predicates,
+            NONCE,
+            SITE,
+            None,
+            0,
+            None,
+        )
+    }
+
+    fn prove_and_check(&self, predicate: RangePredicate) -> Result<(), VerifyError> {
+        let proof = self.prove(&[0], std::slice::from_ref(&predicate))?;
+        self.check(&BbsProof::parse(&proof.to_bytes())?, &[0], &[predicate])
+    }
+}
+
+#[test]
+fn lower_upper_and_two_sided_bounds_verify() {
+    let cred = credential(30);
+    cred.prove_and_check(range(Some(18), None)).unwrap();
+    cred.prove_and_check(range(None, Some(65))).unwrap();
+    cred.prove_and_check(range(Some(18), Some(65))).unwrap();
+}
+
+#[test]
+fn out_of_range_value_is_rejected_at_prove_time() {
+    let cred = credential(16);
+    let unsatisfied = Some(VerifyError::PredicateUnsatisfied { index: 1 });
+    assert_eq!(cred.prove(&[0], &[range(Some(18), None)]).err(), unsatisfied);
+    assert_eq!(cred.prove(&[0], &[range(None, Some(15))]).err(), unsatisfied);
+    let err = cred.prove(&[0], &[range(Some(0), Some(15))]).err().unwrap();
+    assert_eq!(err.code(), "predicate_unsatisfied");
+}
+
+#[test]
+fn predicate_on_non_integer_message_is_rejected() {
+    let cred = credential(30);
+    let err = cred.prove(&[1], &[RangePredicate { index: 0, lower: Some(1), upper: None }]).err().unwrap();
+    assert_eq!(err, VerifyError::NotAnIntegerMessage { index: 0 });
+    assert_eq!(err.code(), "not_an_integer_message");
+
+    let err = cred.prove(&[0], &[RangePredicate { index: 3, lower: Some(1), upper: None }]).err().unwrap();
+    assert_eq!(err, VerifyError::RevealedIndexOutOfRange { index: 3, message_count: 3 });
+}
+
+#[test]
+fn predicate_changed_at_verify_time_is_rejected() {
+    let cred = credential(30);
+    let proof = cred.prove(&[0], &[range(Some(18), Some(65))]).unwrap();
+    cred.check(&proof, &[0], &[range(Some(18), Some(65))]).unwrap();
+
+    assert!(matches!(cred.check(&proof, &[0], &[range(Some(21), Some(65))]), Err(VerifyError::ChallengeMismatch)));
+    assert!(matches!(cred.check(&proof, &[0], &[range(Some(18), Some(40))]), Err(VerifyError::ChallengeMismatch)));
+    assert!(cred.check(&proof, &[0], &[range(Some(18), None)]).is_err());
+    assert!(matches!(
+        cred.check(&proof, &[0], &[]),
+        Err(VerifyError::ProofStructure { ref field }) if field == "range_proofs"
+    ));
+}
+
+#[test]
+fn predicate_on_revealed_index_is_rejected() {
+    let cred = credential(30);
+    let err = cred.prove(&[0, 1], &[range(Some(18), None)]).err().unwrap();
+    assert_eq!(err, VerifyError::PredicateOnRevealedMessage { index: 1 });
+    assert_eq!(err.code(), "predicate_on_revealed_message");
+
+    // A valid proof replayed with the predicate's message claimed as revealed
+    let proof = cred.prove(&[0], &[range(Some(18), None)]).unwrap();
+    assert!(cred.check(&proof, &[0, 1], &[range(Some(18), None)]).is_err());
+}
+
+#[test]
+fn boundaries_are_inclusive() {
+    // lower == value == upper in one two-sided predicate
+    credential(18).prove_and_check(range(Some(18), Some(18))).unwrap();
+    credential(0).prove_and_check(range(Some(0), Some(0))).unwrap();
+
+    let max = credential(u64::MAX);
+    max.prove_and_check(range(Some(u64::MAX), Some(u64::MAX))).unwrap();
+    assert!(matches!(
+        max.prove(&[0], &[range(None, Some(u64::MAX - 1))]),
+        Err(VerifyError::PredicateUnsatisfied { index: 1 })
+    ));
+}
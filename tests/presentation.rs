@@ -0,0 +1,123 @@
+// Presentation envelopes: CBOR and JSON encodings and the checks on verify
+// `cargo test --test presentation`
+
+mod common;
+
+use common::*;
+use multipass::deterministic::seeded_rng;
+use multipass::presentation::{
+    create_presentation_with_rng, issuer_key_id, presentation_from_cbor, presentation_from_json, presentation_to_cbor,
+    presentation_to_json, verify_presentation, verify_presentation_verdict, PRESENTATION_VERSION,
+};
+use multipass::*;
+
+fn public_key(issuer: &str) -> Vec<u8> {
+    Issuer::new(issuer, 3).public_key
+}
+
+/// Presentation of name || country || nym secret revealing the country
+fn presentation() -> Presentation {
+    let credential = Issuer::new("issuer", 3).issue(b"alice", vec![b"alice".to_vec(), b"NL".to_vec()]);
+    create_presentation_with_rng(
+        credential.public_key,
+        credential.signature,
+        credential.messages,
+        vec![1],
+        NONCE.to_vec(),
+        SITE.to_vec(),
+        Some(b"2026-10".to_vec()),
+        0,
+        None,
+        &mut seeded_rng(b"present"),
+    )
+    .unwrap()
+}
+
+fn check(presentation: &Presentation, nonce: &[u8], site_id: &[u8]) -> Result<(), VerifyError> {
+    presentation.check(&BbsPublicKey::parse(&public_key("issuer")).unwrap(), nonce, site_id)
+}
+
+#[test]
+fn cbor_and_json_round_trips() {
+    let presentation = presentation();
+    assert_eq!(presentation.version, PRESENTATION_VERSION);
+    assert_eq!(presentation.issuer_key_id, issuer_key_id(public_key("issuer")).unwrap());
+    assert_eq!(presentation.revealed_messages, vec![b"NL".to_vec()]);
+    check(&presentation, NONCE, SITE).unwrap();
+
+    let cbor = presentation_to_cbor(presentation.clone()).unwrap();
+    let decoded = presentation_from_cbor(cbor).unwrap();
+    assert_eq!(decoded, presentation);
+    check(&decoded, NONCE, SITE).unwrap();
+
+    let json = presentation_to_json(presentation.clone());
+    assert!(json.contains(r#""site_id":"cnAuZXhhbXBsZQ""#));
+    let decoded = presentation_from_json(json).unwrap();
+    assert_eq!(decoded, presentation);
+    check(&decoded, NONCE, SITE).unwrap();
+
+    assert!(matches!(presentation_from_cbor(b"not cbor".to_vec()), Err(VerifyError::ProofStructure { .. })));
+    assert!(matches!(presentation_from_json("{}".to_string()), Err(VerifyError::ProofStructure { .. })));
+}
+
+#[test]
+fn mismatched_nonce_and_site_id_are_reported() {
+    let presentation = presentation();
+    assert_eq!(
+        check(&presentation, b"other nonce", SITE),
+        Err(VerifyError::ContextMismatch { field: "nonce".to_string() })
+    );
+    assert_eq!(
+        check(&presentation, NONCE, b"other.example"),
+        Err(VerifyError::ContextMismatch { field: "site_id".to_string() })
+    );
+
+    // A rewritten envelope no longer matches its proof
+    let mut replayed = presentation.clone();
+    replayed.nonce = b"other nonce".to_vec();
+    assert_eq!(check(&replayed, b"other nonce", SITE), Err(VerifyError::ChallengeMismatch));
+
+    // verify reports a context mismatch as an invalid presentation
+    let verify = |nonce: &[u8], site_id: &[u8]| {
+        verify_presentation(public_key("issuer"), presentation.clone(), nonce.to_vec(), site_id.to_vec())
+    };
+    assert_eq!(verify(NONCE, SITE), Ok(true));
+    assert_eq!(verify(b"other nonce", SITE), Ok(false));
+    assert_eq!(verify(NONCE, b"other.example"), Ok(false));
+    let verdict = verify_presentation_verdict(public_key("issuer"), presentation, NONCE.to_vec(), b"other.example".to_vec());
+    assert!(!verdict.valid);
+    assert_eq!(verdict.code, "context_mismatch");
+}
+
+#[test]
+fn wrong_issuer_key_id_is_reported() {
+    let presentation = presentation();
+    let mut relabelled = presentation.clone();
+    relabelled.issuer_key_id = issuer_key_id(public_key("other issuer")).unwrap();
+    assert_eq!(check(&relabelled, NONCE, SITE), Err(VerifyError::InvalidKey));
+
+    // The right id checked against another issuer's key
+    assert_eq!(
+        verify_presentation(public_key("other issuer"), presentation, NONCE.to_vec(), SITE.to_vec()),
+        Err(VerifyError::InvalidKey)
+    );
+    let verdict = verify_presentation_verdict(public_key("issuer"), relabelled, NONCE.to_vec(), SITE.to_vec());
+    assert_eq!(verdict.code, "invalid_key");
+}
+
+#[test]
+fn wrong_version_is_reported() {
+    let mut presentation = presentation();
+    presentation.version = PRESENTATION_VERSION + 1;
+    assert_eq!(check(&presentation, NONCE, SITE), Err(VerifyError::UnsupportedProofVersion));
+
+    // The version survives both encodings and is checked after decoding
+    let decoded = presentation_from_cbor(presentation_to_cbor(presentation.clone()).unwrap()).unwrap();
+    assert_eq!(decoded.version, PRESENTATION_VERSION + 1);
+    assert_eq!(
+        verify_presentation(public_key("issuer"), decoded, NONCE.to_vec(), SITE.to_vec()),
+        Err(VerifyError::UnsupportedProofVersion)
+    );
+    let decoded = presentation_from_json(presentation_to_json(presentation)).unwrap();
+    assert_eq!(check(&decoded, NONCE, SITE), Err(VerifyError::UnsupportedProofVersion));
+}
@@ -0,0 +1,104 @@
+// Per-site pseudonyms derived from the hidden nym secret
+// `cargo test --test pseudonym`
+
+mod common;
+
+use common::*;
+use multipass::deterministic::seeded_rng;
+use multipass::*;
+
+/// Credential over name || country || nym secret, the nym secret from `seed`
+fn holder(seed: &[u8]) -> Credential {
+    Issuer::new("issuer", 3).issue(seed, vec![b"alice".to_vec(), b"NL".to_vec()])
+}
+
+impl Credential {
+    fn prove(&self, site: &[u8], revealed: &[u32], proof_seed: &[u8]) -> Result<BbsProof, VerifyError> {
+        let (public_key, signature) = self.typed();
+        signature.prove_with_rng(
+            &public_key,
+            &self.messages,
+            revealed,
+            Some(NONCE),
+            site,
+            None,
+            0,
+            None,
+            &mut seeded_rng(proof_seed),
+        )
+    }
+
+    fn pseudonym(&self, site: &[u8], proof_seed: &[u8]) -> Vec<u8> {
+        self.prove(site, &[1], proof_seed).unwrap().pseudonym().unwrap()
+    }
+
+    fn check(&self, proof: &BbsProof, site: &[u8], revealed: &[u32]) -> Result<(), VerifyError> {
+        self.typed().0.check_proof_with_predicates(proof, 3, revealed, &self.revealed(revealed), &[], NONCE, site, None, 0, None)
+    }
+}
+
+#[test]
+fn same_holder_and_site_give_the_same_pseudonym() {
+    let alice = holder(b"alice");
+    let first = alice.prove(b"shop.example", &[1], b"first").unwrap();
+    let second = alice.prove(b"shop.example", &[0, 1], b"second").unwrap();
+    assert_ne!(first.to_bytes(), second.to_bytes());
+    assert_eq!(first.pseudonym(), second.pseudonym());
+    assert!(first.nullifier().is_none());
+    alice.check(&first, b"shop.example", &[1]).unwrap();
+    alice.check(&second, b"shop.example", &[0, 1]).unwrap();
+}
+
+#[test]
+fn different_sites_and_holders_give_unrelated_pseudonyms() {
+    let alice = holder(b"alice");
+    let bob = holder(b"bob");
+    let shop = alice.pseudonym(b"shop.example", b"1");
+    let forum = alice.pseudonym(b"forum.example", b"2");
+    assert_ne!(shop, forum);
+    assert_ne!(shop, bob.pseudonym(b"shop.example", b"3"));
+}
+
+#[test]
+fn swapped_pseudonym_is_rejected() {
+    let alice = holder(b"alice");
+    let bob = holder(b"bob");
+    let proof = alice.prove(b"shop.example", &[1], b"proof").unwrap();
+    assert_eq!(proof.to_bytes()[TAG_OFFSET..TAG_OFFSET + 48], proof.pseudonym().unwrap()[..]);
+
+    let bob_pseudonym = bob.pseudonym(b"shop.example", b"bob");
+    let mut bytes = proof.to_bytes();
+    bytes[TAG_OFFSET..TAG_OFFSET + 48].copy_from_slice(&bob_pseudonym);
+    let swapped = BbsProof::parse(&bytes).unwrap();
+    assert_eq!(swapped.pseudonym(), Some(bob_pseudonym));
+    assert_eq!(alice.check(&swapped, b"shop.example", &[1]), Err(VerifyError::ChallengeMismatch));
+
+    // Alice's own pseudonym replayed for another site
+    let mut bytes = proof.to_bytes();
+    bytes[TAG_OFFSET..TAG_OFFSET + 48].copy_from_slice(&alice.pseudonym(b"forum.example", b"forum"));
+    let replayed = BbsProof::parse(&bytes).unwrap();
+    assert_eq!(alice.check(&replayed, b"forum.example", &[1]), Err(VerifyError::ChallengeMismatch));
+    assert_eq!(alice.check(&proof, b"forum.example", &[1]), Err(VerifyError::ChallengeMismatch));
+}
+
+#[test]
+fn revealed_nym_secret_is_rejected() {
+    let alice = holder(b"alice");
+    let err = alice.prove(b"shop.example", &[2], b"proof").err().unwrap();
+    assert_eq!(err, VerifyError::NymSecretNotHidden);
+    assert_eq!(err.code(), "nym_secret_not_hidden");
+    assert_eq!(alice.prove(b"shop.example", &[0, 1, 2], b"proof").err(), Some(VerifyError::NymSecretNotHidden));
+
+    // A credential signed without a nym slot, fully disclosed
+    let mut rng = seeded_rng(b"no nym");
+    let sk = BbsSecretKey::generate_with_rng(&mut rng).unwrap();
+    let public_key = BbsPublicKey::parse(&sk.public_key(1).to_bytes()).unwrap();
+    let messages = vec![b"alice".to_vec()];
+    let signature = sk.sign_messages_with_rng(&public_key, &messages, &mut rng).unwrap();
+    let proof = signature.prove_with_rng(&public_key, &messages, &[0], Some(NONCE), b"shop.example", None, 0, None, &mut rng);
+    assert_eq!(proof.err(), Some(VerifyError::NymSecretNotHidden));
+
+    // An honest proof checked as if the last message were disclosed
+    let proof = alice.prove(b"shop.example", &[1], b"proof").unwrap();
+    assert!(alice.check(&proof, b"shop.example", &[1, 2]).is_err());
+}
@@ -0,0 +1,181 @@
+// Credential schemas: JSON form, attribute encoding and schema-bound proofs
+// `cargo test --test schema`
+
+mod common;
+
+use common::*;
+use multipass::deterministic::{generate_nym_secret_seeded, seeded_rng};
+use multipass::predicate::encode_integer_message;
+use multipass::schema::{
+    create_proof_with_schema_with_rng, schema_from_json, schema_to_json, sign_with_schema_with_rng,
+    verify_proof_with_schema,
+};
+use multipass::*;
+use std::collections::HashMap;
+
+fn definition(name: &str, attribute_type: AttributeType) -> AttributeDefinition {
+    AttributeDefinition { name: name.to_string(), attribute_type }
+}
+
+fn member_schema() -> CredentialSchema {
+    CredentialSchema {
+        id: "https://example.com/member".to_string(),
+        attributes: vec![
+            definition("name", AttributeType::String),
+            definition("birth_date", AttributeType::Date),
+            definition("level", AttributeType::Integer),
+            definition("active", AttributeType::Boolean),
+        ],
+    }
+}
+
+fn member_attributes() -> HashMap<String, AttributeValue> {
+    HashMap::from([
+        ("name".to_string(), AttributeValue::String { value: "alice".to_string() }),
+        ("birth_date".to_string(), AttributeValue::Date { value: "2000-02-29".to_string() }),
+        ("level".to_string(), AttributeValue::Integer { value: 3 }),
+        ("active".to_string(), AttributeValue::Boolean { value: true }),
+    ])
+}
+
+fn date(value: &str) -> Result<Vec<u8>, VerifyError> {
+    AttributeValue::Date { value: value.to_string() }.to_message()
+}
+
+#[test]
+fn schema_json_round_trip() {
+    let schema = member_schema();
+    let json = schema_to_json(schema.clone());
+    assert_eq!(schema_from_json(json).unwrap(), schema);
+
+    let json = r#"{"id":"https://example.com/member","attributes":[
+        {"name":"name","type":"string"},{"name":"birth_date","type":"date"},
+        {"name":"level","type":"integer"},{"name":"active","type":"boolean"}]}"#;
+    let parsed = CredentialSchema::from_json(json).unwrap();
+    assert_eq!(parsed, schema);
+    assert_eq!(parsed.hash(), schema.hash());
+    assert_eq!(parsed.message_count(), 6);
+
+    assert_eq!(
+        CredentialSchema::from_json(r#"{"id":"x","attributes":[{"name":"a","type":"float"}]}"#),
+        Err(VerifyError::SchemaMismatch)
+    );
+    assert_eq!(
+        CredentialSchema::from_json(r#"{"id":"x","attributes":[{"name":"a","type":"string"},{"name":"a","type":"date"}]}"#),
+        Err(VerifyError::SchemaMismatch)
+    );
+}
+
+#[test]
+fn date_edge_cases() {
+    // Leap years: divisible by 4, centuries only when divisible by 400
+    assert_eq!(date("2024-02-29").unwrap(), encode_integer_message(20240229));
+    assert_eq!(date("2000-02-29").unwrap(), encode_integer_message(20000229));
+    assert_eq!(date("2023-02-29"), Err(VerifyError::SchemaMismatch));
+    assert_eq!(date("1900-02-29"), Err(VerifyError::SchemaMismatch));
+
+    assert_eq!(date("0000-01-01"), Err(VerifyError::SchemaMismatch));
+    assert_eq!(date("0001-01-01").unwrap(), encode_integer_message(10101));
+    assert_eq!(date("2024-13-01"), Err(VerifyError::SchemaMismatch));
+    assert_eq!(date("2024-00-10"), Err(VerifyError::SchemaMismatch));
+    assert_eq!(date("2024-04-31"), Err(VerifyError::SchemaMismatch));
+    assert_eq!(date("2024-1-01"), Err(VerifyError::SchemaMismatch));
+    assert_eq!(date("+024-01-01"), Err(VerifyError::SchemaMismatch));
+
+    // Revealed messages decode back to the same dates
+    for value in ["2024-02-29", "0001-01-01", "9999-12-31"] {
+        let message = date(value).unwrap();
+        assert_eq!(
+            AttributeValue::from_message(AttributeType::Date, &message).unwrap(),
+            AttributeValue::Date { value: value.to_string() }
+        );
+    }
+    for invalid in [20230229, 101, 20241301, 100_000_101] {
+        assert_eq!(
+            AttributeValue::from_message(AttributeType::Date, &encode_integer_message(invalid)),
+            Err(VerifyError::SchemaMismatch)
+        );
+    }
+}
+
+#[test]
+fn sign_prove_and_verify_with_schema() {
+    let schema = member_schema();
+    let attributes = member_attributes();
+    let Issuer { secret_key: sk, public_key: pk } = Issuer::new("issuer", schema.message_count() as u32);
+    let nym_secret = generate_nym_secret_seeded(b"nym".to_vec()).unwrap();
+    let signature = sign_with_schema_with_rng(
+        sk,
+        pk.clone(),
+        schema.clone(),
+        attributes.clone(),
+        nym_secret.clone(),
+        &mut seeded_rng(b"sign"),
+    )
+    .unwrap();
+
+    let proof = create_proof_with_schema_with_rng(
+        pk.clone(),
+        signature,
+        schema.clone(),
+        attributes.clone(),
+        nym_secret,
+        vec!["birth_date".to_string(), "active".to_string()],
+        Some(NONCE.to_vec()),
+        SITE.to_vec(),
+        None,
+        0,
+        None,
+        &mut seeded_rng(b"prove"),
+    )
+    .unwrap();
+
+    let verify = |schema: &CredentialSchema, revealed: HashMap<String, AttributeValue>| {
+        verify_proof_with_schema(pk.clone(), proof.clone(), schema.clone(), revealed, NONCE.to_vec(), SITE.to_vec(), None, 0, None)
+    };
+    let revealed: HashMap<_, _> =
+        attributes.into_iter().filter(|(name, _)| name == "birth_date" || name == "active").collect();
+    assert!(verify(&schema, revealed.clone()).unwrap());
+
+    let mut other_date = revealed.clone();
+    other_date.insert("birth_date".to_string(), AttributeValue::Date { value: "2000-03-01".to_string() });
+    assert!(!verify(&schema, other_date).unwrap());
+
+    // Same attributes under another schema id
+    let mut renamed = schema;
+    renamed.id = "https://example.com/visitor".to_string();
+    assert!(!verify(&renamed, revealed).unwrap());
+}
+
+#[test]
+fn wrong_attribute_type_is_rejected() {
+    let schema = member_schema();
+    let Issuer { secret_key: sk, public_key: pk } = Issuer::new("issuer", schema.message_count() as u32);
+    let nym_secret = generate_nym_secret_seeded(b"nym".to_vec()).unwrap();
+    let sign = |attributes: HashMap<String, AttributeValue>| {
+        sign_with_schema_with_rng(
+            sk.clone(),
+            pk.clone(),
+            schema.clone(),
+            attributes,
+            nym_secret.clone(),
+            &mut seeded_rng(b"sign"),
+        )
+    };
+
+    let mut attributes = member_attributes();
+    attributes.insert("level".to_string(), AttributeValue::String { value: "3".to_string() });
+    assert_eq!(sign(attributes), Err(VerifyError::SchemaMismatch));
+
+    let mut attributes = member_attributes();
+    attributes.insert("birth_date".to_string(), AttributeValue::Integer { value: 20000229 });
+    assert_eq!(sign(attributes), Err(VerifyError::SchemaMismatch));
+
+    let mut attributes = member_attributes();
+    attributes.remove("active");
+    assert_eq!(sign(attributes), Err(VerifyError::SchemaMismatch));
+
+    // A disclosed value typed differently from its schema entry
+    let revealed = HashMap::from([("active".to_string(), AttributeValue::Integer { value: 1 })]);
+    assert_eq!(schema.revealed_messages(&revealed), Err(VerifyError::SchemaMismatch));
+}
@@ -0,0 +1,85 @@
+// Sealed root secrets: reproducible derivations, sealing and rotation
+// `cargo test --test sealed_root`
+
+use multipass::deterministic::seeded_rng;
+use multipass::periwinkle::EntropyError;
+use multipass::sealed_root::SealedRoot;
+use std::path::PathBuf;
+
+const KEK: [u8; 32] = [0x11; 32];
+
+fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("sealed-root-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn derivations_survive_reopening() {
+    let path = temp_path("reopen");
+    let root = SealedRoot::create_with_rng(&path, KEK, &mut seeded_rng(b"reopen")).unwrap();
+    assert_eq!(root.current_version(), 1);
+    let tag = root.derive(b"LinkageTag");
+    assert_eq!(tag, root.derive(b"LinkageTag"));
+    assert_ne!(tag, root.derive(b"OtherContext"));
+    drop(root);
+
+    let reopened = SealedRoot::open(&path, KEK).unwrap();
+    assert_eq!(reopened.derive(b"LinkageTag"), tag);
+    assert_eq!(reopened.derive_path(1, &[b"LinkageTag"]).unwrap(), tag);
+    assert!(SealedRoot::create(&path, KEK).is_err());
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn labels_form_a_hierarchy() {
+    let path = temp_path("hierarchy");
+    let root = SealedRoot::create_with_rng(&path, KEK, &mut seeded_rng(b"hierarchy")).unwrap();
+    let parent = root.derive_path(1, &[b"app"]).unwrap();
+    let child = root.derive_path(1, &[b"app", b"site"]).unwrap();
+    assert_ne!(parent, child);
+    assert_ne!(child, root.derive_path(1, &[b"site", b"app"]).unwrap());
+    assert_eq!(root.derive_path(1, &[]).unwrap().len(), 32);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn wrong_key_or_tampering_fails() {
+    let path = temp_path("tamper");
+    SealedRoot::create_with_rng(&path, KEK, &mut seeded_rng(b"tamper")).unwrap();
+    assert!(matches!(SealedRoot::open(&path, [0x22; 32]), Err(EntropyError::SealedRoot { .. })));
+
+    let mut bytes = std::fs::read(&path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 1;
+    std::fs::write(&path, &bytes).unwrap();
+    assert!(matches!(SealedRoot::open(&path, KEK), Err(EntropyError::SealedRoot { .. })));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn rotation_keeps_old_versions_until_retired() {
+    let path = temp_path("rotate");
+    let mut rng = seeded_rng(b"rotate");
+    let mut root = SealedRoot::create_with_rng(&path, KEK, &mut rng).unwrap();
+    let v1_tag = root.derive(b"LinkageTag");
+
+    assert_eq!(root.rotate_with_rng(&mut rng).unwrap(), 2);
+    let v2_tag = root.derive(b"LinkageTag");
+    assert_ne!(v1_tag, v2_tag);
+    assert_eq!(root.derive_path(1, &[b"LinkageTag"]).unwrap(), v1_tag);
+    assert!(root.retire(2).is_err());
+    drop(root);
+
+    let mut root = SealedRoot::open(&path, KEK).unwrap();
+    assert_eq!(root.current_version(), 2);
+    assert_eq!(root.versions(), vec![1, 2]);
+    assert_eq!(root.derive(b"LinkageTag"), v2_tag);
+    root.retire(1).unwrap();
+    drop(root);
+
+    let root = SealedRoot::open(&path, KEK).unwrap();
+    assert_eq!(root.versions(), vec![2]);
+    assert_eq!(root.derive_path(1, &[b"LinkageTag"]), Err(EntropyError::UnknownRootVersion { version: 1 }));
+    std::fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,151 @@
+// Bitstring status lists: encoding, signed lists and index allocation
+// `cargo test --test status_list`
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use multipass::deterministic::seeded_rng;
+use multipass::miner::MinerEngine;
+use multipass::status_list::{
+    check_credential_status, encode_status_index, verify_status_list, MAX_STATUS_LIST_BYTES, MIN_STATUS_LIST_BITS,
+};
+use multipass::*;
+use std::collections::HashSet;
+use std::io::Write;
+
+const LIST_ID: &str = "https://example.com/status/1";
+
+fn signed(list: &StatusList) -> (BbsPublicKey, StatusListCredential) {
+    let mut rng = seeded_rng(b"status-list");
+    let sk = BbsSecretKey::generate_with_rng(&mut rng).unwrap();
+    let pk = BbsPublicKey::parse(&sk.public_key(4).to_bytes()).unwrap();
+    let credential = StatusListCredential::sign_with_rng(&sk, &pk, LIST_ID, "revocation", list, 1_700_000_000, &mut rng).unwrap();
+    (pk, credential)
+}
+
+#[test]
+fn encode_decode_round_trip() {
+    let mut list = StatusList::new(0).unwrap();
+    assert_eq!(list.len(), MIN_STATUS_LIST_BITS);
+    for index in [1, 42, 4_096, MIN_STATUS_LIST_BITS - 2] {
+        list.set(index, true).unwrap();
+    }
+    let encoded = list.encode().unwrap();
+    assert!(encoded.starts_with('u'));
+    assert_eq!(StatusList::decode(&encoded).unwrap(), list);
+}
+
+#[test]
+fn set_and_get_first_and_last_bits() {
+    let mut list = StatusList::new(0).unwrap();
+    let last = list.len() - 1;
+    for index in [0, 7, last] {
+        assert_eq!(list.get(index), Some(false));
+        list.set(index, true).unwrap();
+        assert_eq!(list.get(index), Some(true));
+    }
+    // Index 0 is the most significant bit of the first byte
+    assert_eq!(list.as_bytes()[0], 0x81);
+    assert_eq!(*list.as_bytes().last().unwrap(), 0x01);
+    assert_eq!(list.get(1), Some(false));
+    assert_eq!(list.get(8), Some(false));
+
+    list.set(7, false).unwrap();
+    assert_eq!(list.as_bytes()[0], 0x80);
+
+    assert_eq!(list.get(last + 1), None);
+    assert_eq!(
+        list.set(last + 1, true),
+        Err(VerifyError::StatusIndexOutOfRange { index: last + 1, length: last + 1 })
+    );
+}
+
+#[test]
+fn list_length_is_bounded() {
+    assert_eq!(StatusList::new(MIN_STATUS_LIST_BITS + 1).unwrap().len(), MIN_STATUS_LIST_BITS + 8);
+    let too_long = MAX_STATUS_LIST_BYTES * 8 + 1;
+    assert_eq!(StatusList::new(too_long), Err(VerifyError::StatusListLength { length: too_long }));
+    assert_eq!(
+        StatusList::from_bytes(vec![0; 16]),
+        Err(VerifyError::StatusListLength { length: 128 })
+    );
+}
+
+#[test]
+fn gzip_bomb_is_rejected() {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(&vec![0u8; MAX_STATUS_LIST_BYTES as usize + 4_096]).unwrap();
+    let compressed = encoder.finish().unwrap();
+    let encoded = format!("u{}", URL_SAFE_NO_PAD.encode(compressed));
+    assert!(encoded.len() < 64 * 1024);
+
+    // The bounded read stops one byte past the limit
+    assert_eq!(
+        StatusList::decode(&encoded),
+        Err(VerifyError::StatusListLength { length: (MAX_STATUS_LIST_BYTES + 1) * 8 })
+    );
+}
+
+#[test]
+fn check_credential_status_reads_the_signed_list() {
+    let mut list = StatusList::new(0).unwrap();
+    list.set(5, true).unwrap();
+    let (pk, credential) = signed(&list);
+    let pk_bytes = pk.to_bytes();
+
+    assert!(check_credential_status(pk_bytes.clone(), credential.clone(), encode_status_index(5)).unwrap());
+    assert!(!check_credential_status(pk_bytes.clone(), credential.clone(), encode_status_index(6)).unwrap());
+    assert_eq!(
+        check_credential_status(pk_bytes, credential, encode_status_index(list.len())),
+        Err(VerifyError::StatusIndexOutOfRange { index: list.len(), length: list.len() })
+    );
+}
+
+#[test]
+fn tampered_signed_list_is_rejected() {
+    let mut list = StatusList::new(0).unwrap();
+    list.set(5, true).unwrap();
+    let (pk, credential) = signed(&list);
+    let pk_bytes = pk.to_bytes();
+
+    // Revocation of index 5 undone without re-signing
+    let mut reinstated = credential.clone();
+    reinstated.encoded_list = StatusList::new(0).unwrap().encode().unwrap();
+    assert!(!verify_status_list(pk_bytes.clone(), reinstated.clone()).unwrap());
+    assert_eq!(
+        check_credential_status(pk_bytes.clone(), reinstated, encode_status_index(5)),
+        Err(VerifyError::InvalidSignature)
+    );
+
+    let mut backdated = credential.clone();
+    backdated.valid_from -= 1;
+    assert!(check_credential_status(pk_bytes.clone(), backdated, encode_status_index(5)).is_err());
+
+    let mut relabelled = credential;
+    relabelled.id = "https://example.com/status/2".to_string();
+    assert!(check_credential_status(pk_bytes, relabelled, encode_status_index(5)).is_err());
+}
+
+#[test]
+fn concurrent_allocations_are_unique() {
+    let path = std::env::temp_dir().join(format!("multipass-status-list-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+    let engine = MinerEngine::new(path.to_str().unwrap(), 1.0).unwrap();
+    assert!(engine.create_status_list(LIST_ID, 0).unwrap());
+    assert!(!engine.create_status_list(LIST_ID, 0).unwrap());
+
+    let indexes: Vec<u64> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..8)
+            .map(|_| scope.spawn(|| (0..64).map(|_| engine.allocate_status_index(LIST_ID).unwrap()).collect::<Vec<_>>()))
+            .collect();
+        workers.into_iter().flat_map(|w| w.join().unwrap()).collect()
+    });
+    let unique: HashSet<u64> = indexes.iter().copied().collect();
+    assert_eq!(unique.len(), indexes.len());
+    assert_eq!(unique, (0..8 * 64).collect());
+    assert_eq!(engine.allocate_status_index(LIST_ID).unwrap(), 8 * 64);
+
+    drop(engine);
+    let _ = std::fs::remove_dir_all(&path);
+}
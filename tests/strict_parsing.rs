@@ -0,0 +1,306 @@
+// Typed BBS+ objects: byte round-trips, sign -> verify, and every rejection
+// of BbsPublicKey::parse_strict and BbsProof::parse
+
+use multipass::deterministic::seeded_rng;
+use multipass::*;
+use std::sync::Arc;
+
+fn key_bytes(message_count: u32) -> Vec<u8> {
+    let sk = BbsSecretKey::generate_with_rng(&mut seeded_rng(b"strict-parsing")).unwrap();
+    sk.public_key(message_count).to_bytes()
+}
+
+fn key_parse_field(bytes: &[u8]) -> String {
+    match BbsPublicKey::parse(bytes) {
+        Err(VerifyError::KeyParse { field, .. }) => field,
+        other => panic!("expected KeyParse, got {:?}", other.err()),
+    }
+}
+
+/// Compressed encoding of a point on E(Fp) outside the prime-order subgroup
+fn g1_outside_subgroup() -> [u8; 48] {
+    (1u8..)
+        .map(|x| {
+            let mut bytes = [0u8; 48];
+            bytes[0] = 0x80;
+            bytes[47] = x;
+            bytes
+        })
+        .find(|bytes| {
+            G1Affine::from_compressed_unchecked(bytes)
+                .into_option()
+                .is_some_and(|p| !bool::from(p.is_torsion_free()))
+        })
+        .unwrap()
+}
+
+/// Compressed encoding of a point on E'(Fp2) outside the prime-order subgroup
+fn g2_outside_subgroup() -> [u8; 96] {
+    (1u8..)
+        .map(|x| {
+            let mut bytes = [0u8; 96];
+            bytes[0] = 0x80;
+            bytes[95] = x;
+            bytes
+        })
+        .find(|bytes| {
+            G2Affine::from_compressed_unchecked(bytes)
+                .into_option()
+                .is_some_and(|p| !bool::from(p.is_torsion_free()))
+        })
+        .unwrap()
+}
+
+/// A compressed x coordinate with no point on the curve
+fn g1_not_on_curve() -> [u8; 48] {
+    (1u8..)
+        .map(|x| {
+            let mut bytes = [0u8; 48];
+            bytes[0] = 0x80;
+            bytes[47] = x;
+            bytes
+        })
+        .find(|bytes| G1Affine::from_compressed_unchecked(bytes).is_none().into())
+        .unwrap()
+}
+
+fn set_generator(key: &mut [u8], index: usize, point: &[u8; 48]) {
+    key[96 + 48 * index..96 + 48 * (index + 1)].copy_from_slice(point);
+}
+
+// ----------------------------------------------------------------------------
+// Typed objects
+// ----------------------------------------------------------------------------
+
+#[test]
+fn typed_objects_round_trip_through_bytes() {
+    let mut rng = seeded_rng(b"typed-objects");
+    let sk = BbsSecretKey::generate_with_rng(&mut rng).unwrap();
+    let sk_bytes = sk.to_bytes();
+    assert_eq!(BbsSecretKey::from_bytes(sk_bytes.clone()).unwrap().to_bytes(), sk_bytes);
+
+    let pk = sk.public_key(3);
+    let pk_bytes = pk.to_bytes();
+    assert_eq!(pk_bytes.len(), 96 + 48 * 4);
+    assert_eq!(BbsPublicKey::from_bytes(pk_bytes.clone()).unwrap().to_bytes(), pk_bytes);
+
+    let messages = vec![b"a".to_vec(), b"b".to_vec(), generate_nym_secret_with_rng(&mut rng).unwrap()];
+    let signature = sk.sign_messages_with_rng(&pk, &messages, &mut rng).unwrap();
+    let sig_bytes = signature.to_bytes();
+    assert_eq!(sig_bytes.len(), 112);
+    assert_eq!(BbsSignature::from_bytes(sig_bytes.clone()).unwrap().to_bytes(), sig_bytes);
+
+    let proof = signature
+        .prove_with_rng(&pk, &messages, &[1], Some(b"nonce"), b"site", None, 0, None, &mut rng)
+        .unwrap();
+    let proof_bytes = proof.to_bytes();
+    let parsed = BbsProof::from_bytes(proof_bytes.clone()).unwrap();
+    assert_eq!(parsed.to_bytes(), proof_bytes);
+    assert_eq!(parsed.pseudonym(), proof.pseudonym());
+}
+
+#[test]
+fn typed_sign_then_verify() {
+    let mut rng = seeded_rng(b"typed-objects");
+    let sk = Arc::new(BbsSecretKey::generate_with_rng(&mut rng).unwrap());
+    let pk = sk.public_key(3);
+    let nym_secret = generate_nym_secret_with_rng(&mut rng).unwrap();
+    let messages = vec![b"a".to_vec(), b"b".to_vec(), nym_secret];
+
+    let signature = sk.sign(pk.clone(), messages.clone()).unwrap();
+    assert!(pk.verify(signature.clone(), messages.clone()).unwrap());
+    let mut altered = messages.clone();
+    altered[0] = b"z".to_vec();
+    assert!(!pk.verify(signature.clone(), altered).unwrap());
+    // Another issuer's key
+    let other = BbsSecretKey::generate_with_rng(&mut rng).unwrap().public_key(3);
+    assert!(!other.verify(signature.clone(), messages.clone()).unwrap());
+
+    // The byte API and the typed API produce and accept the same encodings
+    let signed_bytes = sign_with_rng(sk.to_bytes(), pk.to_bytes(), messages.clone(), &mut rng).unwrap();
+    assert!(pk.verify(BbsSignature::from_bytes(signed_bytes).unwrap(), messages.clone()).unwrap());
+    assert!(verify_signature_safe(pk.to_bytes(), signature.to_bytes(), messages.clone()).unwrap());
+
+    let proof = signature
+        .create_proof(pk.clone(), messages.clone(), vec![0], Some(b"nonce".to_vec()), b"site".to_vec(), None, 0, None)
+        .unwrap();
+    assert!(pk
+        .verify_proof(proof, 3, vec![0], vec![messages[0].clone()], b"nonce".to_vec(), b"site".to_vec(), None, 0, None)
+        .unwrap());
+}
+
+#[test]
+fn well_formed_key_parses() {
+    let key = key_bytes(3);
+    assert_eq!(BbsPublicKey::parse_strict(&key).unwrap().message_count(), 3);
+}
+
+#[test]
+fn truncated_key_is_rejected() {
+    let key = key_bytes(3);
+    assert!(matches!(BbsPublicKey::parse_strict(&key[..96 + 47]), Err(KeyError::Truncated)));
+    assert_eq!(key_parse_field(&key[..96]), "length");
+}
+
+#[test]
+fn trailing_bytes_are_rejected() {
+    let mut key = key_bytes(3);
+    key.push(0);
+    assert!(matches!(BbsPublicKey::parse_strict(&key), Err(KeyError::TrailingBytes)));
+    assert_eq!(key_parse_field(&key), "length");
+}
+
+#[test]
+fn identity_w_is_rejected() {
+    let mut key = key_bytes(3);
+    key[..96].copy_from_slice(&G2Affine::identity().to_compressed());
+    assert!(matches!(BbsPublicKey::parse_strict(&key), Err(KeyError::IdentityW)));
+    assert_eq!(key_parse_field(&key), "w");
+}
+
+#[test]
+fn w_outside_subgroup_is_rejected() {
+    let mut key = key_bytes(3);
+    key[..96].copy_from_slice(&g2_outside_subgroup());
+    assert!(matches!(BbsPublicKey::parse_strict(&key), Err(KeyError::WNotInSubgroup)));
+    assert_eq!(key_parse_field(&key), "w");
+}
+
+#[test]
+fn invalid_w_encoding_is_rejected() {
+    let mut key = key_bytes(3);
+    // Compression flag cleared
+    key[0] &= 0x7f;
+    assert!(matches!(BbsPublicKey::parse_strict(&key), Err(KeyError::InvalidW)));
+}
+
+#[test]
+fn identity_generator_is_rejected() {
+    let mut key = key_bytes(3);
+    set_generator(&mut key, 2, &G1Affine::identity().to_compressed());
+    assert!(matches!(BbsPublicKey::parse_strict(&key), Err(KeyError::IdentityGenerator { index: 2 })));
+    assert_eq!(key_parse_field(&key), "h2");
+}
+
+#[test]
+fn generator_outside_subgroup_is_rejected() {
+    let mut key = key_bytes(3);
+    set_generator(&mut key, 1, &g1_outside_subgroup());
+    assert!(matches!(BbsPublicKey::parse_strict(&key), Err(KeyError::GeneratorNotInSubgroup { index: 1 })));
+    assert_eq!(key_parse_field(&key), "h1");
+}
+
+#[test]
+fn generator_off_the_curve_is_rejected() {
+    let mut key = key_bytes(3);
+    set_generator(&mut key, 3, &g1_not_on_curve());
+    assert!(matches!(BbsPublicKey::parse_strict(&key), Err(KeyError::InvalidGenerator { index: 3 })));
+    assert_eq!(key_parse_field(&key), "h3");
+}
+
+#[test]
+fn duplicate_generator_is_rejected() {
+    let mut key = key_bytes(3);
+    let h0: [u8; 48] = key[96..144].try_into().unwrap();
+    set_generator(&mut key, 3, &h0);
+    assert!(matches!(
+        BbsPublicKey::parse_strict(&key),
+        Err(KeyError::DuplicateGenerator { index: 3, first: 0 })
+    ));
+    assert_eq!(key_parse_field(&key), "h3");
+}
+
+#[test]
+fn too_many_messages_is_rejected() {
+    // The count is checked before any generator is decoded
+    let mut key = key_bytes(3);
+    key.resize(96 + 48 * (bbs::MAX_MESSAGE_COUNT as usize + 2), 0);
+    assert!(matches!(BbsPublicKey::parse_strict(&key), Err(KeyError::TooManyMessages { .. })));
+    assert_eq!(key_parse_field(&key), "length");
+}
+
+// ----------------------------------------------------------------------------
+// Proofs
+// ----------------------------------------------------------------------------
+
+const A_PRIME: usize = 1;
+const CHALLENGE: usize = 1 + 48 * 3 + 32 * 4;
+const TAG: usize = 1 + 48 * 3 + 32 * 5;
+const FLAGS: usize = TAG + 48;
+const RESPONSE_COUNT: usize = FLAGS + 1;
+
+fn proof_bytes() -> Vec<u8> {
+    let mut rng = seeded_rng(b"strict-parsing");
+    let sk = BbsSecretKey::generate_with_rng(&mut rng).unwrap();
+    let pk = sk.public_key(3);
+    let messages = vec![b"a".to_vec(), b"b".to_vec(), generate_nym_secret_with_rng(&mut rng).unwrap()];
+    let signature = sk.sign_messages_with_rng(&pk, &messages, &mut rng).unwrap();
+    signature
+        .prove_with_rng(&pk, &messages, &[0], Some(b"nonce"), b"site", None, 0, None, &mut rng)
+        .unwrap()
+        .to_bytes()
+}
+
+fn proof_structure_field(bytes: &[u8]) -> String {
+    match BbsProof::parse(bytes) {
+        Err(VerifyError::ProofStructure { field }) => field,
+        other => panic!("expected ProofStructure, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn truncated_proof_is_rejected() {
+    let proof = proof_bytes();
+    assert_eq!(proof_structure_field(&[]), "version");
+    assert_eq!(proof_structure_field(&proof[..RESPONSE_COUNT]), "length");
+    assert_eq!(proof_structure_field(&proof[..proof.len() - 1]), "m_hat");
+}
+
+#[test]
+fn trailing_proof_bytes_are_rejected() {
+    let mut proof = proof_bytes();
+    proof.push(0);
+    assert_eq!(proof_structure_field(&proof), "range_proofs");
+}
+
+#[test]
+fn proof_point_outside_subgroup_is_rejected() {
+    let mut proof = proof_bytes();
+    proof[A_PRIME..A_PRIME + 48].copy_from_slice(&g1_outside_subgroup());
+    assert_eq!(proof_structure_field(&proof), "a_prime");
+}
+
+#[test]
+fn non_canonical_scalar_is_rejected() {
+    let mut proof = proof_bytes();
+    proof[CHALLENGE..CHALLENGE + 32].copy_from_slice(&[0xff; 32]);
+    assert_eq!(proof_structure_field(&proof), "challenge");
+}
+
+#[test]
+fn unknown_flags_are_rejected() {
+    let mut proof = proof_bytes();
+    proof[FLAGS] = 0x80;
+    assert_eq!(proof_structure_field(&proof), "flags");
+}
+
+#[test]
+fn oversized_response_count_is_rejected() {
+    let mut proof = proof_bytes();
+    proof[RESPONSE_COUNT..RESPONSE_COUNT + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(proof_structure_field(&proof), "m_hat");
+}
+
+#[test]
+fn identity_pseudonym_is_rejected() {
+    let mut rng = seeded_rng(b"strict-parsing");
+    let sk = BbsSecretKey::generate_with_rng(&mut rng).unwrap();
+    let pk = sk.public_key(3);
+    let mut proof = proof_bytes();
+    proof[TAG..TAG + 48].copy_from_slice(&G1Affine::identity().to_compressed());
+    let proof = BbsProof::parse(&proof).unwrap();
+    let err = pk
+        .check_proof_with_predicates(&proof, 3, &[0], &[b"a".to_vec()], &[], b"nonce", b"site", None, 0, None)
+        .unwrap_err();
+    assert_eq!(err, VerifyError::ProofStructure { field: "tag".to_string() });
+}
@@ -0,0 +1,153 @@
+// Verdict codes of failed checks, and the bool verifiers built on them
+// `cargo test --test verdict`
+
+mod common;
+
+use common::*;
+use multipass::deterministic::{create_proof_seeded, generate_secret_key_seeded, seeded_rng};
+use multipass::*;
+
+const NOW: u64 = 1_800_000_000;
+
+/// A credential and one proof over it
+struct Proven {
+    public_key: Vec<u8>,
+    messages: Vec<Vec<u8>>,
+    proof: Vec<u8>,
+}
+
+/// Proof over name || country || nym secret revealing the country
+fn credential() -> Proven {
+    let Credential { public_key, signature, messages } =
+        Issuer::new("issuer", 3).issue(b"alice", vec![b"alice".to_vec(), b"NL".to_vec()]);
+    let proof = create_proof_seeded(
+        b"prove".to_vec(),
+        public_key.clone(),
+        signature,
+        messages.clone(),
+        vec![1],
+        Some(NONCE.to_vec()),
+        SITE.to_vec(),
+        None,
+        0,
+        None,
+    )
+    .unwrap();
+    Proven { public_key, messages, proof }
+}
+
+impl Proven {
+    fn verdict(&self, proof: &[u8], revealed_indices: &[u32]) -> Verdict {
+        verify_proof_verdict(
+            self.public_key.clone(),
+            proof.to_vec(),
+            3,
+            revealed_indices.to_vec(),
+            revealed_indices.iter().map(|&i| self.messages.get(i as usize).cloned().unwrap_or_default()).collect(),
+            NONCE.to_vec(),
+            SITE.to_vec(),
+            None,
+            0,
+            None,
+        )
+    }
+
+    fn verify(&self, proof: &[u8], revealed_indices: &[u32]) -> Result<bool, VerifyError> {
+        verify_proof_safe(
+            self.public_key.clone(),
+            proof.to_vec(),
+            3,
+            revealed_indices.to_vec(),
+            revealed_indices.iter().map(|&i| self.messages.get(i as usize).cloned().unwrap_or_default()).collect(),
+            NONCE.to_vec(),
+            SITE.to_vec(),
+            None,
+            0,
+            None,
+        )
+    }
+}
+
+fn delegation(expiration: u64) -> (Vec<u8>, DelegationToken, Vec<u8>) {
+    let secret_key = generate_secret_key_seeded(b"anchor".to_vec()).unwrap();
+    let public_key = get_public_key(secret_key.clone(), 1).unwrap();
+    let token = DelegationToken {
+        anchor_id: b"anchor-1".to_vec(),
+        mobile_key: vec![7; 32],
+        expiration,
+        tier: 1,
+        scope_mask: 0b101,
+        max_passages: 10,
+    };
+    let signature =
+        sign_delegation_with_rng(secret_key, public_key.clone(), token.clone(), &mut seeded_rng(b"delegate")).unwrap();
+    (public_key, token, signature)
+}
+
+#[test]
+fn valid_proof_verdict_is_ok() {
+    let credential = credential();
+    let verdict = credential.verdict(&credential.proof, &[1]);
+    assert!(verdict.valid);
+    assert_eq!(verdict.code, "ok");
+    assert!(verdict.detail.is_empty());
+    assert_eq!(credential.verify(&credential.proof, &[1]), Ok(true));
+}
+
+#[test]
+fn tampered_proof_is_a_challenge_mismatch() {
+    let credential = credential();
+    let mut tampered = credential.proof.clone();
+    tampered[CHALLENGE_OFFSET + 31] ^= 1;
+    let verdict = credential.verdict(&tampered, &[1]);
+    assert!(!verdict.valid);
+    assert_eq!(verdict.code, "challenge_mismatch");
+    assert_eq!(verdict.detail, format!("{:?}", VerifyError::ChallengeMismatch));
+    assert_eq!(credential.verify(&tampered, &[1]), Ok(false));
+
+    // A disclosed message changed after proving
+    let mut messages = credential.messages.clone();
+    messages[1] = b"DE".to_vec();
+    let other = Proven { messages, ..credential };
+    assert_eq!(other.verdict(&other.proof, &[1]).code, "challenge_mismatch");
+    assert_eq!(other.verify(&other.proof, &[1]), Ok(false));
+}
+
+#[test]
+fn out_of_range_index_is_reported() {
+    let credential = credential();
+    let verdict = credential.verdict(&credential.proof, &[3]);
+    assert!(!verdict.valid);
+    assert_eq!(verdict.code, "revealed_index_out_of_range");
+    // A malformed statement is an error, not an invalid proof
+    assert_eq!(
+        credential.verify(&credential.proof, &[3]),
+        Err(VerifyError::RevealedIndexOutOfRange { index: 3, message_count: 3 })
+    );
+}
+
+#[test]
+fn expired_delegation_is_reported() {
+    let (public_key, token, signature) = delegation(NOW);
+    let pk = BbsPublicKey::parse(&public_key).unwrap();
+    let sig = BbsSignature::parse(&signature).unwrap();
+    token.check(&pk, &sig, &FixedClock(NOW - 1)).unwrap();
+    assert_eq!(
+        token.check(&pk, &sig, &FixedClock(NOW)),
+        Err(VerifyError::TokenExpired { expiration: NOW, now: NOW })
+    );
+
+    // Expired by the wall clock
+    let (public_key, token, signature) = delegation(1);
+    let verdict = verify_delegation_verdict(public_key.clone(), token.clone(), signature.clone());
+    assert!(!verdict.valid);
+    assert_eq!(verdict.code, "token_expired");
+    assert!(verify_delegation_signature(public_key, token, signature).unwrap());
+
+    // A token altered after signing fails the pairing check
+    let (public_key, mut token, signature) = delegation(u64::MAX);
+    assert_eq!(verify_delegation_verdict(public_key.clone(), token.clone(), signature.clone()).code, "ok");
+    token.max_passages += 1;
+    assert_eq!(verify_delegation_verdict(public_key.clone(), token.clone(), signature.clone()).code, "pairing_failure");
+    assert_eq!(verify_delegation_signature(public_key, token, signature), Ok(false));
+}